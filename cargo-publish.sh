@@ -2,6 +2,7 @@
 
 set -euo pipefail
 
+cargo publish -p wormhole-io-derive $@
 cargo publish -p wormhole-io $@
 cargo publish -p wormhole-raw-vaas --features ruint $@
 cargo publish -p wormhole-solana-consts --features mainnet $@
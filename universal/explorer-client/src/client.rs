@@ -7,8 +7,8 @@ use crate::{
     ApiCall, Result, VaaRequest,
 };
 
-use alloy_primitives::FixedBytes;
 use reqwest::Url;
+use wormhole_vaas::FixedBytes;
 
 /// API client
 #[derive(Debug, Clone)]
@@ -34,6 +34,31 @@ pub(crate) mod base64 {
     }
 }
 
+pub(crate) mod uint {
+    use serde::{Deserialize, Serialize};
+    use serde::{Deserializer, Serializer};
+    use wormhole_vaas::U256;
+
+    pub fn serialize<S: Serializer>(v: &U256, s: S) -> Result<S::Ok, S::Error> {
+        String::serialize(&v.to_string(), s)
+    }
+
+    /// Accepts decimal or `0x`-prefixed hex strings, and plain numbers.
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<U256, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum StringOrNumber {
+            String(String),
+            Number(u64),
+        }
+
+        match StringOrNumber::deserialize(d)? {
+            StringOrNumber::String(s) => s.parse().map_err(serde::de::Error::custom),
+            StringOrNumber::Number(n) => Ok(U256::from(n)),
+        }
+    }
+}
+
 pub(crate) mod one_or_many {
     use std::marker::PhantomData;
 
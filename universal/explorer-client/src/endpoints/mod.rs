@@ -47,7 +47,7 @@ pub trait ApiCall: Send + Sync + Debug {
 
         // populate span with above
         tracing::Span::current().record("url", url.as_str());
-        tracing::Span::current().record("uuid", &uuid.to_string());
+        tracing::Span::current().record("uuid", uuid.to_string());
 
         tracing::debug!("prepped response");
         let fut = client.get(url.clone()).send();
@@ -1,7 +1,7 @@
 use std::collections::HashMap;
 
-use alloy_primitives::{FixedBytes, U256};
 use reqwest::Url;
+use wormhole_vaas::{FixedBytes, U256};
 
 use crate::ApiCall;
 
@@ -45,6 +45,7 @@ pub struct GlobalTx {
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DestinationTx {
+    #[serde(with = "crate::common::uint")]
     pub block_number: U256,
     pub chain_id: u16,
     pub from: String,
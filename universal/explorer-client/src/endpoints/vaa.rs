@@ -1,6 +1,5 @@
-use alloy_primitives::FixedBytes;
 use reqwest::Url;
-use wormhole_vaas::{Readable, Vaa};
+use wormhole_vaas::{FixedBytes, Readable, Vaa};
 
 use crate::{ApiCall, Pagination, Result};
 
@@ -0,0 +1,128 @@
+use syn::{spanned::Spanned, Attribute, Expr, ExprArray, Lit, LitInt, Type};
+
+/// Container attributes, e.g. `#[payload(type = 1)]`.
+#[derive(Default)]
+pub(crate) struct ContainerAttrs {
+    pub type_prefix: Option<TypePrefix>,
+}
+
+/// The type prefix of a payload, given as an integer literal (one byte), an array of bytes or a
+/// byte string literal.
+pub(crate) struct TypePrefix {
+    pub expr: proc_macro2::TokenStream,
+    pub len: usize,
+}
+
+/// Field attributes, e.g. `#[payload(len = u16)]` or `#[payload(pad = 2)]`.
+#[derive(Default)]
+pub(crate) struct FieldAttrs {
+    /// Encode the field as bytes prefixed with a length of this type.
+    pub len: Option<Type>,
+
+    /// Number of zero bytes preceding the field.
+    pub pad: Option<usize>,
+}
+
+impl ContainerAttrs {
+    pub fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut out = Self::default();
+
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("payload")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("type") {
+                    let expr: Expr = meta.value()?.parse()?;
+                    out.type_prefix = Some(TypePrefix::parse(expr)?);
+                    Ok(())
+                } else {
+                    Err(meta.error("unsupported payload attribute"))
+                }
+            })?;
+        }
+
+        Ok(out)
+    }
+}
+
+impl TypePrefix {
+    fn parse(expr: Expr) -> syn::Result<Self> {
+        match &expr {
+            Expr::Lit(lit) => match &lit.lit {
+                Lit::Int(int) => {
+                    let byte = int.base10_parse::<u8>()?;
+                    Ok(Self {
+                        expr: quote::quote!([#byte]),
+                        len: 1,
+                    })
+                }
+                Lit::ByteStr(bytes) => {
+                    let bytes = bytes.value();
+                    Ok(Self {
+                        expr: quote::quote!([#(#bytes),*]),
+                        len: bytes.len(),
+                    })
+                }
+                _ => Err(syn::Error::new(
+                    expr.span(),
+                    "expected an integer, byte array or byte string",
+                )),
+            },
+            Expr::Array(ExprArray { elems, .. }) => Ok(Self {
+                expr: quote::quote!(#expr),
+                len: elems.len(),
+            }),
+            _ => Err(syn::Error::new(
+                expr.span(),
+                "expected an integer, byte array or byte string",
+            )),
+        }
+    }
+}
+
+impl FieldAttrs {
+    pub fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut out = Self::default();
+
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("payload")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("len") {
+                    out.len = Some(meta.value()?.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("pad") {
+                    let pad: LitInt = meta.value()?.parse()?;
+                    out.pad = Some(pad.base10_parse()?);
+                    Ok(())
+                } else {
+                    Err(meta.error("unsupported payload attribute"))
+                }
+            })?;
+        }
+
+        Ok(out)
+    }
+}
+
+/// Find the integer type in `#[repr(..)]`, which encodes an enum's discriminant.
+pub(crate) fn repr_type(attrs: &[Attribute]) -> syn::Result<Option<syn::Ident>> {
+    let mut repr = None;
+
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("repr")) {
+        attr.parse_nested_meta(|meta| {
+            if let Some(ident) = meta.path.get_ident() {
+                match ident.to_string().as_str() {
+                    "u8" | "u16" | "u32" | "u64" | "u128" | "i8" | "i16" | "i32" | "i64"
+                    | "i128" => repr = Some(ident.clone()),
+                    _ => {}
+                }
+            }
+
+            // Skip arguments of other representations, e.g. `align(8)`.
+            if meta.input.peek(syn::token::Paren) {
+                let _args;
+                syn::parenthesized!(_args in meta.input);
+            }
+            Ok(())
+        })?;
+    }
+
+    Ok(repr)
+}
@@ -0,0 +1,648 @@
+use proc_macro2::{Literal, Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::{
+    parse_quote, Data, DataEnum, DeriveInput, Fields, GenericParam, Generics, Ident, Index,
+    Lifetime, LifetimeParam, Member,
+};
+
+use crate::attr::{repr_type, ContainerAttrs, FieldAttrs};
+
+/// Byte order of integers and length prefixes.
+#[derive(Clone, Copy)]
+pub(crate) enum Endian {
+    Big,
+    Little,
+}
+
+impl Endian {
+    fn readable(self) -> TokenStream {
+        match self {
+            Endian::Big => quote!(::wormhole_io::Readable),
+            Endian::Little => quote!(::wormhole_io::LeReadable),
+        }
+    }
+
+    fn writeable(self) -> TokenStream {
+        match self {
+            Endian::Big => quote!(::wormhole_io::Writeable),
+            Endian::Little => quote!(::wormhole_io::LeWriteable),
+        }
+    }
+
+    fn write(self, value: TokenStream) -> TokenStream {
+        match self {
+            Endian::Big => quote!(::wormhole_io::Writeable::write(#value, writer)),
+            Endian::Little => quote!(::wormhole_io::LeWriteable::write_le(#value, writer)),
+        }
+    }
+
+    fn written_size(self, value: TokenStream) -> TokenStream {
+        match self {
+            Endian::Big => quote!(::wormhole_io::Writeable::written_size(#value)),
+            Endian::Little => quote!(::wormhole_io::LeWriteable::written_size_le(#value)),
+        }
+    }
+}
+
+/// Whether fields are decoded with `Readable` (or `LeReadable`) from an `io::Read` or with
+/// `ReadableRef` from a borrowed slice.
+#[derive(Clone, Copy)]
+enum Mode {
+    Owned(Endian),
+    Borrowed,
+}
+
+impl Mode {
+    fn trait_name(self) -> &'static str {
+        match self {
+            Mode::Owned(Endian::Big) => "Readable",
+            Mode::Owned(Endian::Little) => "LeReadable",
+            Mode::Borrowed => "ReadableRef",
+        }
+    }
+
+    fn read(self, ty: TokenStream, lifetime: &Lifetime) -> TokenStream {
+        match self {
+            Mode::Owned(Endian::Big) => quote!(<#ty as ::wormhole_io::Readable>::read(reader)),
+            Mode::Owned(Endian::Little) => {
+                quote!(<#ty as ::wormhole_io::LeReadable>::read_le(reader))
+            }
+            Mode::Borrowed => {
+                quote!(<#ty as ::wormhole_io::ReadableRef<#lifetime>>::take_ref(remaining))
+            }
+        }
+    }
+
+    fn finish(self, value: TokenStream) -> TokenStream {
+        match self {
+            Mode::Owned(_) => quote!(Ok(#value)),
+            Mode::Borrowed => quote!(Ok((#value, buf.len() - remaining.len()))),
+        }
+    }
+}
+
+pub(crate) fn readable(input: DeriveInput, endian: Endian) -> syn::Result<TokenStream> {
+    let lifetime = Lifetime::new("'_", Span::call_site());
+    let body = read_body(&input, Mode::Owned(endian), &lifetime)?;
+
+    let name = &input.ident;
+    let readable = endian.readable();
+    let read = match endian {
+        Endian::Big => format_ident!("read"),
+        Endian::Little => format_ident!("read_le"),
+    };
+    let generics = add_trait_bounds(input.generics.clone(), readable.clone());
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics #readable for #name #ty_generics #where_clause {
+            fn #read<R>(reader: &mut R) -> ::wormhole_io::io::Result<Self>
+            where
+                Self: Sized,
+                R: ::wormhole_io::io::Read,
+            {
+                #body
+            }
+        }
+    })
+}
+
+pub(crate) fn readable_ref(input: DeriveInput) -> syn::Result<TokenStream> {
+    // Borrow from the input with the type's first lifetime, or introduce one if it has none.
+    let mut generics = input.generics.clone();
+    let lifetime = match input.generics.lifetimes().next() {
+        Some(param) => param.lifetime.clone(),
+        None => {
+            let lifetime = Lifetime::new("'__a", Span::call_site());
+            generics.params.insert(
+                0,
+                GenericParam::Lifetime(LifetimeParam::new(lifetime.clone())),
+            );
+            lifetime
+        }
+    };
+    let body = read_body(&input, Mode::Borrowed, &lifetime)?;
+
+    let name = &input.ident;
+    let generics = add_trait_bounds(generics, quote!(::wormhole_io::ReadableRef<#lifetime>));
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::wormhole_io::ReadableRef<#lifetime> for #name #ty_generics #where_clause {
+            fn read_ref(buf: &#lifetime [u8]) -> ::wormhole_io::io::Result<(Self, usize)> {
+                let remaining = &mut &buf[..];
+                #body
+            }
+        }
+    })
+}
+
+fn read_body(input: &DeriveInput, mode: Mode, lifetime: &Lifetime) -> syn::Result<TokenStream> {
+    match &input.data {
+        Data::Struct(data) => {
+            let construct = construct(quote!(Self), &data.fields, mode, lifetime)?;
+            let finish = mode.finish(quote!(value));
+            Ok(quote! {
+                let value = #construct;
+                #finish
+            })
+        }
+        Data::Enum(data) => {
+            let repr = enum_repr(input)?;
+            let read_discriminant = mode.read(quote!(#repr), lifetime);
+
+            let discriminants = discriminants(data);
+            let arms = discriminants
+                .iter()
+                .zip(&data.variants)
+                .map(|(discriminant, variant)| {
+                    let ident = &variant.ident;
+                    let construct =
+                        construct(quote!(Self::#ident), &variant.fields, mode, lifetime)?;
+                    let construct = match variant.fields {
+                        Fields::Unit => construct,
+                        _ => in_field(construct, quote!(Self), &ident.to_string()),
+                    };
+                    let finish = mode.finish(quote!(value));
+                    Ok(quote! {
+                        if discriminant == #discriminant {
+                            let value = #construct;
+                            return #finish;
+                        }
+                    })
+                })
+                .collect::<syn::Result<Vec<_>>>()?;
+
+            Ok(quote! {
+                const EXPECTED: &[i128] = &[#(((#discriminants) as #repr) as i128),*];
+                let discriminant = #read_discriminant?;
+                #(#arms)*
+                Err(::wormhole_io::__private::invalid_discriminant(
+                    EXPECTED,
+                    discriminant as i128,
+                ))
+            })
+        }
+        Data::Union(_) => Err(syn::Error::new(
+            Span::call_site(),
+            format!("{} cannot be derived for unions", mode.trait_name()),
+        )),
+    }
+}
+
+pub(crate) fn writeable(input: DeriveInput, endian: Endian) -> syn::Result<TokenStream> {
+    let name = &input.ident;
+    let writeable = endian.writeable();
+    let (write, written_size) = match endian {
+        Endian::Big => (format_ident!("write"), format_ident!("written_size")),
+        Endian::Little => (format_ident!("write_le"), format_ident!("written_size_le")),
+    };
+    let generics = add_trait_bounds(input.generics.clone(), writeable.clone());
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let (write_body, size_body) = match &input.data {
+        Data::Struct(data) => {
+            let members = members(&data.fields);
+            let accesses = members
+                .iter()
+                .map(|member| quote!(&self.#member))
+                .collect::<Vec<_>>();
+
+            let writes = write_fields(&data.fields, &accesses, endian)?;
+            let sizes = field_sizes(&data.fields, &accesses, endian)?;
+
+            (
+                quote! {
+                    #(#writes)*
+                    Ok(())
+                },
+                quote!(0 #(+ #sizes)*),
+            )
+        }
+        Data::Enum(data) => {
+            let repr = enum_repr(&input)?;
+
+            let mut write_arms = Vec::with_capacity(data.variants.len());
+            let mut size_arms = Vec::with_capacity(data.variants.len());
+            for (discriminant, variant) in discriminants(data).into_iter().zip(&data.variants) {
+                let ident = &variant.ident;
+                let bindings = (0..variant.fields.len())
+                    .map(|i| format_ident!("__field_{}", i))
+                    .collect::<Vec<_>>();
+                let accesses = bindings
+                    .iter()
+                    .map(|binding| quote!(#binding))
+                    .collect::<Vec<_>>();
+                let pattern = match &variant.fields {
+                    Fields::Named(fields) => {
+                        let names = fields.named.iter().map(|field| &field.ident);
+                        quote!(Self::#ident { #(#names: #bindings),* })
+                    }
+                    Fields::Unnamed(_) => quote!(Self::#ident(#(#bindings),*)),
+                    Fields::Unit => quote!(Self::#ident),
+                };
+
+                let writes = write_fields(&variant.fields, &accesses, endian)?;
+                let sizes = field_sizes(&variant.fields, &accesses, endian)?;
+                let write_discriminant = endian.write(quote!(&discriminant));
+
+                write_arms.push(quote! {
+                    #pattern => {
+                        let discriminant: #repr = #discriminant;
+                        #write_discriminant?;
+                        #(#writes)*
+                    }
+                });
+                size_arms.push(quote! {
+                    #pattern => ::core::mem::size_of::<#repr>() #(+ #sizes)*
+                });
+            }
+
+            (
+                quote! {
+                    match self {
+                        #(#write_arms)*
+                    }
+                    Ok(())
+                },
+                quote! {
+                    match self {
+                        #(#size_arms,)*
+                    }
+                },
+            )
+        }
+        Data::Union(_) => {
+            let trait_name = match endian {
+                Endian::Big => "Writeable",
+                Endian::Little => "LeWriteable",
+            };
+            return Err(syn::Error::new(
+                Span::call_site(),
+                format!("{trait_name} cannot be derived for unions"),
+            ));
+        }
+    };
+
+    Ok(quote! {
+        impl #impl_generics #writeable for #name #ty_generics #where_clause {
+            fn #write<W>(&self, writer: &mut W) -> ::wormhole_io::io::Result<()>
+            where
+                W: ::wormhole_io::io::Write,
+            {
+                #write_body
+            }
+
+            fn #written_size(&self) -> usize {
+                #size_body
+            }
+        }
+    })
+}
+
+pub(crate) fn type_prefixed_payload(input: DeriveInput) -> syn::Result<TokenStream> {
+    let name = &input.ident;
+    let attrs = ContainerAttrs::parse(&input.attrs)?;
+    let generics = add_trait_bounds(
+        input.generics.clone(),
+        quote! {
+            ::wormhole_io::Readable
+                + ::wormhole_io::Writeable
+                + ::core::clone::Clone
+                + ::core::fmt::Debug
+        },
+    );
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    // Payloads without a type prefix default to `TypePrefixedPayload<1>`, which is what most
+    // generic consumers of payloads expect.
+    let (len, type_prefix) = match attrs.type_prefix {
+        Some(prefix) => {
+            let expr = prefix.expr;
+            (prefix.len, quote!(Some(#expr)))
+        }
+        None => (1, quote!(None)),
+    };
+    let len = Literal::usize_unsuffixed(len);
+
+    Ok(quote! {
+        impl #impl_generics ::wormhole_io::TypePrefixedPayload<#len> for #name #ty_generics #where_clause {
+            const TYPE: Option<[u8; #len]> = #type_prefix;
+        }
+    })
+}
+
+pub(crate) fn layout(input: DeriveInput) -> syn::Result<TokenStream> {
+    let name = &input.ident;
+    let type_name = name.to_string();
+    let generics = add_trait_bounds(input.generics.clone(), quote!(::wormhole_io::Layout));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let fields = layout_fields(&data.fields)?;
+            quote!(::wormhole_io::layout::LayoutItem::structure(#type_name, #fields))
+        }
+        Data::Enum(data) => {
+            let repr = enum_repr(&input)?;
+            let variants = discriminants(data)
+                .into_iter()
+                .zip(&data.variants)
+                .map(|(discriminant, variant)| {
+                    let variant_name = variant.ident.to_string();
+                    let fields = layout_fields(&variant.fields)?;
+                    Ok(quote! {
+                        ::wormhole_io::layout::Variant::new(
+                            #variant_name,
+                            (#discriminant) as i128,
+                            #fields,
+                        )
+                    })
+                })
+                .collect::<syn::Result<Vec<_>>>()?;
+
+            quote! {
+                ::wormhole_io::layout::LayoutItem::enumeration(
+                    #type_name,
+                    ::wormhole_io::layout::IntEncoding::big(::core::mem::size_of::<#repr>()),
+                    [#(#variants),*],
+                )
+            }
+        }
+        Data::Union(_) => {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "Layout cannot be derived for unions",
+            ))
+        }
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::wormhole_io::Layout for #name #ty_generics #where_clause {
+            fn layout() -> ::wormhole_io::layout::LayoutItem {
+                #body
+            }
+        }
+    })
+}
+
+/// Array of `Field`s, typed so that it is not ambiguous when empty.
+fn layout_fields(fields: &Fields) -> syn::Result<TokenStream> {
+    let len = fields.len();
+    let fields = fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| {
+            let attrs = FieldAttrs::parse(&field.attrs)?;
+            let name = match &field.ident {
+                Some(ident) => ident.to_string(),
+                None => i.to_string(),
+            };
+            let ty = &field.ty;
+
+            let item = match &attrs.len {
+                Some(len_ty) => quote! {
+                    ::wormhole_io::layout::LayoutItem::LengthPrefixedBytes {
+                        len: ::wormhole_io::layout::IntEncoding::big(
+                            ::core::mem::size_of::<#len_ty>(),
+                        ),
+                    }
+                },
+                None => quote!(<#ty as ::wormhole_io::Layout>::layout()),
+            };
+            let pad = attrs.pad.unwrap_or_default();
+
+            Ok(quote!(::wormhole_io::layout::Field::new(#name, #item).padded(#pad)))
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    Ok(quote! {{
+        let fields: [::wormhole_io::layout::Field; #len] = [#(#fields),*];
+        fields
+    }})
+}
+
+fn add_trait_bounds(mut generics: Generics, bound: TokenStream) -> Generics {
+    let params = generics
+        .params
+        .iter()
+        .filter_map(|param| match param {
+            GenericParam::Type(param) => Some(param.ident.clone()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    let where_clause = generics.make_where_clause();
+    for ident in params {
+        where_clause.predicates.push(parse_quote!(#ident: #bound));
+    }
+    generics
+}
+
+fn enum_repr(input: &DeriveInput) -> syn::Result<Ident> {
+    repr_type(&input.attrs)?.ok_or_else(|| {
+        syn::Error::new(
+            input.ident.span(),
+            "enums must specify an integer representation, e.g. #[repr(u8)]",
+        )
+    })
+}
+
+/// Resolve each variant's discriminant, following Rust's rule that implicit discriminants
+/// increment the previous one.
+fn discriminants(data: &DataEnum) -> Vec<TokenStream> {
+    let mut out = Vec::with_capacity(data.variants.len());
+    let mut prev: Option<TokenStream> = None;
+
+    for variant in &data.variants {
+        let discriminant = match (&variant.discriminant, &prev) {
+            (Some((_, expr)), _) => quote!(#expr),
+            (None, Some(prev)) => quote!((#prev) + 1),
+            (None, None) => quote!(0),
+        };
+        prev = Some(discriminant.clone());
+        out.push(discriminant);
+    }
+
+    out
+}
+
+fn members(fields: &Fields) -> Vec<Member> {
+    fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| match &field.ident {
+            Some(ident) => Member::Named(ident.clone()),
+            None => Member::Unnamed(Index::from(i)),
+        })
+        .collect()
+}
+
+fn construct(
+    path: TokenStream,
+    fields: &Fields,
+    mode: Mode,
+    lifetime: &Lifetime,
+) -> syn::Result<TokenStream> {
+    let reads = fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| {
+            let read = read_field(&field.ty, &FieldAttrs::parse(&field.attrs)?, mode, lifetime)?;
+            let name = match &field.ident {
+                Some(ident) => ident.to_string(),
+                None => i.to_string(),
+            };
+            let ty = &field.ty;
+            Ok(in_field(read, quote!(#ty), &name))
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    Ok(match fields {
+        Fields::Named(fields) => {
+            let names = fields.named.iter().map(|field| &field.ident);
+            quote!(#path { #(#names: #reads),* })
+        }
+        Fields::Unnamed(_) => quote!(#path(#(#reads),*)),
+        Fields::Unit => path,
+    })
+}
+
+fn read_field(
+    ty: &syn::Type,
+    attrs: &FieldAttrs,
+    mode: Mode,
+    lifetime: &Lifetime,
+) -> syn::Result<TokenStream> {
+    let pad = attrs.pad.map(|pad| {
+        let read_pad = mode.read(quote!([u8; #pad]), lifetime);
+        quote! {
+            if #read_pad? != [0u8; #pad] {
+                return Err(::wormhole_io::__private::invalid_padding());
+            }
+        }
+    });
+
+    let value = match &attrs.len {
+        Some(len_ty) => {
+            let read_len = mode.read(quote!(#len_ty), lifetime);
+            let read_bytes = match mode {
+                Mode::Owned(_) => quote! {
+                    let buf = ::wormhole_io::__private::read_bytes(reader, len)?;
+                    <#ty as ::core::convert::From<::wormhole_io::__private::Vec<u8>>>::from(buf)
+                },
+                Mode::Borrowed => quote! {
+                    if remaining.len() < len {
+                        return Err(::wormhole_io::io::Error::new(
+                            ::wormhole_io::io::ErrorKind::UnexpectedEof,
+                            "failed to fill whole buffer",
+                        ));
+                    }
+                    let (bytes, rest) = remaining.split_at(len);
+                    *remaining = rest;
+                    <#ty as ::core::convert::From<&#lifetime [u8]>>::from(bytes)
+                },
+            };
+
+            quote! {{
+                let len = #read_len
+                    .map_err(|err| ::wormhole_io::__private::in_field(err, "len"))?;
+                let len = usize::try_from(len)
+                    .map_err(|_| ::wormhole_io::__private::length_overflow())?;
+                #read_bytes
+            }}
+        }
+        None => {
+            let read = mode.read(quote!(#ty), lifetime);
+            quote!(#read?)
+        }
+    };
+
+    Ok(quote! {{
+        #pad
+        #value
+    }})
+}
+
+/// Evaluate `read` in a closure so that any error it returns is attributed to `field`.
+fn in_field(read: TokenStream, ty: TokenStream, field: &str) -> TokenStream {
+    quote! {
+        match (|| -> ::wormhole_io::io::Result<#ty> { Ok(#read) })() {
+            Ok(value) => value,
+            Err(err) => return Err(::wormhole_io::__private::in_field(err, #field)),
+        }
+    }
+}
+
+fn write_fields(
+    fields: &Fields,
+    accesses: &[TokenStream],
+    endian: Endian,
+) -> syn::Result<Vec<TokenStream>> {
+    fields
+        .iter()
+        .zip(accesses)
+        .map(|(field, access)| {
+            let attrs = FieldAttrs::parse(&field.attrs)?;
+
+            let pad = attrs.pad.map(|pad| {
+                let write_pad = endian.write(quote!(&[0u8; #pad]));
+                quote!(#write_pad?;)
+            });
+
+            let value = match &attrs.len {
+                Some(len_ty) => {
+                    let write_len = endian.write(quote!(&len));
+                    quote! {
+                    let bytes: &[u8] = ::core::convert::AsRef::<[u8]>::as_ref(#access);
+                    let len = <#len_ty as ::core::convert::TryFrom<usize>>::try_from(bytes.len())
+                        .map_err(|_| {
+                            ::wormhole_io::io::Error::new(
+                                ::wormhole_io::io::ErrorKind::InvalidData,
+                                "Length overflow when converting from usize",
+                            )
+                        })?;
+                    #write_len?;
+                    writer.write_all(bytes)?;
+                    }
+                }
+                None => {
+                    let write = endian.write(access.clone());
+                    quote!(#write?;)
+                }
+            };
+
+            Ok(quote! {
+                #pad
+                #value
+            })
+        })
+        .collect()
+}
+
+fn field_sizes(
+    fields: &Fields,
+    accesses: &[TokenStream],
+    endian: Endian,
+) -> syn::Result<Vec<TokenStream>> {
+    fields
+        .iter()
+        .zip(accesses)
+        .map(|(field, access)| {
+            let attrs = FieldAttrs::parse(&field.attrs)?;
+            let pad = attrs.pad.unwrap_or_default();
+
+            Ok(match &attrs.len {
+                Some(len_ty) => quote! {
+                    #pad + ::core::mem::size_of::<#len_ty>()
+                        + ::core::convert::AsRef::<[u8]>::as_ref(#access).len()
+                },
+                None => {
+                    let size = endian.written_size(access.clone());
+                    quote!(#pad + #size)
+                }
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,91 @@
+//! Derive macros for the [wormhole-io] traits `Readable`, `ReadableRef`, `Writeable`,
+//! `TypePrefixedPayload`, `Layout`, and the little-endian `LeReadable` and `LeWriteable`.
+//!
+//! Fields are encoded in declaration order. Enums must specify an integer representation (e.g.
+//! `#[repr(u8)]`), and each variant is encoded as its discriminant followed by its fields.
+//!
+//! The following attributes are supported:
+//! - `#[payload(type = ..)]` on the container sets `TypePrefixedPayload::TYPE`. The prefix can be
+//!   an integer (one byte), an array of bytes or a byte string (e.g. `b"\x99NTT"`). Without it,
+//!   the payload has no type prefix.
+//! - `#[payload(len = u16)]` on a byte field (e.g. `Vec<u8>`) encodes it with a length prefix of
+//!   the given integer type.
+//! - `#[payload(pad = 2)]` on a field encodes that many zero bytes before it. Decoding fails if
+//!   these bytes are not zero.
+//!
+//! Decoding errors record the name of the field (and enum variant) being read, so that a
+//! `DecodeError` can report a path like `TransferWithMessage.payload.len`.
+//!
+//! `ReadableRef` borrows from the input using the type's first lifetime parameter, so fields like
+//! `&'a [u8; 32]` or `WriteableBytesRef<'a, u16>` are decoded without copying. A length-prefixed
+//! field must implement `From<&'a [u8]>` (e.g. `&'a [u8]` itself).
+//!
+//! `LeReadable` and `LeWriteable` support the same attributes, but encode integers, enum
+//! discriminants and length prefixes in little-endian, e.g. for Solana account layouts.
+//!
+//! `Layout` describes the big-endian encoding, and requires the `alloc` feature of wormhole-io.
+//!
+//! [wormhole-io]: https://docs.rs/wormhole-io
+
+mod attr;
+mod expand;
+
+use expand::Endian;
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, DeriveInput};
+
+#[proc_macro_derive(Readable, attributes(payload))]
+pub fn derive_readable(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand::readable(input, Endian::Big)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+#[proc_macro_derive(ReadableRef, attributes(payload))]
+pub fn derive_readable_ref(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand::readable_ref(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+#[proc_macro_derive(Writeable, attributes(payload))]
+pub fn derive_writeable(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand::writeable(input, Endian::Big)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+#[proc_macro_derive(LeReadable, attributes(payload))]
+pub fn derive_le_readable(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand::readable(input, Endian::Little)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+#[proc_macro_derive(LeWriteable, attributes(payload))]
+pub fn derive_le_writeable(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand::writeable(input, Endian::Little)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+#[proc_macro_derive(TypePrefixedPayload, attributes(payload))]
+pub fn derive_type_prefixed_payload(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand::type_prefixed_payload(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+#[proc_macro_derive(Layout, attributes(payload))]
+pub fn derive_layout(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand::layout(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
@@ -0,0 +1,379 @@
+use hex_literal::hex;
+use wormhole_io::{
+    layout::{self, Field, IntEncoding, LayoutItem, Variant},
+    DecodeError, DecodeErrorKind, Layout, LeReadable, LeWriteable, Readable, ReadableRef,
+    TypePrefixedPayload, Writeable, WriteableBytes, WriteableBytesRef,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Readable, Writeable, TypePrefixedPayload, Layout)]
+#[payload(type = 2)]
+struct GuardianSetUpdate {
+    #[payload(pad = 2)]
+    new_index: u32,
+    guardians: WriteableBytes<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Readable, Writeable, TypePrefixedPayload, Layout)]
+#[payload(type = [0x99, 0x4e, 0x54, 0x54])]
+struct NativeTokenTransfer {
+    amount: u64,
+    #[payload(len = u16)]
+    recipient: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Readable, Writeable, TypePrefixedPayload)]
+#[payload(type = b"\x99NTT")]
+struct NativeTokenTransferByteString(u64);
+
+#[derive(Debug, Clone, PartialEq, Eq, Readable, Writeable, TypePrefixedPayload, Layout)]
+#[repr(u8)]
+enum Decree {
+    Pause = 1,
+    SetFee(u64),
+    Register { chain: u16, emitter: [u8; 32] } = 5,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, ReadableRef)]
+struct TransferRef<'a> {
+    #[payload(pad = 1)]
+    token_address: &'a [u8; 32],
+    recipient: WriteableBytesRef<'a, u16>,
+    #[payload(len = u8)]
+    memo: &'a [u8],
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, ReadableRef)]
+#[repr(u8)]
+enum DecreeRef<'a> {
+    Pause = 1,
+    Register { chain: u16, emitter: &'a [u8; 32] } = 5,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Readable, Writeable)]
+struct Pair<T> {
+    left: T,
+    right: T,
+}
+
+/// Layout of the Core Bridge's posted VAA account.
+#[derive(Debug, Clone, PartialEq, Eq, LeReadable, LeWriteable)]
+struct PostedVaaV1 {
+    discriminator: [u8; 4],
+    consistency_level: u8,
+    timestamp: u32,
+    signature_set: [u8; 32],
+    guardian_set_index: u32,
+    nonce: u32,
+    sequence: u64,
+    emitter_chain: u16,
+    emitter_address: [u8; 32],
+    #[payload(len = u32)]
+    payload: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, LeReadable, LeWriteable)]
+#[repr(u16)]
+enum ProcessingStatus {
+    Unset,
+    Writing { written: u32 } = 0x0100,
+}
+
+#[test]
+fn struct_with_padding() {
+    let update = GuardianSetUpdate {
+        new_index: 4,
+        guardians: WriteableBytes::new(hex!("beefbeef").to_vec()),
+    };
+
+    let encoded = update.to_vec();
+    assert_eq!(encoded, hex!("0200000000000404beefbeef"));
+    assert_eq!(update.payload_written_size(), encoded.len());
+    assert_eq!(GuardianSetUpdate::read_slice(&encoded).unwrap(), update);
+}
+
+#[test]
+fn struct_with_invalid_padding() {
+    let encoded = hex!("0200010000000404beefbeef");
+    let err = GuardianSetUpdate::read_slice(&encoded).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    assert_eq!(
+        err.to_string(),
+        "GuardianSetUpdate.new_index: invalid padding at offset 1"
+    );
+}
+
+#[test]
+fn struct_with_length_prefix() {
+    let transfer = NativeTokenTransfer {
+        amount: 69,
+        recipient: hex!("deadbeef").to_vec(),
+    };
+
+    let encoded = transfer.to_vec();
+    assert_eq!(encoded, hex!("994e545400000000000000450004deadbeef"));
+    assert_eq!(transfer.written_size(), 8 + 2 + 4);
+    assert_eq!(NativeTokenTransfer::read_slice(&encoded).unwrap(), transfer);
+}
+
+#[test]
+fn struct_with_truncated_length_prefix() {
+    let encoded = hex!("994e5454000000000000004500");
+
+    let err = DecodeError::from_io(NativeTokenTransfer::read_slice(&encoded).unwrap_err());
+    assert!(matches!(err.kind(), DecodeErrorKind::UnexpectedEof));
+    assert_eq!(err.offset(), Some(12));
+    assert_eq!(
+        err.path().collect::<Vec<_>>(),
+        ["NativeTokenTransfer", "recipient", "len"]
+    );
+    assert_eq!(
+        err.to_string(),
+        "NativeTokenTransfer.recipient.len: unexpected end of input at offset 12"
+    );
+}
+
+#[test]
+fn byte_string_type_prefix() {
+    assert_eq!(
+        <NativeTokenTransferByteString as TypePrefixedPayload<4>>::TYPE,
+        <NativeTokenTransfer as TypePrefixedPayload<4>>::TYPE,
+    );
+
+    let encoded = NativeTokenTransferByteString(69).to_vec();
+    assert_eq!(encoded, hex!("994e54540000000000000045"));
+}
+
+#[test]
+fn enum_discriminants() {
+    let decrees = [
+        (Decree::Pause, hex!("01").to_vec()),
+        (Decree::SetFee(69), hex!("020000000000000045").to_vec()),
+        (
+            Decree::Register {
+                chain: 2,
+                emitter: [0x45; 32],
+            },
+            hex!("0500024545454545454545454545454545454545454545454545454545454545454545").to_vec(),
+        ),
+    ];
+
+    for (decree, expected) in decrees {
+        assert_eq!(<Decree as TypePrefixedPayload<1>>::TYPE, None);
+
+        let encoded = decree.to_vec();
+        assert_eq!(encoded, expected);
+        assert_eq!(decree.written_size(), expected.len());
+        assert_eq!(Decree::read_slice(&encoded).unwrap(), decree);
+    }
+}
+
+#[test]
+fn enum_invalid_discriminant() {
+    let err = Decree::read(&mut &hex!("03")[..]).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    assert_eq!(
+        err.to_string(),
+        "invalid discriminant: expected one of 1, 2, 5, found 3"
+    );
+
+    let err = DecodeError::from_io(Decree::read_slice(&hex!("0600")).unwrap_err());
+    assert!(matches!(
+        err.kind(),
+        DecodeErrorKind::InvalidDiscriminant {
+            expected: &[1, 2, 5],
+            found: 6
+        }
+    ));
+    assert_eq!(
+        err.to_string(),
+        "Decree: invalid discriminant: expected one of 1, 2, 5, found 6 at offset 0"
+    );
+}
+
+#[test]
+fn borrowed_struct() {
+    let encoded = hex!(
+        "00"
+        "4545454545454545454545454545454545454545454545454545454545454545"
+        "0002beef"
+        "03deadbe"
+        "69"
+    );
+
+    let (transfer, consumed) = TransferRef::read_ref(&encoded).unwrap();
+    assert_eq!(consumed, encoded.len() - 1);
+    assert_eq!(transfer.token_address, &[0x45; 32]);
+    assert_eq!(&*transfer.recipient, hex!("beef"));
+    assert_eq!(transfer.memo, hex!("deadbe"));
+    assert!(std::ptr::eq(transfer.memo.as_ptr(), encoded[38..].as_ptr()));
+
+    let err = TransferRef::read_ref(&encoded[..consumed - 1]).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn borrowed_enum() {
+    let encoded = hex!("0500024545454545454545454545454545454545454545454545454545454545454545");
+    let buf = &mut &encoded[..];
+
+    assert_eq!(
+        DecreeRef::take_ref(buf).unwrap(),
+        DecreeRef::Register {
+            chain: 2,
+            emitter: &[0x45; 32],
+        }
+    );
+    assert!(buf.is_empty());
+
+    assert_eq!(
+        DecreeRef::read_ref(&hex!("01")).unwrap(),
+        (DecreeRef::Pause, 1)
+    );
+
+    let err = DecreeRef::read_ref(&hex!("03")).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    assert_eq!(
+        err.to_string(),
+        "invalid discriminant: expected one of 1, 5, found 3"
+    );
+}
+
+#[test]
+fn generic_struct() {
+    let pair = Pair {
+        left: 1u16,
+        right: 2u16,
+    };
+
+    let mut encoded = Vec::with_capacity(pair.written_size());
+    pair.write(&mut encoded).unwrap();
+    assert_eq!(encoded, hex!("00010002"));
+    assert_eq!(Pair::<u16>::read(&mut encoded.as_slice()).unwrap(), pair);
+}
+
+#[test]
+fn little_endian_struct() {
+    let posted = PostedVaaV1 {
+        discriminator: *b"vaa\x01",
+        consistency_level: 1,
+        timestamp: 0x6543_2100,
+        signature_set: [0x11; 32],
+        guardian_set_index: 4,
+        nonce: 0,
+        sequence: 0x0102,
+        emitter_chain: 2,
+        emitter_address: [0x45; 32],
+        payload: b"hello".to_vec(),
+    };
+    assert_eq!(posted.written_size_le(), 100);
+
+    let mut encoded = Vec::with_capacity(posted.written_size_le());
+    posted.write_le(&mut encoded).unwrap();
+    assert_eq!(encoded[..9], hex!("7661610101 00214365"));
+    assert_eq!(
+        encoded[41..59],
+        hex!("04000000 00000000 0201000000000000 0200")
+    );
+    assert_eq!(encoded[91..], hex!("05000000 68656c6c6f"));
+
+    assert_eq!(
+        PostedVaaV1::read_le(&mut encoded.as_slice()).unwrap(),
+        posted
+    );
+
+    // Truncate the payload.
+    let err = PostedVaaV1::read_le(&mut &encoded[..99]).unwrap_err();
+    assert_eq!(
+        DecodeError::from_io(err).to_string(),
+        "payload: unexpected end of input"
+    );
+}
+
+#[test]
+fn little_endian_enum() {
+    let status = ProcessingStatus::Writing { written: 3 };
+
+    let mut encoded = Vec::with_capacity(status.written_size_le());
+    status.write_le(&mut encoded).unwrap();
+    assert_eq!(encoded, hex!("0001 03000000"));
+    assert_eq!(
+        ProcessingStatus::read_le(&mut encoded.as_slice()).unwrap(),
+        status
+    );
+
+    assert_eq!(
+        ProcessingStatus::read_le(&mut &hex!("0000")[..]).unwrap(),
+        ProcessingStatus::Unset
+    );
+}
+
+#[test]
+fn layout() {
+    let layout = layout::payload_layout::<GuardianSetUpdate, 1>();
+    assert_eq!(layout.size(), None);
+    assert_eq!(
+        layout,
+        LayoutItem::structure(
+            "GuardianSetUpdate",
+            [
+                Field::new("type", LayoutItem::Constant { bytes: vec![2] }),
+                Field::new("new_index", LayoutItem::uint(4)).padded(2),
+                Field::new(
+                    "guardians",
+                    LayoutItem::LengthPrefixedBytes {
+                        len: IntEncoding::big(1)
+                    }
+                ),
+            ]
+        )
+    );
+    assert_eq!(
+        layout.to_json(),
+        concat!(
+            r#"{"binary":"struct","type":"GuardianSetUpdate","layout":["#,
+            r#"{"name":"type","offset":0,"binary":"bytes","size":1,"custom":"0x02"},"#,
+            r#"{"name":"new_index","offset":3,"padding":2,"binary":"uint","size":4,"endianness":"big"},"#,
+            r#"{"name":"guardians","offset":7,"binary":"bytes","lengthSize":1,"lengthEndianness":"big"}"#,
+            r#"]}"#
+        )
+    );
+
+    let LayoutItem::Struct { fields, .. } = NativeTokenTransfer::layout() else {
+        panic!("expected a struct");
+    };
+    assert_eq!(fields[1].offset, Some(8));
+    assert_eq!(
+        fields[1].item,
+        LayoutItem::LengthPrefixedBytes {
+            len: IntEncoding::big(2)
+        }
+    );
+}
+
+#[test]
+fn enum_layout() {
+    assert_eq!(
+        Decree::layout(),
+        LayoutItem::enumeration(
+            "Decree",
+            IntEncoding::big(1),
+            [
+                Variant::new("Pause", 1, []),
+                Variant::new("SetFee", 2, [Field::new("0", LayoutItem::uint(8))]),
+                Variant::new(
+                    "Register",
+                    5,
+                    [
+                        Field::new("chain", LayoutItem::uint(2)),
+                        Field::new("emitter", LayoutItem::bytes(32)),
+                    ]
+                ),
+            ]
+        )
+    );
+
+    let LayoutItem::Enum { variants, .. } = Decree::layout() else {
+        panic!("expected an enum");
+    };
+    assert_eq!(variants[2].fields[1].offset, Some(3));
+}
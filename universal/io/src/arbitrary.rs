@@ -0,0 +1,114 @@
+//! [`arbitrary::Arbitrary`] and [`proptest::arbitrary::Arbitrary`] for the wrapper types.
+//!
+//! Generated lengths always fit in the length prefix `L`, so every generated value can be
+//! encoded. The proptest strategies are built from the `arbitrary` impls with
+//! [`arbitrary_strategy`](crate::testing::arbitrary_strategy).
+
+use alloc::{string::String, vec::Vec};
+
+use arbitrary::{Arbitrary, Unstructured};
+use proptest::strategy::BoxedStrategy;
+
+use crate::{
+    testing, Extensions, FixedString, Readable, Writeable, WriteableBytes, WriteableString,
+    WriteableVec,
+};
+
+/// Largest length that fits in the length prefix `L`.
+fn max_len<L: TryFrom<usize>>() -> usize {
+    [u32::MAX, u16::MAX.into(), u8::MAX.into()]
+        .into_iter()
+        .filter_map(|len| usize::try_from(len).ok())
+        .find(|&len| L::try_from(len).is_ok())
+        .unwrap_or_default()
+}
+
+impl<'a, L> Arbitrary<'a> for WriteableBytes<L>
+where
+    u32: From<L>,
+    L: Sized + Readable + Writeable + TryFrom<usize>,
+{
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        let len = u.arbitrary_len::<u8>()?.min(max_len::<L>());
+        u.bytes(len).map(|bytes| Self::new(bytes.to_vec()))
+    }
+}
+
+impl<'a, L, T> Arbitrary<'a> for WriteableVec<L, T>
+where
+    u32: From<L>,
+    L: Sized + Readable + Writeable + TryFrom<usize>,
+    T: Arbitrary<'a>,
+{
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        let len = u.arbitrary_len::<T>()?.min(max_len::<L>());
+        (0..len)
+            .map(|_| T::arbitrary(u))
+            .collect::<arbitrary::Result<Vec<_>>>()
+            .map(Self::new)
+    }
+}
+
+impl<'a, L> Arbitrary<'a> for WriteableString<L>
+where
+    u32: From<L>,
+    L: Sized + Readable + Writeable + TryFrom<usize>,
+{
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        let s = <&str>::arbitrary(u)?;
+        let mut len = s.len().min(max_len::<L>());
+        while !s.is_char_boundary(len) {
+            len -= 1;
+        }
+        Ok(Self::new(String::from(&s[..len])))
+    }
+}
+
+impl<'a, const N: usize> Arbitrary<'a> for FixedString<N> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        // Any bytes are valid, including invalid UTF-8 and non-zero bytes after a zero.
+        <[u8; N]>::arbitrary(u).map(Self::from)
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        <[u8; N]>::size_hint(depth)
+    }
+}
+
+impl<'a> Arbitrary<'a> for Extensions {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        Vec::arbitrary(u).map(Self::new)
+    }
+}
+
+macro_rules! impl_proptest_arbitrary {
+    ([$($generics:tt)*] $type:ty $(where $($bounds:tt)*)?) => {
+        impl<$($generics)*> proptest::arbitrary::Arbitrary for $type
+        where
+            Self: for<'a> Arbitrary<'a> + core::fmt::Debug + 'static,
+            $($($bounds)*)?
+        {
+            type Parameters = ();
+            type Strategy = BoxedStrategy<Self>;
+
+            fn arbitrary_with(_: ()) -> Self::Strategy {
+                testing::arbitrary_strategy()
+            }
+        }
+    };
+}
+
+impl_proptest_arbitrary!([L] WriteableBytes<L> where
+    u32: From<L>,
+    L: Sized + Readable + Writeable + TryFrom<usize>,
+);
+impl_proptest_arbitrary!([L, T] WriteableVec<L, T> where
+    u32: From<L>,
+    L: Sized + Readable + Writeable + TryFrom<usize>,
+);
+impl_proptest_arbitrary!([L] WriteableString<L> where
+    u32: From<L>,
+    L: Sized + Readable + Writeable + TryFrom<usize>,
+);
+impl_proptest_arbitrary!([const N: usize] FixedString<N>);
+impl_proptest_arbitrary!([] Extensions);
@@ -0,0 +1,248 @@
+//! Decoding and encoding over tokio's [`AsyncRead`] and [`AsyncWrite`].
+//!
+//! Every [`Readable`] type can be read asynchronously. The blocking decoder is replayed over the
+//! bytes received so far, and when it runs out, exactly the bytes it asked for are read from the
+//! stream before trying again. Nothing past the end of the value is consumed, so values can be
+//! read one after another from the same socket.
+//!
+//! Each replay decodes from the start of the value again, and a large byte field is requested
+//! one chunk at a time, so decoding a value of `n` bytes costs `O(n²)`. Values are therefore
+//! limited to [`MAX_ASYNC_READ_SIZE`] bytes, which keeps the cost of a bogus length prefix on an
+//! untrusted stream bounded.
+
+use std::future::Future;
+
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+use crate::{error, io, Readable, Writeable};
+
+/// At most this many bytes are requested from the stream at once.
+const READ_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Largest value, in bytes, that can be read asynchronously. Reading more fails with
+/// [`DecodeErrorKind::LimitExceeded`](crate::DecodeErrorKind::LimitExceeded).
+pub const MAX_ASYNC_READ_SIZE: usize = 4 * 1024 * 1024;
+
+/// Async counterpart to [`Readable`], implemented for every [`Readable`] type.
+pub trait AsyncReadable: Sized {
+    fn read_async<R>(reader: &mut R) -> impl Future<Output = io::Result<Self>> + Send
+    where
+        R: AsyncRead + Unpin + Send;
+}
+
+/// Async counterpart to [`Writeable`], implemented for every [`Writeable`] type. The value is
+/// encoded into a buffer before anything is written.
+pub trait AsyncWriteable {
+    fn write_async<W>(&self, writer: &mut W) -> impl Future<Output = io::Result<()>> + Send
+    where
+        W: AsyncWrite + Unpin + Send;
+}
+
+impl<T> AsyncReadable for T
+where
+    T: Readable + Send,
+{
+    fn read_async<R>(reader: &mut R) -> impl Future<Output = io::Result<Self>> + Send
+    where
+        R: AsyncRead + Unpin + Send,
+    {
+        decode(reader, |replay| T::read(replay))
+    }
+}
+
+impl<T> AsyncWriteable for T
+where
+    T: Writeable,
+{
+    fn write_async<W>(&self, writer: &mut W) -> impl Future<Output = io::Result<()>> + Send
+    where
+        W: AsyncWrite + Unpin + Send,
+    {
+        let mut buf = Vec::with_capacity(self.written_size());
+        let encoded = self.write(&mut buf);
+        write(writer, encoded.map(|_| buf))
+    }
+}
+
+/// Bytes a decoder asked for beyond those received so far.
+#[derive(Clone, Copy)]
+enum Needed {
+    /// Exactly this many, from `read_exact`.
+    Exact(usize),
+    /// Up to this many, from `read`.
+    AtMost(usize),
+}
+
+/// Serves the bytes received so far to a blocking decoder. When they run out, it records how
+/// many more bytes the decoder asked for and fails the read.
+pub(crate) struct Replay<'b> {
+    received: &'b [u8],
+    eof: bool,
+    needed: Option<Needed>,
+}
+
+impl io::Read for Replay<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.received.is_empty() && !buf.is_empty() && !self.eof {
+            self.needed = Some(Needed::AtMost(buf.len()));
+            return Err(io::ErrorKind::WouldBlock.into());
+        }
+        io::Read::read(&mut self.received, buf)
+    }
+
+    fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        if self.received.len() < buf.len() && !self.eof {
+            self.needed = Some(Needed::Exact(buf.len() - self.received.len()));
+            return Err(io::ErrorKind::WouldBlock.into());
+        }
+        io::Read::read_exact(&mut self.received, buf)
+    }
+}
+
+/// Run `decode` until it no longer runs out of bytes, reading what it asks for from `reader` in
+/// between attempts, up to [`MAX_ASYNC_READ_SIZE`] bytes in total.
+pub(crate) async fn decode<T, R, F>(reader: &mut R, decode: F) -> io::Result<T>
+where
+    R: AsyncRead + Unpin,
+    F: Fn(&mut Replay<'_>) -> io::Result<T>,
+{
+    let mut received = Vec::new();
+    let mut eof = false;
+
+    loop {
+        let mut replay = Replay {
+            received: &received,
+            eof,
+            needed: None,
+        };
+        let result = decode(&mut replay);
+
+        match (result, replay.needed) {
+            (Ok(value), _) => return Ok(value),
+            (Err(_), Some(needed)) => {
+                // Every replay decodes from the start, so fill an exact request completely
+                // rather than replaying after each partial read.
+                let (len, fill) = match needed {
+                    Needed::Exact(len) => (len, true),
+                    Needed::AtMost(len) => (len, false),
+                };
+
+                let mut filled = received.len();
+                let end = filled + len.min(READ_CHUNK_SIZE).min(MAX_ASYNC_READ_SIZE - filled);
+                if end == filled {
+                    return Err(error::limit_exceeded(MAX_ASYNC_READ_SIZE));
+                }
+                received.resize(end, 0);
+                while filled < end {
+                    let n = reader.read(&mut received[filled..]).await?;
+                    filled += n;
+
+                    // Let the decoder report the truncated input itself, with its field path.
+                    eof = n == 0;
+                    if eof || !fill {
+                        break;
+                    }
+                }
+                received.truncate(filled);
+            }
+            (Err(err), None) => return Err(err),
+        }
+    }
+}
+
+pub(crate) async fn write<W>(writer: &mut W, encoded: io::Result<Vec<u8>>) -> io::Result<()>
+where
+    W: AsyncWrite + Unpin,
+{
+    writer.write_all(&encoded?).await
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{TypePrefixedPayload as _, WriteableBytes};
+    use hex_literal::hex;
+    use wormhole_io_derive::{Readable, TypePrefixedPayload, Writeable};
+
+    #[derive(Debug, Clone, PartialEq, Eq, Readable, Writeable, TypePrefixedPayload)]
+    #[payload(type = 1)]
+    struct Message {
+        sequence: u64,
+        payload: WriteableBytes<u32>,
+    }
+
+    #[tokio::test]
+    async fn read_write_async() {
+        let value = (69u16, [1u32, 2]);
+
+        let mut encoded = Vec::new();
+        value.write_async(&mut encoded).await.unwrap();
+        assert_eq!(encoded, hex!("0045 00000001 00000002"));
+
+        let decoded = <(u16, [u32; 2])>::read_async(&mut encoded.as_slice())
+            .await
+            .unwrap();
+        assert_eq!(decoded, value);
+    }
+
+    #[tokio::test]
+    async fn read_payloads_from_stream() {
+        let first = Message {
+            sequence: 1,
+            payload: WriteableBytes::new(b"All your base".to_vec()),
+        };
+        let second = Message {
+            sequence: 2,
+            payload: WriteableBytes::new(vec![0x45; 3 * READ_CHUNK_SIZE / 2]),
+        };
+
+        // Deliver the payloads a few bytes at a time.
+        let (mut client, mut server) = tokio::io::duplex(7);
+        let writer = async {
+            first.write_payload_async(&mut client).await.unwrap();
+            second.write_payload_async(&mut client).await.unwrap();
+            drop(client);
+        };
+        let reader = async {
+            let decoded = (
+                Message::read_payload_async(&mut server).await.unwrap(),
+                Message::read_payload_async(&mut server).await.unwrap(),
+            );
+
+            // Nothing was read past the second payload.
+            assert_eq!(server.read(&mut [0; 1]).await.unwrap(), 0);
+            decoded
+        };
+
+        let (_, decoded) = tokio::join!(writer, reader);
+        assert_eq!(decoded, (first, second));
+    }
+
+    #[tokio::test]
+    async fn read_size_limit() {
+        // Claims a 4 GiB payload and keeps sending bytes.
+        let mut reader = (&[0x01, 0, 0, 0, 0, 0, 0, 0, 0, 0xff, 0xff, 0xff, 0xff][..])
+            .chain(tokio::io::repeat(0x45));
+
+        let err = Message::read_payload_async(&mut reader).await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(
+            err.to_string(),
+            format!("decoding limit of {MAX_ASYNC_READ_SIZE} bytes exceeded")
+        );
+    }
+
+    #[tokio::test]
+    async fn read_truncated_payload() {
+        let encoded = hex!("01 0000000000000001 00000004 dead");
+
+        let err = Message::read_payload_async(&mut &encoded[..])
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+        assert_eq!(
+            err.to_string(),
+            "Message.payload: unexpected end of input at offset 13"
+        );
+    }
+}
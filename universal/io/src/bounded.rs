@@ -0,0 +1,316 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use crate::{error, io};
+
+/// Length-prefixed bytes are read in chunks of this size, so that a bogus length can only force
+/// an allocation proportional to the input that actually exists.
+#[cfg(feature = "alloc")]
+const READ_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Elements of a length-prefixed vector that read no bytes, e.g. zero-length arrays, are limited
+/// to this many, so that a bogus length cannot keep decoding busy without consuming input.
+pub(crate) const MAX_EMPTY_ELEMENTS: usize = 64 * 1024;
+
+/// Reader that fails once more than a fixed number of bytes is read from it.
+///
+/// Decoding allocates at most one chunk beyond the bytes it has read, so wrapping an untrusted
+/// stream in a `BoundedReader` also bounds how much memory decoding can allocate.
+///
+/// Exact reads past the limit fail without reading anything. Plain reads stop at the limit, like
+/// at the end of the input, and never read ahead, so the inner reader is left exactly after the
+/// decoded bytes. Use [`check_end`](Self::check_end) after reading the rest of the input to
+/// reject input that goes on past the limit.
+///
+/// ```
+/// use wormhole_io::{BoundedReader, Readable, WriteableBytes};
+///
+/// // Claims to be followed by 4 GiB of data.
+/// let input = [0xff, 0xff, 0xff, 0xff, 0x00];
+///
+/// let mut reader = BoundedReader::new(&input[..], 1024);
+/// assert!(WriteableBytes::<u32>::read(&mut reader).is_err());
+/// ```
+#[derive(Debug)]
+pub struct BoundedReader<R> {
+    inner: R,
+    limit: usize,
+    remaining: usize,
+    limit_is_end: bool,
+}
+
+impl<R> BoundedReader<R> {
+    pub fn new(inner: R, limit: usize) -> Self {
+        Self {
+            inner,
+            limit,
+            remaining: limit,
+            limit_is_end: false,
+        }
+    }
+
+    /// Bound reads by the length of the input itself, e.g. a slice, so that reads past the end
+    /// are rejected up front as the end of input rather than as an exceeded limit.
+    pub(crate) fn to_end(inner: R, len: usize) -> Self {
+        Self {
+            limit_is_end: true,
+            ..Self::new(inner, len)
+        }
+    }
+
+    /// Number of bytes that can still be read before the limit is exceeded.
+    pub fn remaining(&self) -> usize {
+        self.remaining
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Fails if the limit was reached and the inner reader has more bytes, i.e. if reading the
+    /// rest of the input (e.g. with `read_to_end`) stopped at the limit rather than at the end
+    /// of the input. This consumes a byte of the inner reader in that case.
+    pub fn check_end(&mut self) -> io::Result<()>
+    where
+        R: io::Read,
+    {
+        if self.remaining == 0 && self.inner.read(&mut [0])? != 0 {
+            return Err(error::limit_exceeded(self.limit));
+        }
+
+        Ok(())
+    }
+}
+
+impl<R: io::Read> io::Read for BoundedReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() || self.remaining == 0 {
+            return Ok(0);
+        }
+
+        let len = buf.len().min(self.remaining);
+        let n = self.inner.read(&mut buf[..len])?;
+        self.remaining -= n;
+        Ok(n)
+    }
+
+    fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        if buf.len() > self.remaining {
+            return Err(if self.limit_is_end {
+                io::Error::new(io::ErrorKind::UnexpectedEof, "failed to fill whole buffer")
+            } else {
+                error::limit_exceeded(self.limit)
+            });
+        }
+
+        self.inner.read_exact(buf)?;
+        self.remaining -= buf.len();
+        Ok(())
+    }
+}
+
+/// Read exactly `len` bytes. The buffer grows one chunk at a time as bytes arrive, instead of
+/// being allocated up front from a length that may have come from untrusted input.
+#[cfg(feature = "alloc")]
+pub fn read_bytes<R>(reader: &mut R, len: usize) -> io::Result<Vec<u8>>
+where
+    R: io::Read,
+{
+    let mut buf = Vec::with_capacity(len.min(READ_CHUNK_SIZE));
+    while buf.len() < len {
+        let start = buf.len();
+        let end = start + (len - start).min(READ_CHUNK_SIZE);
+        buf.resize(end, 0);
+        reader.read_exact(&mut buf[start..])?;
+    }
+    Ok(buf)
+}
+
+/// Read `len` elements with `read`. Like [`read_bytes`], the vector grows as elements arrive,
+/// and at most [`MAX_EMPTY_ELEMENTS`] of them may read nothing, so the work done is bounded by
+/// the input rather than by `len`.
+#[cfg(feature = "alloc")]
+pub(crate) fn read_elements<R, T>(
+    reader: &mut R,
+    len: usize,
+    mut read: impl FnMut(&mut CountingReader<'_, R>) -> io::Result<T>,
+) -> io::Result<Vec<T>>
+where
+    R: io::Read,
+{
+    let mut reader = CountingReader {
+        inner: reader,
+        count: 0,
+    };
+    let mut empty = 0;
+    let mut elements = Vec::new();
+    for _ in 0..len {
+        let start = reader.count;
+        elements.push(read(&mut reader)?);
+        if reader.count == start {
+            empty += 1;
+            if empty > MAX_EMPTY_ELEMENTS {
+                return Err(error::too_many_empty_elements(MAX_EMPTY_ELEMENTS));
+            }
+        }
+    }
+    Ok(elements)
+}
+
+/// Reader that counts the bytes read through it.
+#[cfg(feature = "alloc")]
+pub(crate) struct CountingReader<'r, R> {
+    inner: &'r mut R,
+    count: usize,
+}
+
+#[cfg(feature = "alloc")]
+impl<'r, R: io::Read> io::Read for CountingReader<'r, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.count += n;
+        Ok(n)
+    }
+
+    fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        self.inner.read_exact(buf)?;
+        self.count += buf.len();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Readable, WriteableBytes, WriteableVec};
+
+    #[test]
+    fn read_bytes_in_chunks() {
+        let input = (0..3 * READ_CHUNK_SIZE / 2)
+            .map(|i| i as u8)
+            .collect::<Vec<_>>();
+
+        let bytes = read_bytes(&mut input.as_slice(), input.len()).unwrap();
+        assert_eq!(bytes, input);
+    }
+
+    #[test]
+    fn huge_length_prefix() {
+        // Without chunked reads, this would allocate 4 GiB before failing.
+        let input = [0xff, 0xff, 0xff, 0xff, 0x00];
+
+        let err = WriteableBytes::<u32>::read(&mut &input[..]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn bounded_reader() {
+        let input = [0x00, 0x00, 0x00, 0x04, 0xde, 0xad, 0xbe, 0xef];
+
+        let mut reader = BoundedReader::new(&input[..], input.len());
+        let bytes = WriteableBytes::<u32>::read(&mut reader).unwrap();
+        assert_eq!(bytes.as_slice(), [0xde, 0xad, 0xbe, 0xef]);
+        assert_eq!(reader.remaining(), 0);
+
+        let mut reader = BoundedReader::new(&input[..], input.len() - 1);
+        let err = WriteableBytes::<u32>::read(&mut reader).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), "decoding limit of 7 bytes exceeded");
+    }
+
+    #[test]
+    fn bounded_reader_elements() {
+        // Each element is small, but there are too many of them.
+        let input = [0xff; 64];
+
+        let mut reader = BoundedReader::new(&input[..], 16);
+        let err = WriteableVec::<u8, u32>::read(&mut reader).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn empty_elements() {
+        let input = [0x00, 0x01, 0x00, 0x00];
+        let vec = WriteableVec::<u32, [u8; 0]>::read(&mut &input[..]).unwrap();
+        assert_eq!(vec.len(), 0x10000);
+
+        // Elements that read nothing are not bounded by the input.
+        let input = [0xff, 0xff, 0xff, 0xff];
+        let err = WriteableVec::<u32, [u8; 0]>::read(&mut &input[..]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), "more than 65536 elements read no input");
+    }
+
+    #[test]
+    fn bounded_reader_read_to_end() {
+        use std::io::Read;
+
+        let input = [1, 2, 3, 4];
+
+        let mut buf = Vec::new();
+        let mut reader = BoundedReader::new(&input[..], 4);
+        reader.read_to_end(&mut buf).unwrap();
+        assert_eq!(buf, input);
+
+        reader.check_end().unwrap();
+
+        // Reading stops at the limit, and only checking for the end reads further.
+        let mut buf = Vec::new();
+        let mut reader = BoundedReader::new(&input[..], 3);
+        reader.read_to_end(&mut buf).unwrap();
+        assert_eq!(buf, [1, 2, 3]);
+        assert_eq!(reader.into_inner(), [4]);
+
+        let mut reader = BoundedReader::new(&input[..], 3);
+        reader.read_to_end(&mut Vec::new()).unwrap();
+        let err = reader.check_end().unwrap_err();
+        assert_eq!(err.to_string(), "decoding limit of 3 bytes exceeded");
+    }
+
+    #[test]
+    fn bounded_reader_leaves_rest() {
+        let input = [0x00, 0x00, 0x00, 0x01, 0xaa, 0xbb];
+
+        let mut rest = &input[..];
+        let mut reader = BoundedReader::new(&mut rest, 5);
+        let bytes = WriteableBytes::<u32>::read(&mut reader).unwrap();
+        assert_eq!(bytes.as_slice(), [0xaa]);
+        assert_eq!(std::io::Read::read(&mut reader, &mut [0]).unwrap(), 0);
+        assert_eq!(rest, [0xbb]);
+    }
+
+    #[test]
+    fn length_prefix_past_slice() {
+        #[derive(Debug, Clone)]
+        struct Message(WriteableBytes<u32>);
+
+        impl Readable for Message {
+            fn read<R: io::Read>(reader: &mut R) -> io::Result<Self> {
+                WriteableBytes::read(reader).map(Self)
+            }
+        }
+
+        impl crate::Writeable for Message {
+            fn write<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+                self.0.write(writer)
+            }
+        }
+
+        impl crate::TypePrefixedPayload<1> for Message {
+            const TYPE: Option<[u8; 1]> = None;
+        }
+
+        // Claims far more bytes than the slice has, in a slice larger than one chunk.
+        let mut input = vec![0x10, 0x00, 0x00, 0x00];
+        input.resize(2 * READ_CHUNK_SIZE, 0);
+
+        let err = <Message as crate::TypePrefixedPayload<1>>::read_slice(&input).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+
+        // The chunk that would cross the end of the slice is rejected before it is read.
+        let mut rest = &input[4..];
+        let mut reader = BoundedReader::to_end(&mut rest, 2 * READ_CHUNK_SIZE - 4);
+        assert!(read_bytes(&mut reader, 0x1000_0000).is_err());
+        assert_eq!(rest.len(), READ_CHUNK_SIZE - 4);
+    }
+}
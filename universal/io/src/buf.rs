@@ -0,0 +1,127 @@
+//! Reading from [`bytes::Buf`] and writing into [`bytes::BufMut`], without an intermediate `Vec`.
+
+use bytes::{Buf, BufMut};
+
+use crate::{io, Readable, Writeable};
+
+/// Extension of [`Readable`] for reading from a [`Buf`], which is advanced past the bytes read.
+pub trait BufReadable: Readable + Sized {
+    fn read_buf<B: Buf>(buf: &mut B) -> io::Result<Self>;
+}
+
+/// Extension of [`Writeable`] for writing into a [`BufMut`]. Fails without writing anything if
+/// the value does not fit.
+pub trait BufWriteable: Writeable {
+    fn write_buf<B: BufMut>(&self, buf: &mut B) -> io::Result<()>;
+}
+
+impl<T: Readable> BufReadable for T {
+    fn read_buf<B: Buf>(buf: &mut B) -> io::Result<Self> {
+        Self::read(&mut BufReader(buf))
+    }
+}
+
+impl<T: Writeable> BufWriteable for T {
+    fn write_buf<B: BufMut>(&self, buf: &mut B) -> io::Result<()> {
+        check_fits(buf, self.written_size())?;
+        self.write(&mut BufWriter(buf))
+    }
+}
+
+pub(crate) struct BufReader<'b, B>(pub &'b mut B);
+
+impl<B: Buf> io::Read for BufReader<'_, B> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = buf.len().min(self.0.remaining());
+        self.0.copy_to_slice(&mut buf[..len]);
+        Ok(len)
+    }
+
+    fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        if self.0.remaining() < buf.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "failed to fill whole buffer",
+            ));
+        }
+        self.0.copy_to_slice(buf);
+        Ok(())
+    }
+}
+
+pub(crate) struct BufWriter<'b, B>(pub &'b mut B);
+
+impl<B: BufMut> io::Write for BufWriter<'_, B> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let len = buf.len().min(self.0.remaining_mut());
+        self.0.put_slice(&buf[..len]);
+        Ok(len)
+    }
+
+    #[cfg(feature = "std")]
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+pub(crate) fn check_fits<B: BufMut>(buf: &B, len: usize) -> io::Result<()> {
+    if buf.remaining_mut() < len {
+        Err(io::Error::new(
+            io::ErrorKind::WriteZero,
+            "buffer too small for value",
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{TypePrefixedPayload as _, WriteableBytes};
+    use bytes::{Bytes, BytesMut};
+    use hex_literal::hex;
+    use wormhole_io_derive::{Readable, TypePrefixedPayload, Writeable};
+
+    #[derive(Debug, Clone, PartialEq, Eq, Readable, Writeable, TypePrefixedPayload)]
+    #[payload(type = 1)]
+    struct Message {
+        sequence: u64,
+        payload: WriteableBytes<u16>,
+    }
+
+    #[test]
+    fn read_write_buf() {
+        let msg = Message {
+            sequence: 2,
+            payload: WriteableBytes::new(b"hello".to_vec()),
+        };
+
+        let mut buf = BytesMut::with_capacity(msg.payload_written_size());
+        msg.write_payload_buf(&mut buf).unwrap();
+        assert_eq!(buf[..], hex!("01 0000000000000002 0005 68656c6c6f"));
+
+        // Decoding leaves the rest of the buffer in place.
+        buf.extend_from_slice(&[0xff]);
+        let mut buf = buf.freeze();
+        assert_eq!(Message::read_payload_buf(&mut buf).unwrap(), msg);
+        assert_eq!(buf, Bytes::from_static(&[0xff]));
+    }
+
+    #[test]
+    fn read_buf_truncated() {
+        let mut buf = Bytes::from_static(&hex!("0000000000000002 0005 6865"));
+
+        let err = Message::read_buf(&mut buf).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn write_buf_too_small() {
+        let mut out = [0u8; 4];
+
+        let err = 69u64.write_buf(&mut &mut out[..]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::WriteZero);
+        assert_eq!(out, [0; 4]);
+    }
+}
@@ -0,0 +1,539 @@
+//! Annotated hex dumps of encoded values, driven by their [`Layout`].
+//!
+//! Every field gets a line with its offset, length, name, raw bytes and decoded value. Dumping
+//! never fails: if the input does not match the layout, the dump stops at the offending field and
+//! records why in [`Dump::failure`]. Bytes left over, whether after a failure or after the end of
+//! the layout, are kept in [`Dump::trailing`].
+//!
+//! ```
+//! use wormhole_io::{dump, WriteableBytes};
+//!
+//! let dump = dump::dump::<(u16, WriteableBytes<u8>)>(&[0, 69, 2, 0xbe, 0xef, 0xff]);
+//! assert_eq!(dump.lines.len(), 3);
+//! assert_eq!(dump.trailing.unwrap().bytes, [0xff]);
+//! ```
+
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt;
+
+use crate::{
+    layout::{Endian, Field, IntEncoding, LayoutItem},
+    Layout, TypePrefixedPayload,
+};
+
+/// At most this many bytes are shown per line by [`Dump`]'s `Display` impl.
+const MAX_DISPLAYED_BYTES: usize = 32;
+
+/// Annotated decoding of some bytes. Its `Display` impl renders a table with a line per field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dump {
+    /// Decoded fields, in order.
+    pub lines: Vec<Line>,
+    /// Why decoding stopped before the end of the layout, if it did.
+    pub failure: Option<Failure>,
+    /// Bytes that were not decoded, either because decoding failed or because they follow the end
+    /// of the layout.
+    pub trailing: Option<Line>,
+}
+
+/// A decoded field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Line {
+    pub offset: usize,
+    /// Dotted path of the field, e.g. `signatures[0].r`.
+    pub name: String,
+    pub bytes: Vec<u8>,
+    /// Decoded value, empty if the bytes speak for themselves.
+    pub value: String,
+}
+
+/// Where and why decoding failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Failure {
+    pub offset: usize,
+    /// Dotted path of the field that could not be decoded.
+    pub name: String,
+    pub reason: String,
+}
+
+impl Dump {
+    /// Whether the input matched the layout exactly, with no bytes left over.
+    pub fn is_complete(&self) -> bool {
+        self.failure.is_none() && self.trailing.is_none()
+    }
+}
+
+/// Dump `bytes` decoded as `T`.
+pub fn dump<T: Layout>(bytes: &[u8]) -> Dump {
+    dump_layout(&T::layout(), bytes)
+}
+
+/// Dump `bytes` decoded as a payload, including its type prefix. See
+/// [`TypePrefixedPayload::read_payload`].
+pub fn dump_payload<P, const N: usize>(bytes: &[u8]) -> Dump
+where
+    P: TypePrefixedPayload<N> + Layout,
+{
+    dump_layout(&crate::layout::payload_layout::<P, N>(), bytes)
+}
+
+/// Dump `bytes` decoded according to `layout`.
+pub fn dump_layout(layout: &LayoutItem, bytes: &[u8]) -> Dump {
+    let mut walker = Walker {
+        input: bytes,
+        pos: 0,
+        lines: Vec::new(),
+    };
+    let failure = walker.item("", layout).err();
+
+    let trailing = (walker.pos < bytes.len()).then(|| Line {
+        offset: walker.pos,
+        name: if failure.is_some() {
+            "<undecoded>".into()
+        } else {
+            "<trailing>".into()
+        },
+        bytes: bytes[walker.pos..].to_vec(),
+        value: String::new(),
+    });
+
+    Dump {
+        lines: walker.lines,
+        failure,
+        trailing,
+    }
+}
+
+struct Walker<'a> {
+    input: &'a [u8],
+    pos: usize,
+    lines: Vec<Line>,
+}
+
+impl<'a> Walker<'a> {
+    fn fail(&self, name: &str, reason: impl Into<String>) -> Failure {
+        Failure {
+            offset: self.pos,
+            name: name.into(),
+            reason: reason.into(),
+        }
+    }
+
+    fn take(&mut self, name: &str, len: usize) -> Result<&'a [u8], Failure> {
+        let remaining = self.input.len() - self.pos;
+        if len > remaining {
+            return Err(self.fail(name, format!("expected {len} bytes, found {remaining}")));
+        }
+
+        let bytes = &self.input[self.pos..self.pos + len];
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    /// Consume `len` bytes as a line for `name`. `value` turns them into the decoded value, or
+    /// fails with a reason, in which case nothing is consumed.
+    fn line(
+        &mut self,
+        name: &str,
+        len: usize,
+        value: impl FnOnce(&[u8]) -> Result<String, String>,
+    ) -> Result<&'a [u8], Failure> {
+        let offset = self.pos;
+        let bytes = self.take(name, len)?;
+        match value(bytes) {
+            Ok(value) => {
+                self.lines.push(Line {
+                    offset,
+                    name: name.into(),
+                    bytes: bytes.to_vec(),
+                    value,
+                });
+                Ok(bytes)
+            }
+            Err(reason) => {
+                self.pos = offset;
+                Err(self.fail(name, reason))
+            }
+        }
+    }
+
+    /// Consume a length prefix or enum discriminant.
+    fn int(&mut self, name: &str, int: &IntEncoding) -> Result<u128, Failure> {
+        let bytes = self.line(name, int.size, |bytes| {
+            Ok(uint_to_string(bytes, int.endian))
+        })?;
+        uint(bytes, int.endian)
+            .ok_or_else(|| self.fail(name, format!("{}-byte integer is too large", int.size)))
+    }
+
+    fn len(&mut self, name: &str, len: &IntEncoding) -> Result<usize, Failure> {
+        let name = join(name, "len");
+        let value = self.int(&name, len)?;
+        usize::try_from(value).map_err(|_| self.fail(&name, format!("length {value} is too large")))
+    }
+
+    fn item(&mut self, name: &str, item: &LayoutItem) -> Result<(), Failure> {
+        match item {
+            LayoutItem::Uint(int) => {
+                self.line(name, int.size, |bytes| {
+                    Ok(uint_to_string(bytes, int.endian))
+                })?;
+            }
+            LayoutItem::Int(int) => {
+                self.line(name, int.size, |bytes| Ok(int_to_string(bytes, int.endian)))?;
+            }
+            LayoutItem::Bool => {
+                self.line(name, 1, |bytes| match bytes[0] {
+                    0 => Ok("false".into()),
+                    1 => Ok("true".into()),
+                    b => Err(format!("invalid bool {b}")),
+                })?;
+            }
+            LayoutItem::Bytes { size } => {
+                self.line(name, *size, |_| Ok(String::new()))?;
+            }
+            LayoutItem::Constant { bytes: expected } => {
+                self.line(name, expected.len(), |bytes| {
+                    if bytes == expected.as_slice() {
+                        Ok(format!("0x{}", hex(bytes)))
+                    } else {
+                        Err(format!(
+                            "expected 0x{}, found 0x{}",
+                            hex(expected),
+                            hex(bytes)
+                        ))
+                    }
+                })?;
+            }
+            LayoutItem::FixedString { size } => {
+                self.line(name, *size, |bytes| {
+                    let end = bytes.iter().rposition(|&b| b != 0).map_or(0, |i| i + 1);
+                    Ok(quoted(&bytes[..end]))
+                })?;
+            }
+            LayoutItem::LengthPrefixedBytes { len } => {
+                let len = self.len(name, len)?;
+                self.line(name, len, |_| Ok(String::new()))?;
+            }
+            LayoutItem::LengthPrefixedString { len } => {
+                let len = self.len(name, len)?;
+                self.line(name, len, |bytes| match core::str::from_utf8(bytes) {
+                    Ok(_) => Ok(quoted(bytes)),
+                    Err(_) => Err("invalid UTF-8".into()),
+                })?;
+            }
+            LayoutItem::Remainder => {
+                let len = self.input.len() - self.pos;
+                self.line(name, len, |_| Ok(String::new()))?;
+            }
+            LayoutItem::Array { len, item } => {
+                for i in 0..*len {
+                    self.item(&format!("{name}[{i}]"), item)?;
+                }
+            }
+            LayoutItem::Vec { len, item } => {
+                let len = self.len(name, len)?;
+                for i in 0..len {
+                    self.item(&format!("{name}[{i}]"), item)?;
+                }
+            }
+            LayoutItem::Option(item) => {
+                let some = self.line(&join(name, "is_some"), 1, |bytes| match bytes[0] {
+                    0 => Ok("false".into()),
+                    1 => Ok("true".into()),
+                    b => Err(format!("invalid bool {b}")),
+                })?;
+                if some[0] == 1 {
+                    self.item(name, item)?;
+                }
+            }
+            LayoutItem::Struct { fields, .. } => self.fields(name, fields)?,
+            LayoutItem::Enum {
+                name: type_name,
+                discriminant,
+                variants,
+            } => {
+                let line_name = if name.is_empty() { type_name } else { name };
+                let offset = self.pos;
+                let found = self.int(line_name, discriminant)?;
+                match variants
+                    .iter()
+                    .find(|variant| u128::try_from(variant.discriminant) == Ok(found))
+                {
+                    Some(variant) => {
+                        self.lines.last_mut().unwrap().value =
+                            format!("{type_name}::{}", variant.name);
+                        self.fields(name, &variant.fields)?;
+                    }
+                    None => {
+                        self.lines.pop();
+                        self.pos = offset;
+                        return Err(self.fail(
+                            line_name,
+                            format!("unknown {type_name} discriminant {found}"),
+                        ));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn fields(&mut self, name: &str, fields: &[Field]) -> Result<(), Failure> {
+        for field in fields {
+            let field_name = join(name, field.name);
+            if field.padding > 0 {
+                self.line(&join(&field_name, "padding"), field.padding, |bytes| {
+                    if bytes.iter().all(|&b| b == 0) {
+                        Ok(String::new())
+                    } else {
+                        Err("padding is not zeroed".into())
+                    }
+                })?;
+            }
+            self.item(&field_name, &field.item)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Dump {
+    /// One line per field: offset, length, name, hex (truncated to 32 bytes) and decoded value.
+    /// The failure and any undecoded bytes are marked with `!!`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let lines = self.lines.iter().chain(&self.trailing);
+        let name_width = lines
+            .clone()
+            .map(|line| line.name.len())
+            .max()
+            .unwrap_or_default();
+        let hex_width = lines
+            .clone()
+            .map(|line| displayed_hex(&line.bytes).len())
+            .max()
+            .unwrap_or_default();
+
+        for line in &self.lines {
+            write_line(f, "  ", line, name_width, hex_width)?;
+        }
+        if let Some(failure) = &self.failure {
+            writeln!(
+                f,
+                "!! {:>6}        decoding failed at {}: {}",
+                failure.offset, failure.name, failure.reason
+            )?;
+        }
+        if let Some(trailing) = &self.trailing {
+            write_line(f, "!!", trailing, name_width, hex_width)?;
+        }
+        Ok(())
+    }
+}
+
+fn write_line(
+    f: &mut fmt::Formatter<'_>,
+    marker: &str,
+    line: &Line,
+    name_width: usize,
+    hex_width: usize,
+) -> fmt::Result {
+    let hex = displayed_hex(&line.bytes);
+    let out = format!(
+        "{marker} {:>6} {:>6}  {:name_width$}  {hex:hex_width$}  {}",
+        line.offset,
+        line.bytes.len(),
+        line.name,
+        line.value
+    );
+    writeln!(f, "{}", out.trim_end())
+}
+
+fn displayed_hex(bytes: &[u8]) -> String {
+    if bytes.len() > MAX_DISPLAYED_BYTES {
+        format!("{}..", hex(&bytes[..MAX_DISPLAYED_BYTES]))
+    } else {
+        hex(bytes)
+    }
+}
+
+fn join(path: &str, name: &str) -> String {
+    if path.is_empty() {
+        name.into()
+    } else {
+        format!("{path}.{name}")
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn quoted(bytes: &[u8]) -> String {
+    format!("{:?}", String::from_utf8_lossy(bytes))
+}
+
+/// Big-endian copy of an integer's bytes.
+fn big_endian(bytes: &[u8], endian: Endian) -> Vec<u8> {
+    let mut bytes = bytes.to_vec();
+    if endian == Endian::Little {
+        bytes.reverse();
+    }
+    bytes
+}
+
+fn uint(bytes: &[u8], endian: Endian) -> Option<u128> {
+    big_endian(bytes, endian)
+        .into_iter()
+        .try_fold(0u128, |acc, b| acc.checked_mul(256)?.checked_add(b.into()))
+}
+
+/// Decimal representation of an unsigned integer of any size.
+fn uint_to_string(bytes: &[u8], endian: Endian) -> String {
+    let mut digits = big_endian(bytes, endian);
+    let mut out = Vec::new();
+    // Repeatedly divide by 10, collecting remainders.
+    while digits.iter().any(|&b| b != 0) {
+        let mut rem = 0u16;
+        for digit in &mut digits {
+            let acc = rem << 8 | u16::from(*digit);
+            *digit = (acc / 10) as u8;
+            rem = acc % 10;
+        }
+        out.push(b'0' + rem as u8);
+    }
+    if out.is_empty() {
+        out.push(b'0');
+    }
+    out.reverse();
+    String::from_utf8(out).unwrap()
+}
+
+fn int_to_string(bytes: &[u8], endian: Endian) -> String {
+    match (bytes.len(), uint(bytes, endian)) {
+        (size @ 1..=16, Some(value)) => {
+            // Sign-extend from `size` bytes.
+            let shift = 128 - 8 * size as u32;
+            ((value << shift) as i128 >> shift).to_string()
+        }
+        _ => format!("0x{}", hex(&big_endian(bytes, endian))),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{layout::Variant, WriteableBytes, WriteableString};
+    use hex_literal::hex;
+
+    #[test]
+    fn dump_fields() {
+        let dump = dump::<(u16, i8, WriteableString<u8>, [u8; 2], Option<u32>)>(&hex!(
+            "0045 ff 02 6869 beef 01 00000100"
+        ));
+        assert!(dump.is_complete());
+
+        let lines = dump
+            .lines
+            .iter()
+            .map(|line| (line.offset, line.name.as_str(), line.value.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            lines,
+            [
+                (0, "0", "69"),
+                (2, "1", "-1"),
+                (3, "2.len", "2"),
+                (4, "2", "\"hi\""),
+                (6, "3", ""),
+                (8, "4.is_some", "true"),
+                (9, "4", "256"),
+            ]
+        );
+    }
+
+    #[test]
+    fn large_uint() {
+        assert_eq!(uint_to_string(&[0xff; 32], Endian::Big), U256_MAX);
+        assert_eq!(uint_to_string(&[0; 32], Endian::Big), "0");
+        assert_eq!(uint_to_string(&hex!("3412"), Endian::Little), "4660");
+    }
+
+    const U256_MAX: &str =
+        "115792089237316195423570985008687907853269984665640564039457584007913129639935";
+
+    #[test]
+    fn partial_input() {
+        let dump = dump::<(u8, WriteableBytes<u16>)>(&hex!("01 0004 beef"));
+        assert_eq!(dump.lines.len(), 2);
+        assert_eq!(
+            dump.failure,
+            Some(Failure {
+                offset: 3,
+                name: "1".into(),
+                reason: "expected 4 bytes, found 2".into(),
+            })
+        );
+
+        let trailing = dump.trailing.unwrap();
+        assert_eq!(trailing.name, "<undecoded>");
+        assert_eq!(
+            (trailing.offset, trailing.bytes),
+            (3, hex!("beef").to_vec())
+        );
+    }
+
+    #[test]
+    fn unknown_variant() {
+        let layout = LayoutItem::enumeration(
+            "Action",
+            IntEncoding::big(1),
+            [Variant::new(
+                "SetFee",
+                2,
+                [Field::new("fee", LayoutItem::uint(2))],
+            )],
+        );
+
+        let dump = dump_layout(&layout, &hex!("02 0003"));
+        assert!(dump.is_complete());
+        assert_eq!(dump.lines[0].name, "Action");
+        assert_eq!(dump.lines[0].value, "Action::SetFee");
+        assert_eq!(dump.lines[1].name, "fee");
+
+        let dump = dump_layout(&layout, &hex!("03 0003"));
+        assert!(dump.lines.is_empty());
+        assert_eq!(
+            dump.failure.unwrap().reason,
+            "unknown Action discriminant 3"
+        );
+        assert_eq!(dump.trailing.unwrap().bytes.len(), 3);
+    }
+
+    #[test]
+    fn display() {
+        let layout = <(u16, [u8; 4])>::layout().prefixed(&[1]);
+        let dump = dump_layout(&layout, &hex!("01 0045 deadbeef 00"));
+        assert_eq!(
+            dump.to_string(),
+            concat!(
+                "        0      1  type        01        0x01\n",
+                "        1      2  0           0045      69\n",
+                "        3      4  1           deadbeef\n",
+                "!!      7      1  <trailing>  00\n",
+            )
+        );
+
+        let dump = dump_layout(&layout, &hex!("02 0045"));
+        assert_eq!(
+            dump.to_string(),
+            concat!(
+                "!!      0        decoding failed at type: expected 0x01, found 0x02\n",
+                "!!      0      3  <undecoded>  020045\n",
+            )
+        );
+    }
+}
@@ -0,0 +1,426 @@
+use crate::io;
+
+#[cfg(all(feature = "alloc", not(feature = "std")))]
+use alloc::boxed::Box;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+#[cfg(feature = "alloc")]
+use core::fmt;
+
+/// Describes why decoding failed.
+#[cfg(feature = "alloc")]
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum DecodeErrorKind {
+    /// The input ended before the value was fully read.
+    UnexpectedEof,
+    /// The type prefix of a [`TypePrefixedPayload`](crate::TypePrefixedPayload) did not match.
+    InvalidPayloadType { expected: Vec<u8>, found: Vec<u8> },
+    /// No decoder is registered for the type prefix in a
+    /// [`PayloadRegistry`](crate::PayloadRegistry).
+    UnknownPayloadType { found: Vec<u8> },
+    /// An enum discriminant did not match any variant. `expected` lists the discriminants of
+    /// the variants.
+    InvalidDiscriminant {
+        expected: &'static [i128],
+        found: i128,
+    },
+    /// The version of a [`Versioned`](crate::Versioned) payload is older than its type supports,
+    /// or newer and the type is not extensible.
+    UnsupportedVersion { found: u8 },
+    /// The input was not fully consumed, e.g. by
+    /// [`read_slice`](crate::TypePrefixedPayload::read_slice).
+    InvalidLength { expected: usize, found: usize },
+    /// A bool was encoded as something other than 0 or 1.
+    InvalidBool { found: u8 },
+    /// Padding bytes were not zero.
+    InvalidPadding,
+    /// An encoded length does not fit in `usize`.
+    LengthOverflow,
+    /// A string was not valid UTF-8.
+    InvalidUtf8,
+    /// More bytes were read than a [`BoundedReader`](crate::BoundedReader) allows.
+    LimitExceeded { limit: usize },
+    /// More elements of a length-prefixed vector read no bytes than allowed.
+    TooManyEmptyElements { limit: usize },
+    /// Any other error, e.g. from the reader itself or a hand-written [`Readable`](crate::Readable)
+    /// implementation.
+    Io(io::Error),
+}
+
+/// Error returned when decoding fails, which records where in the input it happened.
+///
+/// Decoding methods return [`io::Error`] for compatibility. Use [`DecodeError::from_io`] to
+/// recover the structured error:
+///
+/// ```
+/// use wormhole_io::{DecodeError, DecodeErrorKind, TypePrefixedPayload};
+/// # use wormhole_io::{io, Readable, Writeable};
+/// # #[derive(Debug, Clone)]
+/// # struct Message(u16);
+/// # impl Readable for Message {
+/// #     fn read<R: io::Read>(reader: &mut R) -> io::Result<Self> {
+/// #         u16::read(reader).map(Self)
+/// #     }
+/// # }
+/// # impl Writeable for Message {
+/// #     fn write<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+/// #         self.0.write(writer)
+/// #     }
+/// #     fn written_size(&self) -> usize {
+/// #         2
+/// #     }
+/// # }
+/// # impl TypePrefixedPayload<1> for Message {
+/// #     const TYPE: Option<[u8; 1]> = Some([1]);
+/// # }
+///
+/// let err = DecodeError::from_io(Message::read_slice(&[2, 0, 69]).unwrap_err());
+/// assert!(matches!(err.kind(), DecodeErrorKind::InvalidPayloadType { .. }));
+/// assert_eq!(err.offset(), Some(0));
+/// assert_eq!(err.to_string(), "Message: invalid payload type: expected 0x01, found 0x02 at offset 0");
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Debug)]
+pub struct DecodeError {
+    kind: DecodeErrorKind,
+    offset: Option<usize>,
+    /// Field names, innermost first.
+    path: Vec<&'static str>,
+}
+
+#[cfg(feature = "alloc")]
+impl DecodeError {
+    pub fn new(kind: DecodeErrorKind) -> Self {
+        Self {
+            kind,
+            offset: None,
+            path: Vec::new(),
+        }
+    }
+
+    pub fn kind(&self) -> &DecodeErrorKind {
+        &self.kind
+    }
+
+    /// Byte offset in the input of the value that failed to decode. This is only known when
+    /// decoding with [`read_payload`](crate::TypePrefixedPayload::read_payload) or
+    /// [`read_slice`](crate::TypePrefixedPayload::read_slice).
+    pub fn offset(&self) -> Option<usize> {
+        self.offset
+    }
+
+    /// Field names leading to the value that failed to decode, outermost first (e.g.
+    /// `["TransferWithMessage", "payload", "len"]`).
+    pub fn path(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.path.iter().rev().copied()
+    }
+
+    /// Prepend a field name to the path.
+    pub fn in_field(mut self, field: &'static str) -> Self {
+        self.path.push(field);
+        self
+    }
+
+    /// Recover the decode error from an [`io::Error`]. Errors that did not originate from a
+    /// `DecodeError` are wrapped as [`DecodeErrorKind::UnexpectedEof`] or [`DecodeErrorKind::Io`].
+    pub fn from_io(err: io::Error) -> Self {
+        #[cfg(feature = "std")]
+        let err = match err.get_ref().map(|inner| inner.is::<Self>()) {
+            Some(true) => return *err.into_inner().unwrap().downcast::<Self>().unwrap(),
+            _ => err,
+        };
+
+        #[cfg(not(feature = "std"))]
+        let err = match err.into_decode_error() {
+            Ok(decode_err) => return *decode_err,
+            Err(err) => err,
+        };
+
+        match err.kind() {
+            io::ErrorKind::UnexpectedEof => Self::new(DecodeErrorKind::UnexpectedEof),
+            _ => Self::new(DecodeErrorKind::Io(err)),
+        }
+    }
+
+    fn io_kind(&self) -> io::ErrorKind {
+        match &self.kind {
+            DecodeErrorKind::UnexpectedEof => io::ErrorKind::UnexpectedEof,
+            DecodeErrorKind::Io(err) => err.kind(),
+            _ => io::ErrorKind::InvalidData,
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl fmt::Display for DecodeErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeErrorKind::UnexpectedEof => write!(f, "unexpected end of input"),
+            DecodeErrorKind::InvalidPayloadType { expected, found } => {
+                write!(f, "invalid payload type: expected 0x")?;
+                expected.iter().try_for_each(|b| write!(f, "{b:02x}"))?;
+                write!(f, ", found 0x")?;
+                found.iter().try_for_each(|b| write!(f, "{b:02x}"))
+            }
+            DecodeErrorKind::UnknownPayloadType { found } => {
+                write!(f, "unknown payload type 0x")?;
+                found.iter().try_for_each(|b| write!(f, "{b:02x}"))
+            }
+            DecodeErrorKind::InvalidDiscriminant { expected, found } => {
+                write!(f, "invalid discriminant: expected one of ")?;
+                for (i, discriminant) in expected.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{discriminant}")?;
+                }
+                write!(f, ", found {found}")
+            }
+            DecodeErrorKind::UnsupportedVersion { found } => {
+                write!(f, "unsupported version {found}")
+            }
+            DecodeErrorKind::InvalidLength { expected, found } => {
+                write!(f, "invalid length: expected {expected}, found {found}")
+            }
+            DecodeErrorKind::InvalidBool { found } => write!(f, "invalid bool value {found}"),
+            DecodeErrorKind::InvalidPadding => write!(f, "invalid padding"),
+            DecodeErrorKind::LengthOverflow => write!(f, "length overflow"),
+            DecodeErrorKind::InvalidUtf8 => write!(f, "invalid UTF-8"),
+            DecodeErrorKind::LimitExceeded { limit } => {
+                write!(f, "decoding limit of {limit} bytes exceeded")
+            }
+            DecodeErrorKind::TooManyEmptyElements { limit } => {
+                write!(f, "more than {limit} elements read no input")
+            }
+            DecodeErrorKind::Io(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, field) in self.path().enumerate() {
+            if i > 0 {
+                f.write_str(".")?;
+            }
+            f.write_str(field)?;
+        }
+        if !self.path.is_empty() {
+            f.write_str(": ")?;
+        }
+
+        write!(f, "{}", self.kind)?;
+
+        match self.offset {
+            Some(offset) => write!(f, " at offset {offset}"),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DecodeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match &self.kind {
+            DecodeErrorKind::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<DecodeError> for io::Error {
+    fn from(err: DecodeError) -> Self {
+        #[cfg(feature = "std")]
+        return io::Error::new(err.io_kind(), err);
+
+        #[cfg(not(feature = "std"))]
+        return io::Error::from_decode_error(err.io_kind(), Box::new(err));
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<DecodeErrorKind> for DecodeError {
+    fn from(kind: DecodeErrorKind) -> Self {
+        Self::new(kind)
+    }
+}
+
+/// Reader that tracks the byte offset of the value being read, so that errors can report where
+/// they happened. Every value in this crate is read with a single `read_exact`, except for large
+/// length-prefixed byte fields, which are read in chunks. An error in one of those reports the
+/// offset of the failing chunk.
+#[cfg(feature = "alloc")]
+pub(crate) struct OffsetReader<'r, R> {
+    inner: &'r mut R,
+    position: usize,
+    value_start: usize,
+}
+
+#[cfg(feature = "alloc")]
+impl<'r, R: io::Read> OffsetReader<'r, R> {
+    pub fn new(inner: &'r mut R) -> Self {
+        Self {
+            inner,
+            position: 0,
+            value_start: 0,
+        }
+    }
+
+    /// Attach the offset of the value being read when the error occurred.
+    pub fn locate(&self, err: io::Error) -> io::Error {
+        let mut err = DecodeError::from_io(err);
+        err.offset = Some(self.value_start);
+        err.into()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'r, R: io::Read> io::Read for OffsetReader<'r, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.value_start = self.position;
+        let n = self.inner.read(buf)?;
+        self.position += n;
+        Ok(n)
+    }
+
+    fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        self.value_start = self.position;
+        self.inner.read_exact(buf)?;
+        self.position += buf.len();
+        Ok(())
+    }
+}
+
+/// Name of a type without its module path or generic parameters, e.g. `TransferWithMessage`.
+pub(crate) fn type_name<T: ?Sized>() -> &'static str {
+    let name = core::any::type_name::<T>();
+    let name = name.split('<').next().unwrap_or(name);
+    name.rsplit("::").next().unwrap_or(name)
+}
+
+/// Add a field name to the path of a decode error. Without `alloc`, the error is unchanged.
+pub fn in_field(err: io::Error, field: &'static str) -> io::Error {
+    #[cfg(feature = "alloc")]
+    return DecodeError::from_io(err).in_field(field).into();
+
+    #[cfg(not(feature = "alloc"))]
+    {
+        let _ = field;
+        err
+    }
+}
+
+pub(crate) fn invalid_bool(found: u8) -> io::Error {
+    #[cfg(feature = "alloc")]
+    return DecodeError::new(DecodeErrorKind::InvalidBool { found }).into();
+
+    #[cfg(not(feature = "alloc"))]
+    {
+        let _ = found;
+        io::Error::new(io::ErrorKind::InvalidData, "invalid bool value")
+    }
+}
+
+pub(crate) fn invalid_payload_type(expected: &[u8], found: &[u8]) -> io::Error {
+    #[cfg(feature = "alloc")]
+    return DecodeError::new(DecodeErrorKind::InvalidPayloadType {
+        expected: expected.to_vec(),
+        found: found.to_vec(),
+    })
+    .into();
+
+    #[cfg(not(feature = "alloc"))]
+    {
+        let _ = (expected, found);
+        io::Error::new(io::ErrorKind::InvalidData, "Invalid payload type")
+    }
+}
+
+#[cfg(feature = "alloc")]
+pub(crate) fn unknown_payload_type(found: &[u8]) -> io::Error {
+    DecodeError::new(DecodeErrorKind::UnknownPayloadType {
+        found: found.to_vec(),
+    })
+    .into()
+}
+
+#[cfg(feature = "alloc")]
+pub(crate) fn unsupported_version(found: u8) -> io::Error {
+    DecodeError::new(DecodeErrorKind::UnsupportedVersion { found }).into()
+}
+
+pub(crate) fn invalid_length(expected: usize, found: usize) -> io::Error {
+    #[cfg(feature = "alloc")]
+    return DecodeError {
+        kind: DecodeErrorKind::InvalidLength { expected, found },
+        offset: Some(expected),
+        path: Vec::new(),
+    }
+    .into();
+
+    #[cfg(not(feature = "alloc"))]
+    {
+        let _ = (expected, found);
+        io::Error::new(io::ErrorKind::InvalidData, "Invalid payload length")
+    }
+}
+
+pub fn invalid_padding() -> io::Error {
+    #[cfg(feature = "alloc")]
+    return DecodeError::new(DecodeErrorKind::InvalidPadding).into();
+
+    #[cfg(not(feature = "alloc"))]
+    io::Error::new(io::ErrorKind::InvalidData, "Invalid padding")
+}
+
+pub fn invalid_discriminant(expected: &'static [i128], found: i128) -> io::Error {
+    #[cfg(feature = "alloc")]
+    return DecodeError::new(DecodeErrorKind::InvalidDiscriminant { expected, found }).into();
+
+    #[cfg(not(feature = "alloc"))]
+    {
+        let _ = (expected, found);
+        io::Error::new(io::ErrorKind::InvalidData, "Invalid discriminant")
+    }
+}
+
+pub fn length_overflow() -> io::Error {
+    #[cfg(feature = "alloc")]
+    return DecodeError::new(DecodeErrorKind::LengthOverflow).into();
+
+    #[cfg(not(feature = "alloc"))]
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        "Length overflow when converting to usize",
+    )
+}
+
+#[cfg(feature = "alloc")]
+pub(crate) fn invalid_utf8() -> io::Error {
+    DecodeError::new(DecodeErrorKind::InvalidUtf8).into()
+}
+
+pub(crate) fn limit_exceeded(limit: usize) -> io::Error {
+    #[cfg(feature = "alloc")]
+    return DecodeError::new(DecodeErrorKind::LimitExceeded { limit }).into();
+
+    #[cfg(not(feature = "alloc"))]
+    {
+        let _ = limit;
+        io::Error::new(io::ErrorKind::InvalidData, "Decoding limit exceeded")
+    }
+}
+
+pub(crate) fn too_many_empty_elements(limit: usize) -> io::Error {
+    #[cfg(feature = "alloc")]
+    return DecodeError::new(DecodeErrorKind::TooManyEmptyElements { limit }).into();
+
+    #[cfg(not(feature = "alloc"))]
+    {
+        let _ = limit;
+        io::Error::new(io::ErrorKind::InvalidData, "Too many empty elements")
+    }
+}
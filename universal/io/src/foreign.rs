@@ -0,0 +1,196 @@
+//! Implementations for types from other crates, each behind an optional feature.
+//!
+//! * `solana`: [`Pubkey`](solana_program::pubkey::Pubkey), encoded as its 32 bytes.
+//! * `ruint`: [`Uint<BITS, LIMBS>`](ruint::Uint), encoded as `BITS / 8` (rounded up) big-endian
+//!   bytes. These also cover `alloy_primitives::{U256, Uint}`, which are re-exports of `ruint`.
+//! * `alloy`: an alias for `ruint`. alloy-primitives cannot be a dependency here, since its
+//!   zeroize requirement conflicts with solana-program 1.18, so alloy's `FixedBytes` and
+//!   `Address` have no impls; encode them through their inner `[u8; N]`.
+//! * `primitive-types`: [`U256`](primitive_types::U256), encoded as 32 big-endian bytes.
+
+#[cfg(any(feature = "ruint", feature = "primitive-types"))]
+use crate::read_ref::split_at_checked;
+#[cfg(any(feature = "solana", feature = "ruint", feature = "primitive-types"))]
+use crate::{io, Readable, ReadableRef, Writeable};
+
+#[cfg(feature = "solana")]
+impl Readable for solana_program::pubkey::Pubkey {
+    fn read<R>(reader: &mut R) -> io::Result<Self>
+    where
+        Self: Sized,
+        R: io::Read,
+    {
+        <[u8; 32]>::read(reader).map(Self::new_from_array)
+    }
+}
+
+#[cfg(feature = "solana")]
+impl<'a> ReadableRef<'a> for solana_program::pubkey::Pubkey {
+    fn read_ref(buf: &'a [u8]) -> io::Result<(Self, usize)> {
+        <[u8; 32]>::read_ref(buf).map(|(bytes, consumed)| (Self::new_from_array(bytes), consumed))
+    }
+}
+
+#[cfg(feature = "solana")]
+impl Writeable for solana_program::pubkey::Pubkey {
+    fn write<W>(&self, writer: &mut W) -> io::Result<()>
+    where
+        W: io::Write,
+    {
+        writer.write_all(self.as_ref())
+    }
+
+    fn written_size(&self) -> usize {
+        32
+    }
+}
+
+#[cfg(feature = "ruint")]
+impl<const BITS: usize, const LIMBS: usize> Readable for ruint::Uint<BITS, LIMBS> {
+    fn read<R>(reader: &mut R) -> io::Result<Self>
+    where
+        Self: Sized,
+        R: io::Read,
+    {
+        let mut limbs = [0u64; LIMBS];
+        for i in (0..Self::BYTES).rev() {
+            limbs[i / 8] |= u64::from(u8::read(reader)?) << (8 * (i % 8));
+        }
+
+        // If BITS is not a multiple of 8, the unused high bits must be zero.
+        Self::checked_from_limbs_slice(&limbs).ok_or_else(crate::error::invalid_padding)
+    }
+}
+
+#[cfg(feature = "ruint")]
+impl<'a, const BITS: usize, const LIMBS: usize> ReadableRef<'a> for ruint::Uint<BITS, LIMBS> {
+    fn read_ref(buf: &'a [u8]) -> io::Result<(Self, usize)> {
+        let (bytes, _) = split_at_checked(buf, Self::BYTES)?;
+        Self::read(&mut &bytes[..]).map(|value| (value, Self::BYTES))
+    }
+}
+
+#[cfg(feature = "ruint")]
+impl<const BITS: usize, const LIMBS: usize> Writeable for ruint::Uint<BITS, LIMBS> {
+    fn write<W>(&self, writer: &mut W) -> io::Result<()>
+    where
+        W: io::Write,
+    {
+        (0..Self::BYTES)
+            .rev()
+            .try_for_each(|i| writer.write_all(&[self.byte(i)]))
+    }
+
+    fn written_size(&self) -> usize {
+        Self::BYTES
+    }
+}
+
+#[cfg(feature = "primitive-types")]
+impl Readable for primitive_types::U256 {
+    fn read<R>(reader: &mut R) -> io::Result<Self>
+    where
+        Self: Sized,
+        R: io::Read,
+    {
+        <[u8; 32]>::read(reader).map(|bytes| Self::from_big_endian(&bytes))
+    }
+}
+
+#[cfg(feature = "primitive-types")]
+impl<'a> ReadableRef<'a> for primitive_types::U256 {
+    fn read_ref(buf: &'a [u8]) -> io::Result<(Self, usize)> {
+        let (bytes, _) = split_at_checked(buf, 32)?;
+        Ok((Self::from_big_endian(bytes), 32))
+    }
+}
+
+#[cfg(feature = "primitive-types")]
+impl Writeable for primitive_types::U256 {
+    fn write<W>(&self, writer: &mut W) -> io::Result<()>
+    where
+        W: io::Write,
+    {
+        let mut bytes = [0u8; 32];
+        self.to_big_endian(&mut bytes);
+        writer.write_all(&bytes)
+    }
+
+    fn written_size(&self) -> usize {
+        32
+    }
+}
+
+#[cfg(all(
+    test,
+    any(feature = "solana", feature = "ruint", feature = "primitive-types")
+))]
+mod test {
+    use super::*;
+    use hex_literal::hex;
+
+    #[cfg(feature = "solana")]
+    #[test]
+    fn pubkey_read_write() {
+        let encoded = hex!("c6fa7af3bedbad3a3d65f36aabc97431b1bbe4c2d2f6e0e47ca60203452f5d61");
+
+        let pubkey = solana_program::pubkey::Pubkey::read(&mut &encoded[..]).unwrap();
+        assert_eq!(pubkey.to_bytes(), encoded);
+        assert_eq!(
+            solana_program::pubkey::Pubkey::read_ref(&encoded).unwrap(),
+            (pubkey, 32)
+        );
+
+        let mut written = Vec::with_capacity(pubkey.written_size());
+        pubkey.write(&mut written).unwrap();
+        assert_eq!(written, encoded);
+    }
+
+    #[cfg(feature = "ruint")]
+    #[test]
+    fn ruint_read_write() {
+        let encoded = hex!("000000000000000000000000000000000000000000000000000000e8d4a51000");
+
+        let amount = ruint::aliases::U256::read(&mut &encoded[..]).unwrap();
+        assert_eq!(amount, ruint::aliases::U256::from(1_000_000_000_000u64));
+        assert_eq!(
+            ruint::aliases::U256::read_ref(&encoded).unwrap(),
+            (amount, 32)
+        );
+
+        let mut written = Vec::with_capacity(amount.written_size());
+        amount.write(&mut written).unwrap();
+        assert_eq!(written, encoded);
+    }
+
+    #[cfg(feature = "ruint")]
+    #[test]
+    fn ruint_partial_byte() {
+        type U12 = ruint::Uint<12, 1>;
+
+        let value = U12::read(&mut &hex!("0abc")[..]).unwrap();
+        assert_eq!(value, U12::from(0xabcu64));
+        assert_eq!(value.written_size(), 2);
+
+        // The 4 unused high bits must be zero.
+        let err = U12::read(&mut &hex!("1abc")[..]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[cfg(feature = "primitive-types")]
+    #[test]
+    fn primitive_types_u256_read_write() {
+        let encoded = hex!("000000000000000000000000000000000000000000000000000000e8d4a51000");
+
+        let amount = primitive_types::U256::read(&mut &encoded[..]).unwrap();
+        assert_eq!(amount, primitive_types::U256::from(1_000_000_000_000u64));
+        assert_eq!(
+            primitive_types::U256::read_ref(&encoded).unwrap(),
+            (amount, 32)
+        );
+
+        let mut written = Vec::with_capacity(amount.written_size());
+        amount.write(&mut written).unwrap();
+        assert_eq!(written, encoded);
+    }
+}
@@ -0,0 +1,183 @@
+//! I/O primitives used by [`Readable`](crate::Readable) and [`Writeable`](crate::Writeable).
+//!
+//! With the `std` feature (enabled by default), these are re-exports of [`std::io`]. Without it,
+//! this module provides a minimal equivalent so that payloads can be encoded and decoded in
+//! `no_std` environments.
+
+#[cfg(feature = "std")]
+pub use std::io::{Error, ErrorKind, Read, Result, Write};
+
+#[cfg(not(feature = "std"))]
+pub use no_std::{Error, ErrorKind, Read, Result, Write};
+
+#[cfg(not(feature = "std"))]
+mod no_std {
+    #[cfg(feature = "alloc")]
+    use alloc::boxed::Box;
+    use core::fmt;
+
+    /// Subset of [`std::io::ErrorKind`] produced by this crate.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    #[non_exhaustive]
+    pub enum ErrorKind {
+        InvalidData,
+        UnexpectedEof,
+        WriteZero,
+        Other,
+    }
+
+    impl ErrorKind {
+        fn as_str(&self) -> &'static str {
+            match self {
+                ErrorKind::InvalidData => "invalid data",
+                ErrorKind::UnexpectedEof => "unexpected end of file",
+                ErrorKind::WriteZero => "write zero",
+                ErrorKind::Other => "other error",
+            }
+        }
+    }
+
+    /// Error type mirroring [`std::io::Error`], which carries a kind and a static message (or,
+    /// with `alloc`, a [`DecodeError`](crate::DecodeError)).
+    #[derive(Debug)]
+    pub struct Error {
+        kind: ErrorKind,
+        repr: Repr,
+    }
+
+    #[derive(Debug)]
+    enum Repr {
+        Message(&'static str),
+        #[cfg(feature = "alloc")]
+        Decode(Box<crate::DecodeError>),
+    }
+
+    impl Error {
+        pub fn new(kind: ErrorKind, message: &'static str) -> Self {
+            Self {
+                kind,
+                repr: Repr::Message(message),
+            }
+        }
+
+        pub fn kind(&self) -> ErrorKind {
+            self.kind
+        }
+
+        #[cfg(feature = "alloc")]
+        pub(crate) fn from_decode_error(kind: ErrorKind, err: Box<crate::DecodeError>) -> Self {
+            Self {
+                kind,
+                repr: Repr::Decode(err),
+            }
+        }
+
+        #[cfg(feature = "alloc")]
+        pub(crate) fn into_decode_error(
+            self,
+        ) -> core::result::Result<Box<crate::DecodeError>, Self> {
+            match self.repr {
+                Repr::Decode(err) => Ok(err),
+                repr => Err(Self { repr, ..self }),
+            }
+        }
+    }
+
+    impl From<ErrorKind> for Error {
+        fn from(kind: ErrorKind) -> Self {
+            Self::new(kind, kind.as_str())
+        }
+    }
+
+    impl fmt::Display for Error {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match &self.repr {
+                Repr::Message(message) => f.write_str(message),
+                #[cfg(feature = "alloc")]
+                Repr::Decode(err) => write!(f, "{err}"),
+            }
+        }
+    }
+
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    /// Minimal counterpart to [`std::io::Read`].
+    pub trait Read {
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize>;
+
+        fn read_exact(&mut self, mut buf: &mut [u8]) -> Result<()> {
+            while !buf.is_empty() {
+                match self.read(buf)? {
+                    0 => {
+                        return Err(Error::new(
+                            ErrorKind::UnexpectedEof,
+                            "failed to fill whole buffer",
+                        ))
+                    }
+                    n => buf = &mut buf[n..],
+                }
+            }
+            Ok(())
+        }
+    }
+
+    /// Minimal counterpart to [`std::io::Write`].
+    pub trait Write {
+        fn write(&mut self, buf: &[u8]) -> Result<usize>;
+
+        fn write_all(&mut self, mut buf: &[u8]) -> Result<()> {
+            while !buf.is_empty() {
+                match self.write(buf)? {
+                    0 => {
+                        return Err(Error::new(
+                            ErrorKind::WriteZero,
+                            "failed to write whole buffer",
+                        ))
+                    }
+                    n => buf = &buf[n..],
+                }
+            }
+            Ok(())
+        }
+    }
+
+    impl<R: Read + ?Sized> Read for &mut R {
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+            (**self).read(buf)
+        }
+    }
+
+    impl<W: Write + ?Sized> Write for &mut W {
+        fn write(&mut self, buf: &[u8]) -> Result<usize> {
+            (**self).write(buf)
+        }
+    }
+
+    impl Read for &[u8] {
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+            let len = core::cmp::min(buf.len(), self.len());
+            let (head, tail) = self.split_at(len);
+            buf[..len].copy_from_slice(head);
+            *self = tail;
+            Ok(len)
+        }
+    }
+
+    impl Write for &mut [u8] {
+        fn write(&mut self, buf: &[u8]) -> Result<usize> {
+            let len = core::cmp::min(buf.len(), self.len());
+            let (head, tail) = core::mem::take(self).split_at_mut(len);
+            head.copy_from_slice(&buf[..len]);
+            *self = tail;
+            Ok(len)
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    impl Write for alloc::vec::Vec<u8> {
+        fn write(&mut self, buf: &[u8]) -> Result<usize> {
+            self.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+    }
+}
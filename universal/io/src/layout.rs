@@ -0,0 +1,611 @@
+//! Machine-readable descriptions of encodings, e.g. to generate or check the binary layout
+//! definitions of SDKs in other languages against the Rust types.
+//!
+//! ```
+//! use wormhole_io::{Layout, WriteableBytes};
+//!
+//! let layout = <(u16, WriteableBytes<u8>)>::layout();
+//! assert_eq!(layout.size(), None);
+//! assert_eq!(
+//!     layout.to_json(),
+//!     r#"{"binary":"struct","layout":[{"name":"0","offset":0,"binary":"uint","size":2,"endianness":"big"},{"name":"1","offset":2,"binary":"bytes","lengthSize":1,"lengthEndianness":"big"}]}"#
+//! );
+//! ```
+
+use alloc::{boxed::Box, string::String, vec::Vec};
+use core::fmt::Write as _;
+
+use crate::{
+    Extensions, FixedString, TypePrefixedPayload, WriteableBytes, WriteableString, WriteableVec,
+};
+use crate::{Readable, Writeable};
+
+/// Describes how [`Writeable`] encodes a type (and [`Readable`] decodes it).
+pub trait Layout {
+    fn layout() -> LayoutItem;
+}
+
+/// Byte order of an integer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Endian {
+    Big,
+    Little,
+}
+
+/// Size and byte order of an integer, length prefix or enum discriminant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IntEncoding {
+    pub size: usize,
+    pub endian: Endian,
+}
+
+impl IntEncoding {
+    pub const fn big(size: usize) -> Self {
+        Self {
+            size,
+            endian: Endian::Big,
+        }
+    }
+}
+
+/// Encoding of a value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum LayoutItem {
+    Uint(IntEncoding),
+    Int(IntEncoding),
+    /// One byte, either 0 or 1.
+    Bool,
+    /// Exactly `size` bytes.
+    Bytes {
+        size: usize,
+    },
+    /// Exactly these bytes, e.g. the type prefix of a payload.
+    Constant {
+        bytes: Vec<u8>,
+    },
+    /// String padded with trailing zeros to exactly `size` bytes.
+    FixedString {
+        size: usize,
+    },
+    /// Bytes preceded by their length.
+    LengthPrefixedBytes {
+        len: IntEncoding,
+    },
+    /// UTF-8 string preceded by its length in bytes.
+    LengthPrefixedString {
+        len: IntEncoding,
+    },
+    /// All remaining bytes of the input.
+    Remainder,
+    /// Exactly `len` elements.
+    Array {
+        len: usize,
+        item: Box<LayoutItem>,
+    },
+    /// Elements preceded by their number.
+    Vec {
+        len: IntEncoding,
+        item: Box<LayoutItem>,
+    },
+    /// A bool, followed by the value if it is 1.
+    Option(Box<LayoutItem>),
+    /// Fields in order. Tuples have no name.
+    Struct {
+        name: Option<&'static str>,
+        fields: Vec<Field>,
+    },
+    /// A discriminant, followed by the fields of the variant it identifies.
+    Enum {
+        name: &'static str,
+        discriminant: IntEncoding,
+        variants: Vec<Variant>,
+    },
+}
+
+/// Field of a struct or enum variant.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Field {
+    pub name: &'static str,
+    /// Number of zero bytes preceding the field.
+    pub padding: usize,
+    /// Offset of the field from the start of the struct (or enum, including its discriminant).
+    /// Only known if every preceding field has a fixed size.
+    pub offset: Option<usize>,
+    pub item: LayoutItem,
+}
+
+/// Variant of an enum.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Variant {
+    pub name: &'static str,
+    pub discriminant: i128,
+    pub fields: Vec<Field>,
+}
+
+impl LayoutItem {
+    pub fn uint(size: usize) -> Self {
+        Self::Uint(IntEncoding::big(size))
+    }
+
+    pub fn bytes(size: usize) -> Self {
+        Self::Bytes { size }
+    }
+
+    pub fn structure(name: &'static str, fields: impl IntoIterator<Item = Field>) -> Self {
+        Self::Struct {
+            name: Some(name),
+            fields: Field::sequence(0, fields),
+        }
+    }
+
+    pub fn enumeration(
+        name: &'static str,
+        discriminant: IntEncoding,
+        variants: impl IntoIterator<Item = Variant>,
+    ) -> Self {
+        let variants = variants
+            .into_iter()
+            .map(|variant| Variant {
+                fields: Field::sequence(discriminant.size, variant.fields),
+                ..variant
+            })
+            .collect();
+
+        Self::Enum {
+            name,
+            discriminant,
+            variants,
+        }
+    }
+
+    /// Precede the value with constant bytes, as a `type` field before the fields of a struct.
+    pub fn prefixed(self, prefix: &[u8]) -> Self {
+        let prefix = Field::new(
+            "type",
+            Self::Constant {
+                bytes: prefix.to_vec(),
+            },
+        );
+        match self {
+            Self::Struct { name, fields } => Self::Struct {
+                name,
+                fields: Field::sequence(0, core::iter::once(prefix).chain(fields)),
+            },
+            layout => Self::Struct {
+                name: None,
+                fields: Field::sequence(0, [prefix, Field::new("payload", layout)]),
+            },
+        }
+    }
+
+    /// Encoded size, if it is the same for every value.
+    pub fn size(&self) -> Option<usize> {
+        match self {
+            Self::Uint(int) | Self::Int(int) => Some(int.size),
+            Self::Bool => Some(1),
+            Self::Bytes { size } | Self::FixedString { size } => Some(*size),
+            Self::Constant { bytes } => Some(bytes.len()),
+            Self::Array { len, item } => item.size().map(|size| len * size),
+            Self::Struct { fields, .. } => fields_size(0, fields),
+            Self::Enum {
+                discriminant,
+                variants,
+                ..
+            } => {
+                // Fixed only if every variant has the same size.
+                let mut sizes = variants
+                    .iter()
+                    .map(|variant| fields_size(discriminant.size, &variant.fields));
+                let first = sizes.next().unwrap_or(Some(discriminant.size))?;
+                sizes.all(|size| size == Some(first)).then_some(first)
+            }
+            Self::LengthPrefixedBytes { .. }
+            | Self::LengthPrefixedString { .. }
+            | Self::Remainder
+            | Self::Vec { .. }
+            | Self::Option(_) => None,
+        }
+    }
+
+    /// Serialize as JSON, in a form close to the binary layouts of the TypeScript SDK. Objects
+    /// have a `binary` key naming the kind of item, e.g.
+    /// `{"binary":"uint","size":2,"endianness":"big"}`. Struct fields and enum variants are
+    /// listed under `layout`, with a `name` and, where known, an `offset`.
+    pub fn to_json(&self) -> String {
+        let mut out = String::new();
+        self.write_json(&mut out, None);
+        out
+    }
+
+    fn write_json(&self, out: &mut String, field: Option<&Field>) {
+        out.push('{');
+        if let Some(field) = field {
+            let _ = write!(out, r#""name":"{}","#, field.name);
+            if let Some(offset) = field.offset {
+                let _ = write!(out, r#""offset":{offset},"#);
+            }
+            if field.padding > 0 {
+                let _ = write!(out, r#""padding":{},"#, field.padding);
+            }
+        }
+
+        match self {
+            Self::Uint(int) => write_int(out, "uint", int),
+            Self::Int(int) => write_int(out, "int", int),
+            Self::Bool => out.push_str(r#""binary":"bool","size":1"#),
+            Self::Bytes { size } => {
+                let _ = write!(out, r#""binary":"bytes","size":{size}"#);
+            }
+            Self::Constant { bytes } => {
+                let _ = write!(
+                    out,
+                    r#""binary":"bytes","size":{},"custom":"0x"#,
+                    bytes.len()
+                );
+                bytes.iter().for_each(|b| {
+                    let _ = write!(out, "{b:02x}");
+                });
+                out.push('"');
+            }
+            Self::FixedString { size } => {
+                let _ = write!(out, r#""binary":"string","size":{size}"#);
+            }
+            Self::LengthPrefixedBytes { len } => write_prefixed(out, "bytes", len),
+            Self::LengthPrefixedString { len } => write_prefixed(out, "string", len),
+            Self::Remainder => out.push_str(r#""binary":"bytes""#),
+            Self::Array { len, item } => {
+                let _ = write!(out, r#""binary":"array","length":{len},"layout":"#);
+                item.write_json(out, None);
+            }
+            Self::Vec { len, item } => {
+                write_prefixed(out, "array", len);
+                out.push_str(r#","layout":"#);
+                item.write_json(out, None);
+            }
+            Self::Option(item) => {
+                out.push_str(r#""binary":"option","layout":"#);
+                item.write_json(out, None);
+            }
+            Self::Struct { name, fields } => {
+                out.push_str(r#""binary":"struct","#);
+                if let Some(name) = name {
+                    let _ = write!(out, r#""type":"{name}","#);
+                }
+                out.push_str(r#""layout":"#);
+                write_fields(out, fields);
+            }
+            Self::Enum {
+                name,
+                discriminant,
+                variants,
+            } => {
+                let _ = write!(
+                    out,
+                    r#""binary":"switch","type":"{name}","idSize":{},"idEndianness":"{}","layouts":["#,
+                    discriminant.size,
+                    endian_str(discriminant.endian),
+                );
+                for (i, variant) in variants.iter().enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    let _ = write!(
+                        out,
+                        r#"{{"name":"{}","id":{},"layout":"#,
+                        variant.name, variant.discriminant
+                    );
+                    write_fields(out, &variant.fields);
+                    out.push('}');
+                }
+                out.push(']');
+            }
+        }
+        out.push('}');
+    }
+}
+
+impl Field {
+    pub fn new(name: &'static str, item: LayoutItem) -> Self {
+        Self {
+            name,
+            padding: 0,
+            offset: None,
+            item,
+        }
+    }
+
+    /// Precede the field with `padding` zero bytes.
+    pub fn padded(self, padding: usize) -> Self {
+        Self { padding, ..self }
+    }
+
+    /// Assign offsets to fields laid out one after another, starting at `start`.
+    fn sequence(start: usize, fields: impl IntoIterator<Item = Field>) -> Vec<Field> {
+        let mut offset = Some(start);
+        fields
+            .into_iter()
+            .map(|field| {
+                let field_offset = offset.map(|offset| offset + field.padding);
+                offset = field_offset.zip(field.item.size()).map(|(a, b)| a + b);
+                Field {
+                    offset: field_offset,
+                    ..field
+                }
+            })
+            .collect()
+    }
+}
+
+impl Variant {
+    pub fn new(
+        name: &'static str,
+        discriminant: i128,
+        fields: impl IntoIterator<Item = Field>,
+    ) -> Self {
+        Self {
+            name,
+            discriminant,
+            fields: fields.into_iter().collect(),
+        }
+    }
+}
+
+/// End of the last field, or `start` if there are none.
+fn fields_size(start: usize, fields: &[Field]) -> Option<usize> {
+    match fields.last() {
+        Some(last) => last.offset.zip(last.item.size()).map(|(a, b)| a + b),
+        None => Some(start),
+    }
+}
+
+fn endian_str(endian: Endian) -> &'static str {
+    match endian {
+        Endian::Big => "big",
+        Endian::Little => "little",
+    }
+}
+
+fn write_int(out: &mut String, binary: &str, int: &IntEncoding) {
+    let _ = write!(
+        out,
+        r#""binary":"{binary}","size":{},"endianness":"{}""#,
+        int.size,
+        endian_str(int.endian)
+    );
+}
+
+fn write_prefixed(out: &mut String, binary: &str, len: &IntEncoding) {
+    let _ = write!(
+        out,
+        r#""binary":"{binary}","lengthSize":{},"lengthEndianness":"{}""#,
+        len.size,
+        endian_str(len.endian)
+    );
+}
+
+fn write_fields(out: &mut String, fields: &[Field]) {
+    out.push('[');
+    for (i, field) in fields.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        field.item.write_json(out, Some(field));
+    }
+    out.push(']');
+}
+
+/// Layout of a payload including its type prefix. See [`TypePrefixedPayload::read_payload`].
+pub fn payload_layout<P, const N: usize>() -> LayoutItem
+where
+    P: TypePrefixedPayload<N> + Layout,
+{
+    match P::TYPE {
+        Some(prefix) => P::layout().prefixed(&prefix),
+        None => P::layout(),
+    }
+}
+
+macro_rules! impl_for_int {
+    ($variant:ident, $($type:ty)*) => {
+        $(
+            impl Layout for $type {
+                fn layout() -> LayoutItem {
+                    LayoutItem::$variant(IntEncoding::big(core::mem::size_of::<$type>()))
+                }
+            }
+        )*
+    };
+}
+
+impl_for_int!(Uint, u8 u16 u32 u64 u128);
+impl_for_int!(Int, i8 i16 i32 i64 i128);
+
+impl Layout for bool {
+    fn layout() -> LayoutItem {
+        LayoutItem::Bool
+    }
+}
+
+impl<const N: usize> Layout for [u8; N] {
+    fn layout() -> LayoutItem {
+        LayoutItem::bytes(N)
+    }
+}
+
+macro_rules! impl_for_int_array {
+    ($($type:ty)*) => {
+        $(
+            impl<const N: usize> Layout for [$type; N] {
+                fn layout() -> LayoutItem {
+                    LayoutItem::Array {
+                        len: N,
+                        item: Box::new(<$type>::layout()),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_for_int_array!(u16 u32 u64 u128 i8 i16 i32 i64 i128);
+
+impl<const N: usize> Layout for FixedString<N> {
+    fn layout() -> LayoutItem {
+        LayoutItem::FixedString { size: N }
+    }
+}
+
+impl<T: Layout> Layout for Option<T> {
+    fn layout() -> LayoutItem {
+        LayoutItem::Option(Box::new(T::layout()))
+    }
+}
+
+impl<T: Layout> Layout for Box<T> {
+    fn layout() -> LayoutItem {
+        T::layout()
+    }
+}
+
+macro_rules! impl_for_tuple {
+    ($($name:ident $index:literal)+) => {
+        impl<$($name: Layout),+> Layout for ($($name,)+) {
+            fn layout() -> LayoutItem {
+                LayoutItem::Struct {
+                    name: None,
+                    fields: Field::sequence(0, [$(Field::new($index, $name::layout())),+]),
+                }
+            }
+        }
+    };
+}
+
+impl_for_tuple!(A "0");
+impl_for_tuple!(A "0" B "1");
+impl_for_tuple!(A "0" B "1" C "2");
+impl_for_tuple!(A "0" B "1" C "2" D "3");
+impl_for_tuple!(A "0" B "1" C "2" D "3" E "4");
+impl_for_tuple!(A "0" B "1" C "2" D "3" E "4" F "5");
+impl_for_tuple!(A "0" B "1" C "2" D "3" E "4" F "5" G "6");
+impl_for_tuple!(A "0" B "1" C "2" D "3" E "4" F "5" G "6" H "7");
+
+impl<L> Layout for WriteableBytes<L>
+where
+    u32: From<L>,
+    L: Sized + Readable + Writeable + TryFrom<usize>,
+{
+    fn layout() -> LayoutItem {
+        LayoutItem::LengthPrefixedBytes {
+            len: IntEncoding::big(core::mem::size_of::<L>()),
+        }
+    }
+}
+
+impl<L> Layout for WriteableString<L>
+where
+    u32: From<L>,
+    L: Sized + Readable + Writeable + TryFrom<usize>,
+{
+    fn layout() -> LayoutItem {
+        LayoutItem::LengthPrefixedString {
+            len: IntEncoding::big(core::mem::size_of::<L>()),
+        }
+    }
+}
+
+impl<L, T> Layout for WriteableVec<L, T>
+where
+    u32: From<L>,
+    L: Sized + Readable + Writeable + TryFrom<usize>,
+    T: Layout,
+{
+    fn layout() -> LayoutItem {
+        LayoutItem::Vec {
+            len: IntEncoding::big(core::mem::size_of::<L>()),
+            item: Box::new(T::layout()),
+        }
+    }
+}
+
+impl Layout for Extensions {
+    fn layout() -> LayoutItem {
+        LayoutItem::Remainder
+    }
+}
+
+#[cfg(feature = "solana")]
+impl Layout for solana_program::pubkey::Pubkey {
+    fn layout() -> LayoutItem {
+        LayoutItem::bytes(32)
+    }
+}
+
+#[cfg(feature = "ruint")]
+impl<const BITS: usize, const LIMBS: usize> Layout for ruint::Uint<BITS, LIMBS> {
+    fn layout() -> LayoutItem {
+        LayoutItem::uint(Self::BYTES)
+    }
+}
+
+#[cfg(feature = "primitive-types")]
+impl Layout for primitive_types::U256 {
+    fn layout() -> LayoutItem {
+        LayoutItem::uint(32)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn primitive_layouts() {
+        assert_eq!(u16::layout(), LayoutItem::uint(2));
+        assert_eq!(i64::layout(), LayoutItem::Int(IntEncoding::big(8)));
+        assert_eq!(<[u8; 32]>::layout().size(), Some(32));
+        assert_eq!(<[u16; 3]>::layout().size(), Some(6));
+        assert_eq!(FixedString::<32>::layout().size(), Some(32));
+        assert_eq!(Option::<u8>::layout().size(), None);
+        assert_eq!(
+            WriteableBytes::<u32>::layout(),
+            LayoutItem::LengthPrefixedBytes {
+                len: IntEncoding::big(4)
+            }
+        );
+    }
+
+    #[test]
+    fn offsets_after_variable_field() {
+        let layout = <(u8, WriteableVec<u8, [u8; 20]>, u32)>::layout();
+        let LayoutItem::Struct { fields, .. } = &layout else {
+            panic!("expected a struct");
+        };
+
+        let offsets = fields.iter().map(|field| field.offset).collect::<Vec<_>>();
+        assert_eq!(offsets, [Some(0), Some(1), None]);
+        assert_eq!(layout.size(), None);
+    }
+
+    #[test]
+    fn enum_to_json() {
+        let layout = LayoutItem::enumeration(
+            "Action",
+            IntEncoding::big(1),
+            [
+                Variant::new("Pause", 1, []),
+                Variant::new("SetFee", 2, [Field::new("fee", LayoutItem::uint(8))]),
+            ],
+        );
+        assert_eq!(layout.size(), None);
+        assert_eq!(
+            layout.to_json(),
+            concat!(
+                r#"{"binary":"switch","type":"Action","idSize":1,"idEndianness":"big","layouts":["#,
+                r#"{"name":"Pause","id":1,"layout":[]},"#,
+                r#"{"name":"SetFee","id":2,"layout":[{"name":"fee","offset":1,"binary":"uint","size":8,"endianness":"big"}]}"#,
+                r#"]}"#
+            )
+        );
+    }
+}
@@ -0,0 +1,412 @@
+//! Little-endian counterparts to [`Readable`] and [`Writeable`].
+//!
+//! Wormhole wire formats are big-endian, but Solana account layouts (e.g. a posted VAA or the
+//! Core Bridge config) are little-endian. [`LeReadable`] and [`LeWriteable`] encode integers and
+//! length prefixes in little-endian, and everything else the same way as their big-endian
+//! counterparts.
+
+#[cfg(feature = "alloc")]
+use alloc::{boxed::Box, string::String};
+
+#[cfg(feature = "alloc")]
+use crate::{bounded, error, WriteableBytes, WriteableString, WriteableVec};
+use crate::{io, FixedString, Readable, Writeable};
+
+/// Little-endian counterpart to [`Readable`].
+pub trait LeReadable {
+    fn read_le<R>(reader: &mut R) -> io::Result<Self>
+    where
+        Self: Sized,
+        R: io::Read;
+}
+
+/// Little-endian counterpart to [`Writeable`].
+pub trait LeWriteable {
+    fn write_le<W>(&self, writer: &mut W) -> io::Result<()>
+    where
+        W: io::Write;
+
+    /// Returns the number of bytes [write_le](LeWriteable::write_le) will produce.
+    fn written_size_le(&self) -> usize;
+}
+
+/// Types whose encoding does not depend on byte order.
+macro_rules! impl_for_bytes {
+    ([$($generics:tt)*] $type:ty) => {
+        impl<$($generics)*> LeReadable for $type {
+            fn read_le<R>(reader: &mut R) -> io::Result<Self>
+            where
+                Self: Sized,
+                R: io::Read,
+            {
+                <$type as Readable>::read(reader)
+            }
+        }
+
+        impl<$($generics)*> LeWriteable for $type {
+            fn write_le<W>(&self, writer: &mut W) -> io::Result<()>
+            where
+                W: io::Write,
+            {
+                Writeable::write(self, writer)
+            }
+
+            fn written_size_le(&self) -> usize {
+                Writeable::written_size(self)
+            }
+        }
+    };
+    ($type:ty) => {
+        impl_for_bytes!([] $type);
+    };
+}
+
+impl_for_bytes!(u8);
+impl_for_bytes!(i8);
+impl_for_bytes!(bool);
+impl_for_bytes!([const N: usize] [u8; N]);
+impl_for_bytes!([const N: usize] FixedString<N>);
+
+#[cfg(feature = "solana")]
+impl_for_bytes!(solana_program::pubkey::Pubkey);
+
+macro_rules! impl_for_int {
+    ($type:ty) => {
+        impl LeReadable for $type {
+            fn read_le<R>(reader: &mut R) -> io::Result<Self>
+            where
+                Self: Sized,
+                R: io::Read,
+            {
+                let mut buf = [0; core::mem::size_of::<$type>()];
+                reader.read_exact(&mut buf)?;
+                Ok(Self::from_le_bytes(buf))
+            }
+        }
+
+        impl LeWriteable for $type {
+            fn write_le<W>(&self, writer: &mut W) -> io::Result<()>
+            where
+                W: io::Write,
+            {
+                writer.write_all(&self.to_le_bytes())
+            }
+
+            fn written_size_le(&self) -> usize {
+                core::mem::size_of::<$type>()
+            }
+        }
+
+        impl<const N: usize> LeReadable for [$type; N] {
+            fn read_le<R>(reader: &mut R) -> io::Result<Self>
+            where
+                Self: Sized,
+                R: io::Read,
+            {
+                let mut out = [Default::default(); N];
+                for elem in out.iter_mut() {
+                    *elem = <$type>::read_le(reader)?;
+                }
+                Ok(out)
+            }
+        }
+
+        impl<const N: usize> LeWriteable for [$type; N] {
+            fn write_le<W>(&self, writer: &mut W) -> io::Result<()>
+            where
+                W: io::Write,
+            {
+                self.iter().try_for_each(|elem| elem.write_le(writer))
+            }
+
+            fn written_size_le(&self) -> usize {
+                N * core::mem::size_of::<$type>()
+            }
+        }
+    };
+}
+
+impl_for_int!(u16);
+impl_for_int!(u32);
+impl_for_int!(u64);
+impl_for_int!(u128);
+
+impl_for_int!(i16);
+impl_for_int!(i32);
+impl_for_int!(i64);
+impl_for_int!(i128);
+
+impl<T> LeReadable for Option<T>
+where
+    T: LeReadable,
+{
+    fn read_le<R>(reader: &mut R) -> io::Result<Self>
+    where
+        Self: Sized,
+        R: io::Read,
+    {
+        match bool::read_le(reader)? {
+            true => Ok(Some(T::read_le(reader)?)),
+            false => Ok(None),
+        }
+    }
+}
+
+impl<T> LeWriteable for Option<T>
+where
+    T: LeWriteable,
+{
+    fn write_le<W>(&self, writer: &mut W) -> io::Result<()>
+    where
+        W: io::Write,
+    {
+        match self {
+            Some(value) => {
+                true.write_le(writer)?;
+                value.write_le(writer)
+            }
+            None => false.write_le(writer),
+        }
+    }
+
+    fn written_size_le(&self) -> usize {
+        match self {
+            Some(value) => 1 + value.written_size_le(),
+            None => 1,
+        }
+    }
+}
+
+macro_rules! impl_for_tuple {
+    ($($name:ident)+) => {
+        impl<$($name: LeReadable),+> LeReadable for ($($name,)+) {
+            fn read_le<R>(reader: &mut R) -> io::Result<Self>
+            where
+                Self: Sized,
+                R: io::Read,
+            {
+                Ok(($($name::read_le(reader)?,)+))
+            }
+        }
+
+        impl<$($name: LeWriteable),+> LeWriteable for ($($name,)+) {
+            #[allow(non_snake_case)]
+            fn write_le<W>(&self, writer: &mut W) -> io::Result<()>
+            where
+                W: io::Write,
+            {
+                let ($($name,)+) = self;
+                $($name.write_le(writer)?;)+
+                Ok(())
+            }
+
+            #[allow(non_snake_case)]
+            fn written_size_le(&self) -> usize {
+                let ($($name,)+) = self;
+                0 $(+ $name.written_size_le())+
+            }
+        }
+    };
+}
+
+impl_for_tuple!(A);
+impl_for_tuple!(A B);
+impl_for_tuple!(A B C);
+impl_for_tuple!(A B C D);
+impl_for_tuple!(A B C D E);
+impl_for_tuple!(A B C D E F);
+impl_for_tuple!(A B C D E F G);
+impl_for_tuple!(A B C D E F G H);
+
+#[cfg(feature = "alloc")]
+impl<T> LeReadable for Box<T>
+where
+    T: LeReadable,
+{
+    fn read_le<R>(reader: &mut R) -> io::Result<Self>
+    where
+        Self: Sized,
+        R: io::Read,
+    {
+        T::read_le(reader).map(Box::new)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> LeWriteable for Box<T>
+where
+    T: LeWriteable,
+{
+    fn write_le<W>(&self, writer: &mut W) -> io::Result<()>
+    where
+        W: io::Write,
+    {
+        (**self).write_le(writer)
+    }
+
+    fn written_size_le(&self) -> usize {
+        (**self).written_size_le()
+    }
+}
+
+#[cfg(feature = "alloc")]
+fn read_len_le<L, R>(reader: &mut R) -> io::Result<usize>
+where
+    u32: From<L>,
+    L: LeReadable,
+    R: io::Read,
+{
+    usize::try_from(u32::from(L::read_le(reader)?)).map_err(|_| error::length_overflow())
+}
+
+#[cfg(feature = "alloc")]
+impl<L> LeReadable for WriteableBytes<L>
+where
+    u32: From<L>,
+    L: Sized + Readable + Writeable + LeReadable + TryFrom<usize>,
+{
+    fn read_le<R>(reader: &mut R) -> io::Result<Self>
+    where
+        Self: Sized,
+        R: io::Read,
+    {
+        let len = read_len_le::<L, _>(reader)?;
+        bounded::read_bytes(reader, len).map(Self::new)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<L> LeWriteable for WriteableBytes<L>
+where
+    u32: From<L>,
+    L: Sized + Readable + Writeable + LeWriteable + TryFrom<usize>,
+{
+    fn write_le<W>(&self, writer: &mut W) -> io::Result<()>
+    where
+        W: io::Write,
+    {
+        self.try_encoded_len()?.write_le(writer)?;
+        writer.write_all(self.as_slice())
+    }
+
+    fn written_size_le(&self) -> usize {
+        core::mem::size_of::<L>() + self.len()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<L> LeReadable for WriteableString<L>
+where
+    u32: From<L>,
+    L: Sized + Readable + Writeable + LeReadable + TryFrom<usize>,
+{
+    fn read_le<R>(reader: &mut R) -> io::Result<Self>
+    where
+        Self: Sized,
+        R: io::Read,
+    {
+        let len = read_len_le::<L, _>(reader)?;
+        String::from_utf8(bounded::read_bytes(reader, len)?)
+            .map(Self::new)
+            .map_err(|_| error::invalid_utf8())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<L> LeWriteable for WriteableString<L>
+where
+    u32: From<L>,
+    L: Sized + Readable + Writeable + LeWriteable + TryFrom<usize>,
+{
+    fn write_le<W>(&self, writer: &mut W) -> io::Result<()>
+    where
+        W: io::Write,
+    {
+        self.try_encoded_len()?.write_le(writer)?;
+        writer.write_all(self.as_bytes())
+    }
+
+    fn written_size_le(&self) -> usize {
+        core::mem::size_of::<L>() + self.len()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<L, T> LeReadable for WriteableVec<L, T>
+where
+    u32: From<L>,
+    L: Sized + Readable + Writeable + LeReadable + TryFrom<usize>,
+    T: LeReadable,
+{
+    fn read_le<R>(reader: &mut R) -> io::Result<Self>
+    where
+        Self: Sized,
+        R: io::Read,
+    {
+        let len = read_len_le::<L, _>(reader)?;
+        bounded::read_elements(reader, len, |reader| T::read_le(reader)).map(Self::new)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<L, T> LeWriteable for WriteableVec<L, T>
+where
+    u32: From<L>,
+    L: Sized + Readable + Writeable + LeWriteable + TryFrom<usize>,
+    T: LeWriteable,
+{
+    fn write_le<W>(&self, writer: &mut W) -> io::Result<()>
+    where
+        W: io::Write,
+    {
+        self.try_encoded_len()?.write_le(writer)?;
+        self.iter().try_for_each(|elem| elem.write_le(writer))
+    }
+
+    fn written_size_le(&self) -> usize {
+        core::mem::size_of::<L>() + self.iter().map(LeWriteable::written_size_le).sum::<usize>()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use hex_literal::hex;
+
+    #[test]
+    fn int_read_write_le() {
+        let value = 0x0102_0304u32;
+        assert_eq!(value.written_size_le(), 4);
+
+        let mut encoded = Vec::with_capacity(value.written_size_le());
+        value.write_le(&mut encoded).unwrap();
+        assert_eq!(encoded, hex!("04030201"));
+
+        assert_eq!(u32::read_le(&mut encoded.as_slice()).unwrap(), value);
+    }
+
+    #[test]
+    fn bytes_read_write_le() {
+        let bytes = WriteableBytes::<u32>::new(b"All your base".to_vec());
+
+        let mut encoded = Vec::with_capacity(bytes.written_size_le());
+        bytes.write_le(&mut encoded).unwrap();
+        assert_eq!(encoded, hex!("0d000000416c6c20796f75722062617365"));
+
+        let decoded = WriteableBytes::<u32>::read_le(&mut encoded.as_slice()).unwrap();
+        assert_eq!(decoded, bytes);
+    }
+
+    #[test]
+    fn vec_read_write_le() {
+        let guardians = WriteableVec::<u16, [u16; 2]>::new(vec![[1, 2], [3, 4]]);
+        assert_eq!(guardians.written_size_le(), 10);
+
+        let mut encoded = Vec::with_capacity(guardians.written_size_le());
+        guardians.write_le(&mut encoded).unwrap();
+        assert_eq!(encoded, hex!("02000100020003000400"));
+
+        let decoded = WriteableVec::<u16, [u16; 2]>::read_le(&mut encoded.as_slice()).unwrap();
+        assert_eq!(decoded, guardians);
+    }
+}
@@ -1,5 +1,93 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+// Allows the derive macros to refer to `::wormhole_io` from within this crate.
+extern crate self as wormhole_io;
+
+pub mod io;
+
+#[cfg(feature = "arbitrary")]
+mod arbitrary;
+
+#[cfg(feature = "async")]
+mod async_io;
+#[cfg(feature = "async")]
+pub use async_io::{AsyncReadable, AsyncWriteable, MAX_ASYNC_READ_SIZE};
+
+mod bounded;
+pub use bounded::BoundedReader;
+
+#[cfg(feature = "bytes")]
+mod buf;
+#[cfg(feature = "bytes")]
+pub use buf::{BufReadable, BufWriteable};
+
+#[cfg(feature = "alloc")]
+pub mod dump;
+
+mod error;
+#[cfg(feature = "alloc")]
+pub use error::{DecodeError, DecodeErrorKind};
+
+mod foreign;
+
+mod le;
+pub use le::{LeReadable, LeWriteable};
+
+#[cfg(feature = "alloc")]
+pub mod layout;
+#[cfg(feature = "alloc")]
+pub use layout::Layout;
+
 mod payload;
-pub use payload::TypePrefixedPayload;
+pub use payload::{peek_type, TypePrefixedPayload};
+
+#[cfg(feature = "alloc")]
+mod registry;
+#[cfg(feature = "alloc")]
+pub use registry::PayloadRegistry;
+
+mod read_ref;
+pub use read_ref::{ReadableRef, WriteableBytesRef, WriteableVecRef};
 
 mod read_write;
-pub use read_write::{Readable, Writeable, WriteableBytes};
+pub use read_write::{Readable, Writeable};
+
+#[cfg(feature = "alloc")]
+pub use read_write::{WriteableBytes, WriteableVec};
+
+mod string;
+pub use string::FixedString;
+
+#[cfg(feature = "alloc")]
+pub use string::WriteableString;
+
+#[cfg(feature = "alloc")]
+pub mod testing;
+
+#[cfg(feature = "alloc")]
+mod versioned;
+#[cfg(feature = "alloc")]
+pub use versioned::{Extensions, Versioned, VersionedPayload};
+
+#[cfg(feature = "derive")]
+pub use wormhole_io_derive::{
+    LeReadable, LeWriteable, Readable, ReadableRef, TypePrefixedPayload, Writeable,
+};
+
+#[cfg(all(feature = "derive", feature = "alloc"))]
+pub use wormhole_io_derive::Layout;
+
+/// Not part of the public API. Used by code generated by the derive macros.
+#[doc(hidden)]
+pub mod __private {
+    pub use crate::error::{in_field, invalid_discriminant, invalid_padding, length_overflow};
+
+    #[cfg(feature = "alloc")]
+    pub use alloc::vec::Vec;
+
+    #[cfg(feature = "alloc")]
+    pub use crate::bounded::read_bytes;
+}
@@ -1,17 +1,16 @@
-use std::io;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
 
-use crate::{Readable, Writeable};
+use crate::{error, io, BoundedReader, Readable, Writeable};
 
 /// Trait to capture common payload behavior. We do not recommend overwriting
 /// any trait methods. Simply set the type constant and implement [`Readable`]
 /// and [`Writeable`].
 pub trait TypePrefixedPayload<const N: usize>:
-    Readable + Writeable + Clone + std::fmt::Debug
+    Readable + Writeable + Clone + core::fmt::Debug
 {
     const TYPE: Option<[u8; N]>;
 
-    fn written_size(&self) -> usize;
-
     /// Returns the size of the payload, including the type prefix.
     fn payload_written_size(&self) -> usize {
         match Self::TYPE {
@@ -21,37 +20,39 @@ pub trait TypePrefixedPayload<const N: usize>:
     }
 
     /// Read the payload, including the type prefix if applicable.
+    ///
+    /// With the `alloc` feature, errors carry a [`DecodeError`](crate::DecodeError) with the byte
+    /// offset and field path of the value that failed to decode.
     fn read_payload<R: io::Read>(reader: &mut R) -> Result<Self, io::Error> {
-        match Self::TYPE {
-            Some(id) => {
-                if id != <[u8; N]>::read(reader)? {
-                    return Err(io::Error::new(
-                        io::ErrorKind::InvalidData,
-                        "Invalid payload type",
-                    ));
-                }
-
-                Readable::read(reader)
-            }
-            None => Readable::read(reader),
+        #[cfg(feature = "alloc")]
+        {
+            let mut reader = error::OffsetReader::new(reader);
+            read_prefixed(&mut reader)
+                .map_err(|err| error::in_field(reader.locate(err), error::type_name::<Self>()))
         }
+
+        #[cfg(not(feature = "alloc"))]
+        read_prefixed(reader)
     }
 
     /// Read the payload as a slice. Under the hood, this uses
     /// [read_payload](TypePrefixedPayload::read_payload).
     ///
     /// NOTE: This method will check that the slice is empty after reading the
-    /// payload.
+    /// payload. Reads are checked against the rest of the slice before they are made, so a
+    /// length prefix that points past the end of the slice fails as soon as a read would cross
+    /// it.
     fn read_slice(buf: &[u8]) -> Result<Self, io::Error> {
+        let len = buf.len();
         let buf = &mut &buf[..];
-        let out = Self::read_payload(buf)?;
+        let out = Self::read_payload(&mut BoundedReader::to_end(&mut *buf, len))?;
 
         if buf.is_empty() {
             Ok(out)
         } else {
-            Err(io::Error::new(
-                io::ErrorKind::InvalidData,
-                "Invalid payload length",
+            Err(error::in_field(
+                error::invalid_length(len - buf.len(), len),
+                error::type_name::<Self>(),
             ))
         }
     }
@@ -67,6 +68,75 @@ pub trait TypePrefixedPayload<const N: usize>:
         }
     }
 
+    /// Write the payload into the start of `buf` without allocating. Returns the number of bytes
+    /// written, or fails without writing anything if `buf` is too small.
+    fn write_to_slice(&self, buf: &mut [u8]) -> Result<usize, io::Error> {
+        let len = self.payload_written_size();
+        if buf.len() < len {
+            return Err(io::Error::new(
+                io::ErrorKind::WriteZero,
+                "buffer too small for payload",
+            ));
+        }
+
+        self.write_payload(&mut &mut buf[..len])?;
+        Ok(len)
+    }
+
+    /// Write a fixed-size payload into an array of exactly its size, e.g.
+    /// `transfer.to_array::<133>()`. Fails if the payload size is not `LEN`.
+    fn to_array<const LEN: usize>(&self) -> Result<[u8; LEN], io::Error> {
+        let mut out = [0; LEN];
+        if self.write_to_slice(&mut out)? == LEN {
+            Ok(out)
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "payload is shorter than the array",
+            ))
+        }
+    }
+
+    /// Read the payload from a [`bytes::Buf`], advancing it past the payload.
+    #[cfg(feature = "bytes")]
+    fn read_payload_buf<B: bytes::Buf>(buf: &mut B) -> Result<Self, io::Error> {
+        Self::read_payload(&mut crate::buf::BufReader(buf))
+    }
+
+    /// Write the payload into a [`bytes::BufMut`]. Fails without writing anything if the payload
+    /// does not fit.
+    #[cfg(feature = "bytes")]
+    fn write_payload_buf<B: bytes::BufMut>(&self, buf: &mut B) -> Result<(), io::Error> {
+        crate::buf::check_fits(buf, self.payload_written_size())?;
+        self.write_payload(&mut crate::buf::BufWriter(buf))
+    }
+
+    /// Async counterpart to [read_payload](TypePrefixedPayload::read_payload), which reads no
+    /// further than the end of the payload.
+    #[cfg(feature = "async")]
+    fn read_payload_async<R>(
+        reader: &mut R,
+    ) -> impl core::future::Future<Output = io::Result<Self>> + Send
+    where
+        R: tokio::io::AsyncRead + Unpin + Send,
+        Self: Send,
+    {
+        crate::async_io::decode(reader, |replay| Self::read_payload(replay))
+    }
+
+    /// Async counterpart to [write_payload](TypePrefixedPayload::write_payload).
+    #[cfg(feature = "async")]
+    fn write_payload_async<W>(
+        &self,
+        writer: &mut W,
+    ) -> impl core::future::Future<Output = io::Result<()>> + Send
+    where
+        W: tokio::io::AsyncWrite + Unpin + Send,
+    {
+        crate::async_io::write(writer, Ok(self.to_vec()))
+    }
+
+    #[cfg(feature = "alloc")]
     fn to_vec(&self) -> Vec<u8> {
         let mut buf = Vec::with_capacity(self.payload_written_size());
         self.write_payload(&mut buf).expect("no alloc failure");
@@ -74,15 +144,40 @@ pub trait TypePrefixedPayload<const N: usize>:
     }
 }
 
+/// Read the type prefix at the start of `buf` without consuming it, e.g. to decide how to decode
+/// the rest of a payload. See also [`PayloadRegistry`](crate::PayloadRegistry).
+pub fn peek_type<const N: usize>(buf: &[u8]) -> Result<[u8; N], io::Error> {
+    <[u8; N]>::read(&mut &buf[..])
+}
+
+fn read_prefixed<T, R, const N: usize>(reader: &mut R) -> io::Result<T>
+where
+    T: TypePrefixedPayload<N>,
+    R: io::Read,
+{
+    if let Some(expected) = T::TYPE {
+        let found = <[u8; N]>::read(reader)?;
+        if found != expected {
+            return Err(error::invalid_payload_type(&expected, &found));
+        }
+    }
+
+    T::read(reader)
+}
+
 #[cfg(test)]
 mod test {
-    use crate::{Readable, TypePrefixedPayload, Writeable, WriteableBytes};
+    use crate::{
+        DecodeError, DecodeErrorKind, TypePrefixedPayload as _, Writeable as _, WriteableBytes,
+    };
     use hex_literal::hex;
+    use wormhole_io_derive::{Readable, TypePrefixedPayload, Writeable};
 
-    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[derive(Debug, Clone, PartialEq, Eq, Readable, Writeable)]
     pub struct NineteenBytes([u8; 19]);
 
-    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[derive(Debug, Clone, PartialEq, Eq, Readable, Writeable, TypePrefixedPayload)]
+    #[payload(type = 69)]
     pub struct Message {
         pub a: u32,
         pub b: NineteenBytes,
@@ -91,44 +186,6 @@ mod test {
         pub e: bool,
     }
 
-    impl TypePrefixedPayload<1> for Message {
-        const TYPE: Option<[u8; 1]> = Some([69]);
-
-        fn written_size(&self) -> usize {
-            88
-        }
-    }
-
-    impl Readable for Message {
-        fn read<R>(reader: &mut R) -> std::io::Result<Self>
-        where
-            Self: Sized,
-            R: std::io::Read,
-        {
-            Ok(Self {
-                a: Readable::read(reader)?,
-                b: NineteenBytes(Readable::read(reader)?),
-                c: Readable::read(reader)?,
-                d: Readable::read(reader)?,
-                e: Readable::read(reader)?,
-            })
-        }
-    }
-
-    impl Writeable for Message {
-        fn write<W>(&self, writer: &mut W) -> std::io::Result<()>
-        where
-            W: std::io::Write,
-        {
-            self.a.write(writer)?;
-            self.b.0.write(writer)?;
-            self.c.write(writer)?;
-            self.d.write(writer)?;
-            self.e.write(writer)?;
-            Ok(())
-        }
-    }
-
     #[test]
     fn to_vec_payload() {
         let msg = Message {
@@ -141,6 +198,7 @@ mod test {
 
         let mut encoded = msg.to_vec();
         assert_eq!(encoded, hex!("45000001a4ba5edba5edba5edba5edba5edba5edba5edba50000001c536f6d65626f6479207365742075732075702074686520626f6d622e000000000000004500000000000000450000000000000045000000000000004501"));
+        assert_eq!(msg.written_size(), 88);
         assert_eq!(encoded.capacity(), 1 + msg.written_size());
         assert_eq!(encoded.capacity(), encoded.len());
 
@@ -153,10 +211,33 @@ mod test {
     fn invalid_length() {
         let encoded = hex!("45000001a4ba5edba5edba5edba5edba5edba5edba5edba50000001c536f6d65626f6479207365742075732075702074686520626f6d622e00000000000000450000000000000045000000000000004500000000000000450169");
 
+        let err = Message::read_slice(&encoded).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+
+        let err = DecodeError::from_io(err);
         assert!(matches!(
-            Message::read_slice(&encoded).unwrap_err().kind(),
-            std::io::ErrorKind::InvalidData,
+            err.kind(),
+            DecodeErrorKind::InvalidLength {
+                expected: 89,
+                found: 90
+            }
+        ));
+        assert_eq!(err.offset(), Some(89));
+    }
+
+    #[test]
+    fn invalid_bool() {
+        let encoded = hex!("45000001a4ba5edba5edba5edba5edba5edba5edba5edba50000001c536f6d65626f6479207365742075732075702074686520626f6d622e000000000000004500000000000000450000000000000045000000000000004502");
+
+        let err = DecodeError::from_io(Message::read_slice(&encoded).unwrap_err());
+        assert!(matches!(
+            err.kind(),
+            DecodeErrorKind::InvalidBool { found: 2 }
         ));
+        assert_eq!(
+            err.to_string(),
+            "Message.e: invalid bool value 2 at offset 88"
+        );
     }
 
     #[test]
@@ -174,4 +255,39 @@ mod test {
         let decoded = Message::read_slice(&encoded).unwrap();
         assert_eq!(decoded, expected);
     }
+
+    #[test]
+    fn write_to_slice() {
+        let msg = Message {
+            a: 420,
+            b: NineteenBytes(hex!("ba5edba5edba5edba5edba5edba5edba5edba5")),
+            c: b"Somebody set us up the bomb.".to_vec().try_into().unwrap(),
+            d: [0x45; 4],
+            e: true,
+        };
+
+        let mut buf = [0xff; 100];
+        assert_eq!(msg.write_to_slice(&mut buf).unwrap(), 89);
+        assert_eq!(buf[..89], msg.to_vec());
+        assert_eq!(buf[89..], [0xff; 11]);
+
+        let err = msg.write_to_slice(&mut buf[..88]).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::WriteZero);
+        assert_eq!(buf[..89], msg.to_vec());
+    }
+
+    #[test]
+    fn to_array() {
+        let msg = Message {
+            a: 420,
+            b: NineteenBytes(hex!("ba5edba5edba5edba5edba5edba5edba5edba5")),
+            c: b"Somebody set us up the bomb.".to_vec().try_into().unwrap(),
+            d: [0x45; 4],
+            e: true,
+        };
+
+        assert_eq!(msg.to_array::<89>().unwrap()[..], msg.to_vec());
+        assert!(msg.to_array::<88>().is_err());
+        assert!(msg.to_array::<90>().is_err());
+    }
 }
@@ -0,0 +1,519 @@
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+use core::marker::PhantomData;
+
+use crate::{bounded::MAX_EMPTY_ELEMENTS, error, io, Readable, Writeable};
+#[cfg(feature = "alloc")]
+use crate::{WriteableBytes, WriteableVec};
+
+/// Zero-copy counterpart to [`Readable`]. Values are decoded directly from a byte slice, so
+/// variable-length fields can borrow from the input instead of allocating.
+pub trait ReadableRef<'a>: Sized {
+    /// Decode a value from the start of the slice. Returns the value and the number of bytes
+    /// consumed.
+    fn read_ref(buf: &'a [u8]) -> io::Result<(Self, usize)>;
+
+    /// Decode a value from the start of the slice and advance the slice past the consumed bytes.
+    fn take_ref(buf: &mut &'a [u8]) -> io::Result<Self> {
+        let (value, consumed) = Self::read_ref(buf)?;
+        *buf = &buf[consumed..];
+        Ok(value)
+    }
+}
+
+/// Split off the first `len` bytes of the slice, failing the same way [`io::Read::read_exact`]
+/// does if there are not enough bytes.
+pub(crate) fn split_at_checked(buf: &[u8], len: usize) -> io::Result<(&[u8], &[u8])> {
+    if buf.len() < len {
+        Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "failed to fill whole buffer",
+        ))
+    } else {
+        Ok(buf.split_at(len))
+    }
+}
+
+impl<'a> ReadableRef<'a> for u8 {
+    fn read_ref(buf: &'a [u8]) -> io::Result<(Self, usize)> {
+        let (bytes, _) = split_at_checked(buf, 1)?;
+        Ok((bytes[0], 1))
+    }
+}
+
+impl<'a> ReadableRef<'a> for bool {
+    fn read_ref(buf: &'a [u8]) -> io::Result<(Self, usize)> {
+        match u8::read_ref(buf)? {
+            (0, consumed) => Ok((false, consumed)),
+            (1, consumed) => Ok((true, consumed)),
+            (found, _) => Err(error::invalid_bool(found)),
+        }
+    }
+}
+
+macro_rules! impl_for_int {
+    ($type:ty) => {
+        impl<'a> ReadableRef<'a> for $type {
+            fn read_ref(buf: &'a [u8]) -> io::Result<(Self, usize)> {
+                const SIZE: usize = core::mem::size_of::<$type>();
+
+                let (bytes, _) = split_at_checked(buf, SIZE)?;
+                Ok((Self::from_be_bytes(bytes.try_into().unwrap()), SIZE))
+            }
+        }
+    };
+}
+
+impl_for_int!(u16);
+impl_for_int!(u32);
+impl_for_int!(u64);
+impl_for_int!(u128);
+
+impl_for_int!(i8);
+impl_for_int!(i16);
+impl_for_int!(i32);
+impl_for_int!(i64);
+impl_for_int!(i128);
+
+impl<'a, const N: usize> ReadableRef<'a> for &'a [u8; N] {
+    fn read_ref(buf: &'a [u8]) -> io::Result<(Self, usize)> {
+        let (bytes, _) = split_at_checked(buf, N)?;
+        Ok((bytes.try_into().unwrap(), N))
+    }
+}
+
+impl<'a, const N: usize> ReadableRef<'a> for [u8; N] {
+    fn read_ref(buf: &'a [u8]) -> io::Result<(Self, usize)> {
+        <&[u8; N]>::read_ref(buf).map(|(bytes, consumed)| (*bytes, consumed))
+    }
+}
+
+macro_rules! impl_for_int_array {
+    ($type:ty) => {
+        impl<'a, const N: usize> ReadableRef<'a> for [$type; N] {
+            fn read_ref(buf: &'a [u8]) -> io::Result<(Self, usize)> {
+                let mut remaining = buf;
+                let mut out = [Default::default(); N];
+                for elem in out.iter_mut() {
+                    *elem = <$type>::take_ref(&mut remaining)?;
+                }
+                Ok((out, buf.len() - remaining.len()))
+            }
+        }
+    };
+}
+
+impl_for_int_array!(u16);
+impl_for_int_array!(u32);
+impl_for_int_array!(u64);
+impl_for_int_array!(u128);
+
+impl_for_int_array!(i8);
+impl_for_int_array!(i16);
+impl_for_int_array!(i32);
+impl_for_int_array!(i64);
+impl_for_int_array!(i128);
+
+impl<'a, T> ReadableRef<'a> for Option<T>
+where
+    T: ReadableRef<'a>,
+{
+    fn read_ref(buf: &'a [u8]) -> io::Result<(Self, usize)> {
+        match bool::read_ref(buf)? {
+            (true, consumed) => {
+                let (value, value_consumed) = T::read_ref(&buf[consumed..])?;
+                Ok((Some(value), consumed + value_consumed))
+            }
+            (false, consumed) => Ok((None, consumed)),
+        }
+    }
+}
+
+macro_rules! impl_for_tuple {
+    ($($name:ident)+) => {
+        impl<'a, $($name: ReadableRef<'a>),+> ReadableRef<'a> for ($($name,)+) {
+            fn read_ref(buf: &'a [u8]) -> io::Result<(Self, usize)> {
+                let mut remaining = buf;
+                let value = ($($name::take_ref(&mut remaining)?,)+);
+                Ok((value, buf.len() - remaining.len()))
+            }
+        }
+    };
+}
+
+impl_for_tuple!(A);
+impl_for_tuple!(A B);
+impl_for_tuple!(A B C);
+impl_for_tuple!(A B C D);
+impl_for_tuple!(A B C D E);
+impl_for_tuple!(A B C D E F);
+impl_for_tuple!(A B C D E F G);
+impl_for_tuple!(A B C D E F G H);
+
+#[cfg(feature = "alloc")]
+impl<'a, T> ReadableRef<'a> for Box<T>
+where
+    T: ReadableRef<'a>,
+{
+    fn read_ref(buf: &'a [u8]) -> io::Result<(Self, usize)> {
+        T::read_ref(buf).map(|(value, consumed)| (Box::new(value), consumed))
+    }
+}
+
+/// Borrowed counterpart to [`WriteableBytes`]. The encoding is the same: a big-endian length of
+/// type `L` followed by that many bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WriteableBytesRef<'a, L>
+where
+    u32: From<L>,
+    L: Sized + Readable + Writeable + TryFrom<usize>,
+{
+    phantom: PhantomData<L>,
+    inner: &'a [u8],
+}
+
+impl<'a, L> WriteableBytesRef<'a, L>
+where
+    u32: From<L>,
+    L: Sized + Readable + Writeable + TryFrom<usize>,
+{
+    pub fn new(inner: &'a [u8]) -> Self {
+        Self {
+            phantom: PhantomData,
+            inner,
+        }
+    }
+
+    pub fn as_slice(&self) -> &'a [u8] {
+        self.inner
+    }
+
+    pub fn try_encoded_len(&self) -> io::Result<L> {
+        match L::try_from(self.inner.len()) {
+            Ok(len) => Ok(len),
+            Err(_) => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "L overflow when converting from usize",
+            )),
+        }
+    }
+}
+
+impl<'a, L> core::ops::Deref for WriteableBytesRef<'a, L>
+where
+    u32: From<L>,
+    L: Sized + Readable + Writeable + TryFrom<usize>,
+{
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        self.inner
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, L> From<WriteableBytesRef<'a, L>> for WriteableBytes<L>
+where
+    u32: From<L>,
+    L: Sized + Readable + Writeable + TryFrom<usize>,
+{
+    fn from(bytes: WriteableBytesRef<'a, L>) -> Self {
+        WriteableBytes::new(bytes.inner.to_vec())
+    }
+}
+
+impl<'a, L> ReadableRef<'a> for WriteableBytesRef<'a, L>
+where
+    u32: From<L>,
+    L: Sized + Readable + Writeable + TryFrom<usize> + ReadableRef<'a>,
+{
+    fn read_ref(buf: &'a [u8]) -> io::Result<(Self, usize)> {
+        let (len, consumed) = L::read_ref(buf)?;
+        let len = usize::try_from(u32::from(len)).map_err(|_| error::length_overflow())?;
+        let (inner, _) = split_at_checked(&buf[consumed..], len)?;
+
+        Ok((Self::new(inner), consumed + len))
+    }
+}
+
+impl<'a, L> Writeable for WriteableBytesRef<'a, L>
+where
+    u32: From<L>,
+    L: Sized + Readable + Writeable + TryFrom<usize>,
+{
+    fn write<W>(&self, writer: &mut W) -> io::Result<()>
+    where
+        W: io::Write,
+    {
+        self.try_encoded_len()?.write(writer)?;
+        writer.write_all(self.inner)
+    }
+
+    fn written_size(&self) -> usize {
+        core::mem::size_of::<L>() + self.inner.len()
+    }
+}
+
+/// Borrowed counterpart to [`WriteableVec`]. The encoding is the same: an element count of type
+/// `L` followed by the encoded elements.
+///
+/// Elements are validated when the vector is read, and decoded again on each call to
+/// [`iter`](WriteableVecRef::iter).
+#[derive(Debug, PartialEq, Eq)]
+pub struct WriteableVecRef<'a, L, T>
+where
+    u32: From<L>,
+    L: Sized + Readable + Writeable + TryFrom<usize>,
+{
+    phantom: PhantomData<(L, T)>,
+    len: usize,
+    encoded: &'a [u8],
+}
+
+impl<'a, L, T> WriteableVecRef<'a, L, T>
+where
+    u32: From<L>,
+    L: Sized + Readable + Writeable + TryFrom<usize>,
+{
+    /// Number of elements.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The encoded elements, without the element count.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.encoded
+    }
+
+    pub fn try_encoded_len(&self) -> io::Result<L> {
+        match L::try_from(self.len) {
+            Ok(len) => Ok(len),
+            Err(_) => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "L overflow when converting from usize",
+            )),
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = T> + 'a
+    where
+        T: ReadableRef<'a>,
+    {
+        let mut remaining = self.encoded;
+        (0..self.len)
+            .map(move |_| T::take_ref(&mut remaining).expect("elements validated by read_ref"))
+    }
+}
+
+impl<'a, L, T> Clone for WriteableVecRef<'a, L, T>
+where
+    u32: From<L>,
+    L: Sized + Readable + Writeable + TryFrom<usize>,
+{
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, L, T> Copy for WriteableVecRef<'a, L, T>
+where
+    u32: From<L>,
+    L: Sized + Readable + Writeable + TryFrom<usize>,
+{
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, L, T> From<WriteableVecRef<'a, L, T>> for WriteableVec<L, T>
+where
+    u32: From<L>,
+    L: Sized + Readable + Writeable + TryFrom<usize>,
+    T: ReadableRef<'a>,
+{
+    fn from(vec: WriteableVecRef<'a, L, T>) -> Self {
+        WriteableVec::new(vec.iter().collect())
+    }
+}
+
+impl<'a, L, T> ReadableRef<'a> for WriteableVecRef<'a, L, T>
+where
+    u32: From<L>,
+    L: Sized + Readable + Writeable + TryFrom<usize> + ReadableRef<'a>,
+    T: ReadableRef<'a>,
+{
+    fn read_ref(buf: &'a [u8]) -> io::Result<(Self, usize)> {
+        let (len, consumed) = L::read_ref(buf)?;
+        let len = usize::try_from(u32::from(len)).map_err(|_| error::length_overflow())?;
+
+        let start = &buf[consumed..];
+        let mut remaining = start;
+        let mut empty = 0;
+        for _ in 0..len {
+            let before = remaining.len();
+            T::take_ref(&mut remaining)?;
+            if remaining.len() == before {
+                empty += 1;
+                if empty > MAX_EMPTY_ELEMENTS {
+                    return Err(error::too_many_empty_elements(MAX_EMPTY_ELEMENTS));
+                }
+            }
+        }
+        let (encoded, _) = start.split_at(start.len() - remaining.len());
+
+        Ok((
+            Self {
+                phantom: PhantomData,
+                len,
+                encoded,
+            },
+            consumed + encoded.len(),
+        ))
+    }
+}
+
+impl<'a, L, T> Writeable for WriteableVecRef<'a, L, T>
+where
+    u32: From<L>,
+    L: Sized + Readable + Writeable + TryFrom<usize>,
+{
+    fn write<W>(&self, writer: &mut W) -> io::Result<()>
+    where
+        W: io::Write,
+    {
+        self.try_encoded_len()?.write(writer)?;
+        writer.write_all(self.encoded)
+    }
+
+    fn written_size(&self) -> usize {
+        core::mem::size_of::<L>() + self.encoded.len()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use hex_literal::hex;
+
+    #[test]
+    fn u64_read_ref() {
+        let encoded = hex!("000000000000004569");
+
+        let (value, consumed) = u64::read_ref(&encoded).unwrap();
+        assert_eq!(value, 69);
+        assert_eq!(consumed, 8);
+    }
+
+    #[test]
+    fn u8_array_read_ref_borrows() {
+        let encoded = hex!("01020810204045");
+
+        let (value, consumed) = <&[u8; 4]>::read_ref(&encoded).unwrap();
+        assert_eq!(value, &hex!("01020810"));
+        assert_eq!(consumed, 4);
+        assert!(std::ptr::eq(value.as_ptr(), encoded.as_ptr()));
+    }
+
+    #[test]
+    fn u64_array_read_ref() {
+        let encoded = hex!("00000000000000010000000000000045");
+
+        let (value, consumed) = <[u64; 2]>::read_ref(&encoded).unwrap();
+        assert_eq!(value, [1, 69]);
+        assert_eq!(consumed, 16);
+    }
+
+    #[test]
+    fn insufficient_bytes() {
+        let encoded = hex!("000000000000");
+
+        let err = u64::read_ref(&encoded).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn option_read_ref() {
+        let encoded = hex!("01004500");
+        let buf = &mut &encoded[..];
+
+        assert_eq!(Option::<u16>::take_ref(buf).unwrap(), Some(69));
+        assert_eq!(Option::<u16>::take_ref(buf).unwrap(), None);
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn variable_bytes_read_ref_u16() {
+        let encoded = hex!("001f416c6c20796f75722062617365206172652062656c6f6e6720746f2075732e69");
+
+        let (bytes, consumed) = WriteableBytesRef::<u16>::read_ref(&encoded).unwrap();
+        assert_eq!(consumed, 33);
+        assert_eq!(&*bytes, b"All your base are belong to us.");
+
+        // Writing the borrowed bytes produces the same encoding as the owned bytes.
+        let mut encoded_ref = Vec::with_capacity(bytes.written_size());
+        bytes.write(&mut encoded_ref).unwrap();
+
+        let owned = WriteableBytes::<u16>::from(bytes);
+        let mut encoded_owned = Vec::with_capacity(owned.written_size());
+        owned.write(&mut encoded_owned).unwrap();
+
+        assert_eq!(encoded_ref, encoded_owned);
+        assert_eq!(encoded_ref, encoded[..33]);
+    }
+
+    #[test]
+    fn variable_bytes_read_ref_insufficient_bytes() {
+        let encoded = hex!("0000001f416c6c");
+
+        let err = WriteableBytesRef::<u32>::read_ref(&encoded).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn vec_read_ref() {
+        let encoded = hex!("0002000100020003000400050006");
+
+        let (vec, consumed) = WriteableVecRef::<u16, [u16; 2]>::read_ref(&encoded).unwrap();
+        assert_eq!(consumed, 10);
+        assert_eq!(vec.len(), 2);
+        assert_eq!(vec.iter().collect::<Vec<_>>(), [[1, 2], [3, 4]]);
+        assert_eq!(vec.written_size(), 10);
+
+        let owned = WriteableVec::<u16, [u16; 2]>::from(vec);
+        let mut encoded_owned = Vec::with_capacity(owned.written_size());
+        owned.write(&mut encoded_owned).unwrap();
+        assert_eq!(encoded_owned, encoded[..10]);
+    }
+
+    #[test]
+    fn vec_read_ref_borrows_elements() {
+        let encoded = hex!("0202beef01de");
+
+        let (vec, _) = WriteableVecRef::<u8, WriteableBytesRef<u8>>::read_ref(&encoded).unwrap();
+        let elements = vec.iter().collect::<Vec<_>>();
+        assert_eq!(&*elements[0], hex!("beef"));
+        assert_eq!(&*elements[1], hex!("de"));
+        assert!(core::ptr::eq(elements[1].as_ptr(), encoded[5..].as_ptr()));
+    }
+
+    #[test]
+    fn vec_read_ref_insufficient_elements() {
+        let encoded = hex!("03beefbeef");
+
+        let err = WriteableVecRef::<u8, [u8; 2]>::read_ref(&encoded).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn tuple_read_ref() {
+        let encoded = hex!("4501000100020102beef");
+
+        let ((value, flag, ints, bytes), consumed) =
+            <(u8, bool, [u16; 2], Option<WriteableBytesRef<u8>>)>::read_ref(&encoded).unwrap();
+        assert_eq!(consumed, encoded.len());
+        assert_eq!((value, flag, ints), (69, true, [1, 2]));
+        assert_eq!(bytes.as_deref(), Some(&[0xbe, 0xef][..]));
+    }
+}
@@ -1,4 +1,11 @@
-use std::{io, marker::PhantomData};
+#[cfg(feature = "alloc")]
+use alloc::{boxed::Box, vec, vec::Vec};
+#[cfg(feature = "alloc")]
+use core::marker::PhantomData;
+
+#[cfg(feature = "alloc")]
+use crate::bounded;
+use crate::{error, io};
 
 pub trait Readable {
     fn read<R>(reader: &mut R) -> io::Result<Self>
@@ -11,6 +18,38 @@ pub trait Writeable {
     fn write<W>(&self, writer: &mut W) -> io::Result<()>
     where
         W: io::Write;
+
+    /// Returns the number of bytes [write](Writeable::write) will produce.
+    ///
+    /// By default this counts the bytes of a full [write](Writeable::write). The derive and the
+    /// impls in this crate override it to compute the size directly, which hand-written impls
+    /// on hot paths should do as well.
+    ///
+    /// # Panics
+    ///
+    /// The default panics if [write](Writeable::write) fails. Counting never fails, so that is
+    /// a bug in the impl.
+    fn written_size(&self) -> usize {
+        let mut counter = ByteCounter(0);
+        self.write(&mut counter)
+            .expect("write to a byte counter failed");
+        counter.0
+    }
+}
+
+/// A sink that only counts the bytes written to it.
+struct ByteCounter(usize);
+
+impl io::Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0 += buf.len();
+        Ok(buf.len())
+    }
+
+    #[cfg(feature = "std")]
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
 }
 
 impl Readable for u8 {
@@ -31,6 +70,10 @@ impl Writeable for u8 {
     {
         writer.write_all(&[*self])
     }
+
+    fn written_size(&self) -> usize {
+        1
+    }
 }
 
 impl Readable for bool {
@@ -41,10 +84,7 @@ impl Readable for bool {
         match u8::read(reader)? {
             0 => Ok(false),
             1 => Ok(true),
-            _ => Err(io::Error::new(
-                io::ErrorKind::InvalidData,
-                "invalid bool value",
-            )),
+            found => Err(error::invalid_bool(found)),
         }
     }
 }
@@ -56,6 +96,10 @@ impl Writeable for bool {
     {
         writer.write_all(&[u8::from(*self)])
     }
+
+    fn written_size(&self) -> usize {
+        1
+    }
 }
 
 macro_rules! impl_for_int {
@@ -65,7 +109,7 @@ macro_rules! impl_for_int {
             where
                 R: io::Read,
             {
-                let mut buf = [0u8; std::mem::size_of::<$type>()];
+                let mut buf = [0u8; core::mem::size_of::<$type>()];
                 reader.read_exact(&mut buf)?;
                 Ok(Self::from_be_bytes(buf))
             }
@@ -78,6 +122,10 @@ macro_rules! impl_for_int {
             {
                 writer.write_all(&self.to_be_bytes())
             }
+
+            fn written_size(&self) -> usize {
+                core::mem::size_of::<$type>()
+            }
         }
     };
 }
@@ -112,6 +160,10 @@ impl<const N: usize> Writeable for [u8; N] {
     {
         writer.write_all(self)
     }
+
+    fn written_size(&self) -> usize {
+        N
+    }
 }
 
 macro_rules! impl_for_int_array {
@@ -139,6 +191,10 @@ macro_rules! impl_for_int_array {
                 }
                 Ok(())
             }
+
+            fn written_size(&self) -> usize {
+                N * core::mem::size_of::<$type>()
+            }
         }
     };
 }
@@ -186,10 +242,91 @@ where
             None => false.write(writer),
         }
     }
+
+    fn written_size(&self) -> usize {
+        match self {
+            Some(value) => 1 + value.written_size(),
+            None => 1,
+        }
+    }
+}
+
+/// Tuples are encoded as their elements in order, with no length or separator.
+macro_rules! impl_for_tuple {
+    ($($name:ident)+) => {
+        impl<$($name: Readable),+> Readable for ($($name,)+) {
+            fn read<R>(reader: &mut R) -> io::Result<Self>
+            where
+                Self: Sized,
+                R: io::Read,
+            {
+                Ok(($($name::read(reader)?,)+))
+            }
+        }
+
+        impl<$($name: Writeable),+> Writeable for ($($name,)+) {
+            #[allow(non_snake_case)]
+            fn write<W>(&self, writer: &mut W) -> io::Result<()>
+            where
+                W: io::Write,
+            {
+                let ($($name,)+) = self;
+                $($name.write(writer)?;)+
+                Ok(())
+            }
+
+            #[allow(non_snake_case)]
+            fn written_size(&self) -> usize {
+                let ($($name,)+) = self;
+                0 $(+ $name.written_size())+
+            }
+        }
+    };
+}
+
+impl_for_tuple!(A);
+impl_for_tuple!(A B);
+impl_for_tuple!(A B C);
+impl_for_tuple!(A B C D);
+impl_for_tuple!(A B C D E);
+impl_for_tuple!(A B C D E F);
+impl_for_tuple!(A B C D E F G);
+impl_for_tuple!(A B C D E F G H);
+
+#[cfg(feature = "alloc")]
+impl<T> Readable for Box<T>
+where
+    T: Readable,
+{
+    fn read<R>(reader: &mut R) -> io::Result<Self>
+    where
+        Self: Sized,
+        R: io::Read,
+    {
+        T::read(reader).map(Box::new)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> Writeable for Box<T>
+where
+    T: Writeable,
+{
+    fn write<W>(&self, writer: &mut W) -> io::Result<()>
+    where
+        W: io::Write,
+    {
+        (**self).write(writer)
+    }
+
+    fn written_size(&self) -> usize {
+        (**self).written_size()
+    }
 }
 
 /// Wrapper for `Vec<u8>`. Encoding is similar to Borsh, where the length is encoded as u32 (but in
 /// this case, it's big endian).
+#[cfg(feature = "alloc")]
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub struct WriteableBytes<L>
 where
@@ -200,6 +337,7 @@ where
     inner: Vec<u8>,
 }
 
+#[cfg(feature = "alloc")]
 impl<L> WriteableBytes<L>
 where
     u32: From<L>,
@@ -221,12 +359,9 @@ where
             )),
         }
     }
-
-    pub fn written_size(&self) -> usize {
-        std::mem::size_of::<L>() + self.inner.len()
-    }
 }
 
+#[cfg(feature = "alloc")]
 impl<L> TryFrom<Vec<u8>> for WriteableBytes<L>
 where
     u32: From<L>,
@@ -245,6 +380,7 @@ where
     }
 }
 
+#[cfg(feature = "alloc")]
 impl<L> From<WriteableBytes<L>> for Vec<u8>
 where
     u32: From<L>,
@@ -255,7 +391,8 @@ where
     }
 }
 
-impl<L> std::ops::Deref for WriteableBytes<L>
+#[cfg(feature = "alloc")]
+impl<L> core::ops::Deref for WriteableBytes<L>
 where
     L: Sized + Readable + Writeable,
     u32: From<L>,
@@ -268,7 +405,8 @@ where
     }
 }
 
-impl<L> std::ops::DerefMut for WriteableBytes<L>
+#[cfg(feature = "alloc")]
+impl<L> core::ops::DerefMut for WriteableBytes<L>
 where
     u32: From<L>,
     L: Sized + Readable + Writeable + TryFrom<usize>,
@@ -278,6 +416,7 @@ where
     }
 }
 
+#[cfg(feature = "alloc")]
 impl Readable for WriteableBytes<u8> {
     fn read<R>(reader: &mut R) -> io::Result<Self>
     where
@@ -294,6 +433,7 @@ impl Readable for WriteableBytes<u8> {
     }
 }
 
+#[cfg(feature = "alloc")]
 impl Readable for WriteableBytes<u16> {
     fn read<R>(reader: &mut R) -> io::Result<Self>
     where
@@ -310,6 +450,7 @@ impl Readable for WriteableBytes<u16> {
     }
 }
 
+#[cfg(feature = "alloc")]
 impl Readable for WriteableBytes<u32> {
     fn read<R>(reader: &mut R) -> io::Result<Self>
     where
@@ -318,22 +459,16 @@ impl Readable for WriteableBytes<u32> {
     {
         let len = u32::read(reader)?;
         match len.try_into() {
-            Ok(len) => {
-                let mut inner = vec![0u8; len];
-                reader.read_exact(&mut inner)?;
-                Ok(Self {
-                    phantom: PhantomData,
-                    inner,
-                })
-            }
-            Err(_) => Err(io::Error::new(
-                io::ErrorKind::InvalidData,
-                "u32 overflow when converting to usize",
-            )),
+            Ok(len) => Ok(Self {
+                phantom: PhantomData,
+                inner: bounded::read_bytes(reader, len)?,
+            }),
+            Err(_) => Err(error::length_overflow()),
         }
     }
 }
 
+#[cfg(feature = "alloc")]
 impl<L> Writeable for WriteableBytes<L>
 where
     u32: From<L>,
@@ -351,71 +486,149 @@ where
             Err(e) => Err(e),
         }
     }
+
+    fn written_size(&self) -> usize {
+        core::mem::size_of::<L>() + self.inner.len()
+    }
 }
 
-#[cfg(feature = "alloy")]
-impl<const N: usize> Readable for alloy_primitives::FixedBytes<N> {
-    fn read<R>(reader: &mut R) -> io::Result<Self>
-    where
-        Self: Sized,
-        R: io::Read,
-    {
-        <[u8; N]>::read(reader).map(Self)
+/// Wrapper for `Vec<T>`. Encoding is the number of elements as `L` (big endian), followed by the
+/// encoded elements.
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WriteableVec<L, T>
+where
+    u32: From<L>,
+    L: Sized + Readable + Writeable + TryFrom<usize>,
+{
+    phantom: PhantomData<L>,
+    inner: Vec<T>,
+}
+
+#[cfg(feature = "alloc")]
+impl<L, T> WriteableVec<L, T>
+where
+    u32: From<L>,
+    L: Sized + Readable + Writeable + TryFrom<usize>,
+{
+    pub fn new(inner: Vec<T>) -> Self {
+        Self {
+            phantom: PhantomData,
+            inner,
+        }
+    }
+
+    pub fn try_encoded_len(&self) -> io::Result<L> {
+        match L::try_from(self.inner.len()) {
+            Ok(len) => Ok(len),
+            Err(_) => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "L overflow when converting from usize",
+            )),
+        }
     }
 }
 
-#[cfg(feature = "alloy")]
-impl<const N: usize> Writeable for alloy_primitives::FixedBytes<N> {
-    fn write<W>(&self, writer: &mut W) -> io::Result<()>
-    where
-        W: io::Write,
-    {
-        self.0.write(writer)
+#[cfg(feature = "alloc")]
+impl<L, T> Default for WriteableVec<L, T>
+where
+    u32: From<L>,
+    L: Sized + Readable + Writeable + TryFrom<usize>,
+{
+    fn default() -> Self {
+        Self::new(Vec::new())
     }
 }
 
-#[cfg(feature = "alloy")]
-impl<const BITS: usize, const LIMBS: usize> Readable for alloy_primitives::Uint<BITS, LIMBS> {
-    fn read<R>(reader: &mut R) -> io::Result<Self>
-    where
-        Self: Sized,
-        R: io::Read,
-    {
-        let mut buf = alloy_primitives::Uint::<BITS, LIMBS>::default().to_be_bytes_vec();
-        reader.read_exact(buf.as_mut_slice())?;
+#[cfg(feature = "alloc")]
+impl<L, T> TryFrom<Vec<T>> for WriteableVec<L, T>
+where
+    u32: From<L>,
+    L: Sized + Readable + Writeable + TryFrom<usize>,
+{
+    type Error = <L as TryFrom<usize>>::Error;
 
-        Ok(alloy_primitives::Uint::try_from_be_slice(buf.as_slice()).unwrap())
+    fn try_from(vec: Vec<T>) -> Result<Self, Self::Error> {
+        L::try_from(vec.len()).map(|_| Self::new(vec))
     }
 }
 
-#[cfg(feature = "alloy")]
-impl<const BITS: usize, const LIMBS: usize> Writeable for alloy_primitives::Uint<BITS, LIMBS> {
-    fn write<W>(&self, writer: &mut W) -> io::Result<()>
-    where
-        W: io::Write,
-    {
-        writer.write_all(self.to_be_bytes_vec().as_slice())
+#[cfg(feature = "alloc")]
+impl<L, T> From<WriteableVec<L, T>> for Vec<T>
+where
+    u32: From<L>,
+    L: Sized + Readable + Writeable + TryFrom<usize>,
+{
+    fn from(vec: WriteableVec<L, T>) -> Self {
+        vec.inner
     }
 }
 
-#[cfg(feature = "alloy")]
-impl Readable for alloy_primitives::Address {
+#[cfg(feature = "alloc")]
+impl<L, T> core::ops::Deref for WriteableVec<L, T>
+where
+    u32: From<L>,
+    L: Sized + Readable + Writeable + TryFrom<usize>,
+{
+    type Target = Vec<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<L, T> core::ops::DerefMut for WriteableVec<L, T>
+where
+    u32: From<L>,
+    L: Sized + Readable + Writeable + TryFrom<usize>,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<L, T> Readable for WriteableVec<L, T>
+where
+    u32: From<L>,
+    L: Sized + Readable + Writeable + TryFrom<usize>,
+    T: Readable,
+{
     fn read<R>(reader: &mut R) -> io::Result<Self>
     where
         Self: Sized,
         R: io::Read,
     {
-        alloy_primitives::FixedBytes::<20>::read(reader).map(Self)
+        let len =
+            usize::try_from(u32::from(L::read(reader)?)).map_err(|_| error::length_overflow())?;
+
+        bounded::read_elements(reader, len, |reader| T::read(reader)).map(Self::new)
     }
 }
 
-#[cfg(feature = "alloy")]
-impl Writeable for alloy_primitives::Address {
+#[cfg(feature = "alloc")]
+impl<L, T> Writeable for WriteableVec<L, T>
+where
+    u32: From<L>,
+    L: Sized + Readable + Writeable + TryFrom<usize>,
+    T: Writeable,
+{
     fn write<W>(&self, writer: &mut W) -> io::Result<()>
     where
         W: io::Write,
     {
-        self.0.write(writer)
+        self.try_encoded_len()?.write(writer)?;
+        self.inner.iter().try_for_each(|elem| elem.write(writer))
+    }
+
+    fn written_size(&self) -> usize {
+        core::mem::size_of::<L>()
+            + self
+                .inner
+                .iter()
+                .map(Writeable::written_size)
+                .sum::<usize>()
     }
 }
 
@@ -424,6 +637,23 @@ pub mod test {
     use super::*;
     use hex_literal::hex;
 
+    #[test]
+    fn default_written_size() {
+        struct Pair(u16, [u8; 3]);
+
+        impl Writeable for Pair {
+            fn write<W>(&self, writer: &mut W) -> io::Result<()>
+            where
+                W: io::Write,
+            {
+                self.0.write(writer)?;
+                self.1.write(writer)
+            }
+        }
+
+        assert_eq!(Pair(1, [2; 3]).written_size(), 5);
+    }
+
     #[test]
     fn u8_read_write() {
         const EXPECTED_SIZE: usize = 1;
@@ -516,6 +746,56 @@ pub mod test {
         assert_eq!(encoded, expected);
     }
 
+    #[test]
+    fn vec_read_write() {
+        let guardians = WriteableVec::<u8, [u8; 20]>::new(vec![[0x45; 20], [0x69; 20]]);
+        assert_eq!(guardians.written_size(), 41);
+
+        let mut encoded = Vec::<u8>::with_capacity(guardians.written_size());
+        guardians.write(&mut encoded).unwrap();
+
+        let expected = hex!(
+            "0245454545454545454545454545454545454545456969696969696969696969696969696969696969"
+        );
+        assert_eq!(encoded, expected);
+        assert_eq!(
+            WriteableVec::<u8, [u8; 20]>::read(&mut encoded.as_slice()).unwrap(),
+            guardians
+        );
+    }
+
+    #[test]
+    fn vec_of_variable_size_elements() {
+        let tiers = WriteableVec::<u16, WriteableBytes<u8>>::new(vec![
+            WriteableBytes::new(b"gm".to_vec()),
+            WriteableBytes::new(Vec::new()),
+        ]);
+        assert_eq!(tiers.written_size(), 2 + 3 + 1);
+
+        let mut encoded = Vec::<u8>::with_capacity(tiers.written_size());
+        tiers.write(&mut encoded).unwrap();
+        assert_eq!(encoded, hex!("000202676d00"));
+    }
+
+    #[test]
+    fn vec_length_overflow() {
+        let too_long = vec![0u64; 256];
+        assert!(WriteableVec::<u8, u64>::try_from(too_long.clone()).is_err());
+
+        let err = WriteableVec::<u8, u64>::new(too_long)
+            .write(&mut Vec::new())
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn vec_insufficient_elements() {
+        let encoded = hex!("ffffffff0000000000000045");
+
+        let err = WriteableVec::<u32, u64>::read(&mut &encoded[..]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+
     #[test]
     fn mem_take() {
         let data = b"All your base are belong to us.";
@@ -548,4 +828,30 @@ pub mod test {
         let expected = hex!("00");
         assert_eq!(encoded, expected);
     }
+
+    #[test]
+    fn tuple_read_write() {
+        let value = (69u8, true, [1u16, 2], Some(-1i32));
+        assert_eq!(value.written_size(), 11);
+
+        let mut encoded = Vec::with_capacity(value.written_size());
+        value.write(&mut encoded).unwrap();
+        assert_eq!(encoded, hex!("45010001000201ffffffff"));
+
+        let decoded = <(u8, bool, [u16; 2], Option<i32>)>::read(&mut encoded.as_slice()).unwrap();
+        assert_eq!(decoded, value);
+    }
+
+    #[test]
+    fn boxed_read_write() {
+        let value = Box::new((1u64, WriteableBytes::<u8>::new(vec![0xbe, 0xef])));
+        assert_eq!(value.written_size(), 11);
+
+        let mut encoded = Vec::with_capacity(value.written_size());
+        value.write(&mut encoded).unwrap();
+        assert_eq!(encoded, hex!("000000000000000102beef"));
+
+        let decoded = Box::<(u64, WriteableBytes<u8>)>::read(&mut encoded.as_slice()).unwrap();
+        assert_eq!(decoded, value);
+    }
 }
@@ -0,0 +1,284 @@
+//! Decoding payloads whose type is only known at runtime, by dispatching on their type prefix.
+
+use alloc::{boxed::Box, collections::BTreeMap};
+use core::{any::Any, fmt};
+
+use crate::{error, io, BoundedReader, Readable, TypePrefixedPayload};
+
+type Decoder<T> = Box<dyn Fn(&mut dyn io::Read) -> io::Result<T> + Send + Sync>;
+
+/// Maps type prefixes to the [`TypePrefixedPayload`] types they identify, so a payload can be
+/// decoded without knowing its type up front.
+///
+/// Decoded payloads are converted into `T`, which is either a user enum with a variant per
+/// registered payload, or (by default) a `Box<dyn Any + Send>` to be downcast:
+///
+/// ```
+/// use wormhole_io::{PayloadRegistry, TypePrefixedPayload};
+/// # use wormhole_io::{io, Readable, Writeable};
+/// # #[derive(Debug, Clone, PartialEq)]
+/// # struct Ping(u16);
+/// # impl Readable for Ping {
+/// #     fn read<R: io::Read>(reader: &mut R) -> io::Result<Self> {
+/// #         u16::read(reader).map(Self)
+/// #     }
+/// # }
+/// # impl Writeable for Ping {
+/// #     fn write<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+/// #         self.0.write(writer)
+/// #     }
+/// #     fn written_size(&self) -> usize {
+/// #         2
+/// #     }
+/// # }
+/// # impl TypePrefixedPayload<1> for Ping {
+/// #     const TYPE: Option<[u8; 1]> = Some([1]);
+/// # }
+///
+/// let mut registry = PayloadRegistry::<1>::new();
+/// registry.register_any::<Ping>();
+///
+/// let payload = registry.read_slice(&[1, 0, 69]).unwrap();
+/// assert_eq!(payload.downcast_ref::<Ping>(), Some(&Ping(69)));
+/// ```
+pub struct PayloadRegistry<const N: usize, T = Box<dyn Any + Send>> {
+    decoders: BTreeMap<[u8; N], Decoder<T>>,
+}
+
+impl<const N: usize, T> PayloadRegistry<N, T> {
+    pub const fn new() -> Self {
+        Self {
+            decoders: BTreeMap::new(),
+        }
+    }
+
+    /// Register `P` under its type prefix, converting decoded payloads with `T::from`.
+    ///
+    /// # Panics
+    ///
+    /// If `P` has no type prefix, or its prefix is already registered.
+    pub fn register<P>(&mut self) -> &mut Self
+    where
+        P: TypePrefixedPayload<N> + 'static,
+        T: From<P> + 'static,
+    {
+        self.register_with(T::from)
+    }
+
+    /// Register `P` under its type prefix, converting decoded payloads with `into`.
+    ///
+    /// # Panics
+    ///
+    /// If `P` has no type prefix, or its prefix is already registered.
+    pub fn register_with<P>(&mut self, into: fn(P) -> T) -> &mut Self
+    where
+        P: TypePrefixedPayload<N> + 'static,
+        T: 'static,
+    {
+        let prefix = P::TYPE.expect("untyped payloads cannot be registered");
+        assert!(
+            !self.decoders.contains_key(&prefix),
+            "payload type {prefix:02x?} is already registered"
+        );
+
+        let decoder = move |mut reader: &mut dyn io::Read| {
+            P::read(&mut reader)
+                .map(into)
+                .map_err(|err| error::in_field(err, error::type_name::<P>()))
+        };
+        self.decoders.insert(prefix, Box::new(decoder));
+        self
+    }
+
+    /// Whether a payload type is registered for `prefix`.
+    pub fn contains(&self, prefix: &[u8; N]) -> bool {
+        self.decoders.contains_key(prefix)
+    }
+
+    /// Registered type prefixes, in ascending order.
+    pub fn types(&self) -> impl Iterator<Item = &[u8; N]> + '_ {
+        self.decoders.keys()
+    }
+
+    /// Read the type prefix and decode the payload registered for it. Fails with
+    /// [`DecodeErrorKind::UnknownPayloadType`](crate::DecodeErrorKind::UnknownPayloadType) if
+    /// there is none.
+    pub fn read_payload<R: io::Read>(&self, reader: &mut R) -> io::Result<T> {
+        let mut reader = error::OffsetReader::new(reader);
+        let prefix = <[u8; N]>::read(&mut reader).map_err(|err| reader.locate(err))?;
+
+        match self.decoders.get(&prefix) {
+            Some(decode) => decode(&mut reader).map_err(|err| reader.locate(err)),
+            None => Err(reader.locate(error::unknown_payload_type(&prefix))),
+        }
+    }
+
+    /// Same as [`read_payload`](Self::read_payload), but fails if any bytes are left over.
+    pub fn read_slice(&self, buf: &[u8]) -> io::Result<T> {
+        let len = buf.len();
+        let buf = &mut &buf[..];
+        let out = self.read_payload(&mut BoundedReader::to_end(&mut *buf, len))?;
+
+        if buf.is_empty() {
+            Ok(out)
+        } else {
+            Err(error::invalid_length(len - buf.len(), len))
+        }
+    }
+}
+
+impl<const N: usize> PayloadRegistry<N> {
+    /// Register `P` under its type prefix, boxing decoded payloads as `dyn Any`.
+    ///
+    /// # Panics
+    ///
+    /// If `P` has no type prefix, or its prefix is already registered.
+    pub fn register_any<P>(&mut self) -> &mut Self
+    where
+        P: TypePrefixedPayload<N> + Send + 'static,
+    {
+        self.register_with(|payload: P| Box::new(payload) as Box<dyn Any + Send>)
+    }
+}
+
+impl<const N: usize, T> Default for PayloadRegistry<N, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize, T> fmt::Debug for PayloadRegistry<N, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PayloadRegistry")
+            .field("types", &self.decoders.keys())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{peek_type, DecodeError, DecodeErrorKind, WriteableBytes};
+    use hex_literal::hex;
+    use wormhole_io_derive::{Readable, TypePrefixedPayload, Writeable};
+
+    #[derive(Debug, Clone, PartialEq, Eq, Readable, Writeable, TypePrefixedPayload)]
+    #[payload(type = 1)]
+    struct Transfer {
+        amount: u64,
+        recipient: [u8; 4],
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq, Readable, Writeable, TypePrefixedPayload)]
+    #[payload(type = 3)]
+    struct TransferWithMessage {
+        amount: u64,
+        payload: WriteableBytes<u16>,
+    }
+
+    #[derive(Debug, PartialEq, Eq)]
+    enum Message {
+        Transfer(Transfer),
+        TransferWithMessage(TransferWithMessage),
+    }
+
+    impl From<Transfer> for Message {
+        fn from(transfer: Transfer) -> Self {
+            Self::Transfer(transfer)
+        }
+    }
+
+    impl From<TransferWithMessage> for Message {
+        fn from(transfer: TransferWithMessage) -> Self {
+            Self::TransferWithMessage(transfer)
+        }
+    }
+
+    fn registry() -> PayloadRegistry<1, Message> {
+        let mut registry = PayloadRegistry::new();
+        registry
+            .register::<Transfer>()
+            .register::<TransferWithMessage>();
+        registry
+    }
+
+    #[test]
+    fn read_registered_payloads() {
+        let registry = registry();
+        assert_eq!(registry.types().collect::<Vec<_>>(), [&[1], &[3]]);
+
+        let encoded = hex!("01 0000000000000045 deadbeef");
+        assert_eq!(
+            registry.read_slice(&encoded).unwrap(),
+            Message::Transfer(Transfer {
+                amount: 69,
+                recipient: hex!("deadbeef"),
+            })
+        );
+
+        let encoded = hex!("03 0000000000000045 0002 beef");
+        assert_eq!(
+            registry.read_slice(&encoded).unwrap(),
+            Message::TransferWithMessage(TransferWithMessage {
+                amount: 69,
+                payload: WriteableBytes::new(hex!("beef").to_vec()),
+            })
+        );
+    }
+
+    #[test]
+    fn read_any() {
+        let mut registry = PayloadRegistry::<1>::new();
+        registry.register_any::<Transfer>();
+
+        let encoded = hex!("01 0000000000000045 deadbeef");
+        let payload = registry.read_slice(&encoded).unwrap();
+        assert_eq!(
+            payload.downcast_ref::<Transfer>(),
+            Some(&Transfer {
+                amount: 69,
+                recipient: hex!("deadbeef"),
+            })
+        );
+    }
+
+    #[test]
+    fn unknown_payload_type() {
+        let encoded = hex!("02 0000000000000045");
+        assert_eq!(peek_type::<1>(&encoded).unwrap(), [2]);
+
+        let err = DecodeError::from_io(registry().read_slice(&encoded).unwrap_err());
+        assert!(matches!(
+            err.kind(),
+            DecodeErrorKind::UnknownPayloadType { found } if found == &[2]
+        ));
+        assert_eq!(err.to_string(), "unknown payload type 0x02 at offset 0");
+    }
+
+    #[test]
+    fn invalid_registered_payload() {
+        let encoded = hex!("03 0000000000000045 0004 beef");
+
+        let err = registry().read_slice(&encoded).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "TransferWithMessage.payload: unexpected end of input at offset 11"
+        );
+
+        let encoded = hex!("01 0000000000000045 deadbeef 00");
+        let err = DecodeError::from_io(registry().read_slice(&encoded).unwrap_err());
+        assert!(matches!(
+            err.kind(),
+            DecodeErrorKind::InvalidLength {
+                expected: 13,
+                found: 14
+            }
+        ));
+    }
+
+    #[test]
+    #[should_panic(expected = "payload type [01] is already registered")]
+    fn register_twice() {
+        registry().register::<Transfer>();
+    }
+}
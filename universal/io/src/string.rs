@@ -0,0 +1,327 @@
+use core::str::Utf8Error;
+
+#[cfg(feature = "alloc")]
+use alloc::{borrow::Cow, string::String};
+#[cfg(feature = "alloc")]
+use core::marker::PhantomData;
+
+#[cfg(feature = "alloc")]
+use crate::{bounded, error};
+use crate::{io, Readable, ReadableRef, Writeable};
+
+/// String stored in exactly `N` bytes, padded with trailing zeros (e.g. the symbol and name of a
+/// Token Bridge attestation).
+///
+/// The raw bytes are kept as read, so decoding never fails on invalid UTF-8. Use
+/// [`as_str`](FixedString::as_str) for strict decoding or
+/// [`to_string_lossy`](FixedString::to_string_lossy) for lossy decoding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FixedString<const N: usize>([u8; N]);
+
+impl<const N: usize> FixedString<N> {
+    /// Encode a string, truncating it to at most `N` bytes. Truncation never splits a UTF-8
+    /// character.
+    pub fn new(s: &str) -> Self {
+        let mut len = s.len().min(N);
+        while !s.is_char_boundary(len) {
+            len -= 1;
+        }
+
+        let mut out = [0; N];
+        out[..len].copy_from_slice(&s.as_bytes()[..len]);
+        Self(out)
+    }
+
+    /// The string bytes with the zero padding removed.
+    pub fn as_bytes(&self) -> &[u8] {
+        let len = self
+            .0
+            .iter()
+            .rposition(|x| *x != 0)
+            .map(|i| i + 1)
+            .unwrap_or_default();
+        &self.0[..len]
+    }
+
+    /// Decode the string, failing if it is not valid UTF-8.
+    pub fn as_str(&self) -> Result<&str, Utf8Error> {
+        core::str::from_utf8(self.as_bytes())
+    }
+
+    /// Decode the string, replacing invalid UTF-8 sequences with `U+FFFD`.
+    #[cfg(feature = "alloc")]
+    pub fn to_string_lossy(&self) -> Cow<'_, str> {
+        String::from_utf8_lossy(self.as_bytes())
+    }
+
+    /// All `N` bytes, including padding.
+    pub fn to_bytes(self) -> [u8; N] {
+        self.0
+    }
+}
+
+impl<const N: usize> Default for FixedString<N> {
+    fn default() -> Self {
+        Self([0; N])
+    }
+}
+
+impl<const N: usize> From<[u8; N]> for FixedString<N> {
+    fn from(bytes: [u8; N]) -> Self {
+        Self(bytes)
+    }
+}
+
+impl<const N: usize> From<&str> for FixedString<N> {
+    fn from(s: &str) -> Self {
+        Self::new(s)
+    }
+}
+
+impl<const N: usize> Readable for FixedString<N> {
+    fn read<R>(reader: &mut R) -> io::Result<Self>
+    where
+        Self: Sized,
+        R: io::Read,
+    {
+        <[u8; N]>::read(reader).map(Self)
+    }
+}
+
+impl<'a, const N: usize> ReadableRef<'a> for FixedString<N> {
+    fn read_ref(buf: &'a [u8]) -> io::Result<(Self, usize)> {
+        <[u8; N]>::read_ref(buf).map(|(bytes, consumed)| (Self(bytes), consumed))
+    }
+}
+
+impl<const N: usize> Writeable for FixedString<N> {
+    fn write<W>(&self, writer: &mut W) -> io::Result<()>
+    where
+        W: io::Write,
+    {
+        self.0.write(writer)
+    }
+
+    fn written_size(&self) -> usize {
+        N
+    }
+}
+
+/// Wrapper for `String`. Encoding is the same as [`WriteableBytes`](crate::WriteableBytes): the
+/// byte length as `L` (big endian), followed by the UTF-8 bytes. Decoding fails if the bytes are
+/// not valid UTF-8.
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct WriteableString<L>
+where
+    u32: From<L>,
+    L: Sized + Readable + Writeable + TryFrom<usize>,
+{
+    phantom: PhantomData<L>,
+    inner: String,
+}
+
+#[cfg(feature = "alloc")]
+impl<L> WriteableString<L>
+where
+    u32: From<L>,
+    L: Sized + Readable + Writeable + TryFrom<usize>,
+{
+    pub fn new(inner: String) -> Self {
+        Self {
+            phantom: PhantomData,
+            inner,
+        }
+    }
+
+    pub fn try_encoded_len(&self) -> io::Result<L> {
+        match L::try_from(self.inner.len()) {
+            Ok(len) => Ok(len),
+            Err(_) => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "L overflow when converting from usize",
+            )),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<L> TryFrom<String> for WriteableString<L>
+where
+    u32: From<L>,
+    L: Sized + Readable + Writeable + TryFrom<usize>,
+{
+    type Error = <L as TryFrom<usize>>::Error;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        L::try_from(s.len()).map(|_| Self::new(s))
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<L> From<WriteableString<L>> for String
+where
+    u32: From<L>,
+    L: Sized + Readable + Writeable + TryFrom<usize>,
+{
+    fn from(s: WriteableString<L>) -> Self {
+        s.inner
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<L> core::ops::Deref for WriteableString<L>
+where
+    u32: From<L>,
+    L: Sized + Readable + Writeable + TryFrom<usize>,
+{
+    type Target = String;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<L> core::ops::DerefMut for WriteableString<L>
+where
+    u32: From<L>,
+    L: Sized + Readable + Writeable + TryFrom<usize>,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<L> Readable for WriteableString<L>
+where
+    u32: From<L>,
+    L: Sized + Readable + Writeable + TryFrom<usize>,
+{
+    fn read<R>(reader: &mut R) -> io::Result<Self>
+    where
+        Self: Sized,
+        R: io::Read,
+    {
+        let len =
+            usize::try_from(u32::from(L::read(reader)?)).map_err(|_| error::length_overflow())?;
+
+        String::from_utf8(bounded::read_bytes(reader, len)?)
+            .map(Self::new)
+            .map_err(|_| error::invalid_utf8())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<L> Writeable for WriteableString<L>
+where
+    u32: From<L>,
+    L: Sized + Readable + Writeable + TryFrom<usize>,
+{
+    fn write<W>(&self, writer: &mut W) -> io::Result<()>
+    where
+        W: io::Write,
+    {
+        self.try_encoded_len()?.write(writer)?;
+        writer.write_all(self.inner.as_bytes())
+    }
+
+    fn written_size(&self) -> usize {
+        core::mem::size_of::<L>() + self.inner.len()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use hex_literal::hex;
+
+    #[test]
+    fn fixed_string_read_write() {
+        let symbol = FixedString::<32>::new("WETH");
+        assert_eq!(symbol.written_size(), 32);
+
+        let mut encoded = Vec::with_capacity(symbol.written_size());
+        symbol.write(&mut encoded).unwrap();
+        assert_eq!(
+            encoded,
+            hex!("5745544800000000000000000000000000000000000000000000000000000000")
+        );
+
+        let decoded = FixedString::<32>::read(&mut encoded.as_slice()).unwrap();
+        assert_eq!(decoded, symbol);
+        assert_eq!(decoded.as_str(), Ok("WETH"));
+    }
+
+    #[test]
+    fn fixed_string_empty() {
+        let empty = FixedString::<32>::default();
+        assert_eq!(empty.as_bytes(), b"");
+        assert_eq!(empty.as_str(), Ok(""));
+    }
+
+    #[test]
+    fn fixed_string_exact() {
+        let fire = FixedString::<32>::new("🔥🔥🔥🔥🔥🔥🔥🔥");
+        assert_eq!(fire.as_str(), Ok("🔥🔥🔥🔥🔥🔥🔥🔥"));
+    }
+
+    #[test]
+    fn fixed_string_truncates_at_char_boundary() {
+        // Each flame is 4 bytes, so only 7 of them fit in 31 bytes.
+        let fire = FixedString::<31>::new("🔥🔥🔥🔥🔥🔥🔥🔥");
+        assert_eq!(fire.as_str(), Ok("🔥🔥🔥🔥🔥🔥🔥"));
+        assert_eq!(fire.to_bytes()[28..], [0; 3]);
+    }
+
+    #[test]
+    fn fixed_string_invalid_utf8() {
+        let mut bytes = [0; 32];
+        bytes[..4].copy_from_slice(&"🔥".as_bytes()[..4]);
+        bytes[1] = 0xff;
+
+        let invalid = FixedString::from(bytes);
+        assert!(invalid.as_str().is_err());
+        assert_eq!(
+            invalid.to_string_lossy(),
+            "\u{fffd}\u{fffd}\u{fffd}\u{fffd}"
+        );
+    }
+
+    #[test]
+    fn string_read_write() {
+        let name = WriteableString::<u16>::new(String::from("Wrapped ether"));
+        assert_eq!(name.written_size(), 15);
+
+        let mut encoded = Vec::with_capacity(name.written_size());
+        name.write(&mut encoded).unwrap();
+        assert_eq!(encoded, hex!("000d57726170706564206574686572"));
+
+        let decoded = WriteableString::<u16>::read(&mut encoded.as_slice()).unwrap();
+        assert_eq!(decoded, name);
+    }
+
+    #[test]
+    fn string_invalid_utf8() {
+        let encoded = hex!("02ff00");
+
+        let err = WriteableString::<u8>::read(&mut &encoded[..]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(matches!(
+            crate::DecodeError::from_io(err).kind(),
+            crate::DecodeErrorKind::InvalidUtf8
+        ));
+    }
+
+    #[test]
+    fn string_length_overflow() {
+        let too_long = "a".repeat(256);
+        assert!(WriteableString::<u8>::try_from(too_long.clone()).is_err());
+
+        let err = WriteableString::<u8>::new(too_long)
+            .write(&mut Vec::new())
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
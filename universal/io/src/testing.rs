@@ -0,0 +1,300 @@
+//! Helpers for testing [`Readable`] and [`Writeable`] implementations.
+//!
+//! ```
+//! use wormhole_io::{testing, WriteableBytes};
+//!
+//! testing::assert_value_roundtrip(&(1u16, WriteableBytes::<u8>::new(vec![2, 3])));
+//! ```
+//!
+//! With the `arbitrary` feature, `assert_roundtrip` checks the same properties for many
+//! generated values.
+
+use alloc::{format, string::String, vec::Vec};
+use core::fmt::Debug;
+
+use crate::{Readable, TypePrefixedPayload, Writeable};
+
+/// Appended to encodings when checking that trailing bytes are not silently dropped.
+const TRAILING_BYTE: u8 = 0xa5;
+
+/// Check that `value` survives encoding and decoding:
+///
+/// * [`write`](Writeable::write) succeeds and produces [`written_size`](Writeable::written_size)
+///   bytes.
+/// * [`read`](Readable::read) of the encoding returns `value` and consumes every byte.
+/// * Trailing bytes are never silently dropped: reading the encoding followed by an extra byte
+///   either leaves that byte unread, fails or decodes to a different value (e.g. when the last
+///   field takes the rest of the input).
+///
+/// # Panics
+///
+/// If any of the above does not hold.
+pub fn assert_value_roundtrip<T>(value: &T)
+where
+    T: Readable + Writeable + PartialEq + Debug,
+{
+    if let Err(msg) = check_roundtrip(value) {
+        panic!("{msg}");
+    }
+}
+
+/// Payload counterpart to [`assert_value_roundtrip`], using
+/// [`write_payload`](TypePrefixedPayload::write_payload) and
+/// [`read_slice`](TypePrefixedPayload::read_slice). `read_slice` must also reject the encoding
+/// followed by an extra byte, unless it decodes to a different value.
+///
+/// # Panics
+///
+/// If `value` does not roundtrip.
+pub fn assert_payload_value_roundtrip<T, const N: usize>(value: &T)
+where
+    T: TypePrefixedPayload<N> + PartialEq,
+{
+    if let Err(msg) = check_payload_roundtrip(value) {
+        panic!("{msg}");
+    }
+}
+
+/// Run [`assert_value_roundtrip`] against values generated by `T`'s proptest strategy. Failing
+/// values are shrunk before panicking.
+#[cfg(feature = "arbitrary")]
+pub fn assert_roundtrip<T>()
+where
+    T: proptest::arbitrary::Arbitrary + Readable + Writeable + PartialEq,
+{
+    run(|value: T| check_roundtrip(&value));
+}
+
+/// Run [`assert_payload_value_roundtrip`] against values generated by `T`'s proptest strategy.
+#[cfg(feature = "arbitrary")]
+pub fn assert_payload_roundtrip<T, const N: usize>()
+where
+    T: proptest::arbitrary::Arbitrary + TypePrefixedPayload<N> + PartialEq,
+{
+    run(|value: T| check_payload_roundtrip(&value));
+}
+
+/// Proptest strategy that generates values with their [`arbitrary::Arbitrary`] impl, for types
+/// that implement it by hand or by derive. Shrinking the generated input bytes shrinks the values.
+#[cfg(feature = "arbitrary")]
+pub fn arbitrary_strategy<T>() -> proptest::strategy::BoxedStrategy<T>
+where
+    T: for<'a> arbitrary::Arbitrary<'a> + Debug + 'static,
+{
+    use proptest::prelude::*;
+
+    proptest::collection::vec(any::<u8>(), 0..1024)
+        .prop_filter_map("not enough input for a value", |input| {
+            T::arbitrary_take_rest(arbitrary::Unstructured::new(&input)).ok()
+        })
+        .boxed()
+}
+
+#[cfg(feature = "arbitrary")]
+fn run<T, F>(check: F)
+where
+    T: proptest::arbitrary::Arbitrary,
+    F: Fn(T) -> Result<(), String>,
+{
+    use proptest::test_runner::{TestCaseError, TestRunner};
+
+    let result = TestRunner::default().run(&proptest::arbitrary::any::<T>(), |value| {
+        check(value).map_err(TestCaseError::fail)
+    });
+    if let Err(err) = result {
+        panic!("{err}");
+    }
+}
+
+fn check_roundtrip<T>(value: &T) -> Result<(), String>
+where
+    T: Readable + Writeable + PartialEq + Debug,
+{
+    let mut encoded = Vec::with_capacity(value.written_size());
+    value
+        .write(&mut encoded)
+        .map_err(|err| format!("failed to write {value:?}: {err}"))?;
+    check_len(value, encoded.len(), value.written_size())?;
+
+    let mut reader = encoded.as_slice();
+    let decoded = T::read(&mut reader).map_err(|err| format!("failed to read {value:?}: {err}"))?;
+    check_decoded(value, &decoded)?;
+    if !reader.is_empty() {
+        return Err(format!(
+            "reading {value:?} left {} of {} bytes unread",
+            reader.len(),
+            encoded.len()
+        ));
+    }
+
+    encoded.push(TRAILING_BYTE);
+    let mut reader = encoded.as_slice();
+    match T::read(&mut reader) {
+        Ok(decoded) if decoded == *value && reader.is_empty() => Err(format!(
+            "reading {value:?} silently dropped a trailing byte"
+        )),
+        _ => Ok(()),
+    }
+}
+
+fn check_payload_roundtrip<T, const N: usize>(value: &T) -> Result<(), String>
+where
+    T: TypePrefixedPayload<N> + PartialEq,
+{
+    let mut encoded = Vec::with_capacity(value.payload_written_size());
+    value
+        .write_payload(&mut encoded)
+        .map_err(|err| format!("failed to write {value:?}: {err}"))?;
+    check_len(value, encoded.len(), value.payload_written_size())?;
+
+    let decoded =
+        T::read_slice(&encoded).map_err(|err| format!("failed to read {value:?}: {err}"))?;
+    check_decoded(value, &decoded)?;
+
+    encoded.push(TRAILING_BYTE);
+    match T::read_slice(&encoded) {
+        Ok(decoded) if decoded == *value => {
+            Err(format!("reading {value:?} accepted a trailing byte"))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn check_len<T: Debug>(value: &T, actual: usize, expected: usize) -> Result<(), String> {
+    if actual == expected {
+        Ok(())
+    } else {
+        Err(format!(
+            "{value:?} was written in {actual} bytes, but its written size is {expected}"
+        ))
+    }
+}
+
+fn check_decoded<T: PartialEq + Debug>(value: &T, decoded: &T) -> Result<(), String> {
+    if decoded == value {
+        Ok(())
+    } else {
+        Err(format!("{value:?} was read back as {decoded:?}"))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{io, WriteableBytes, WriteableString, WriteableVec};
+    use wormhole_io_derive::{Readable, TypePrefixedPayload, Writeable};
+
+    #[derive(Debug, Clone, PartialEq, Eq, Readable, Writeable, TypePrefixedPayload)]
+    #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+    #[payload(type = 1)]
+    struct Message {
+        sequence: u64,
+        payload: WriteableBytes<u16>,
+    }
+
+    #[cfg(feature = "arbitrary")]
+    impl proptest::arbitrary::Arbitrary for Message {
+        type Parameters = ();
+        type Strategy = proptest::strategy::BoxedStrategy<Self>;
+
+        fn arbitrary_with(_: ()) -> Self::Strategy {
+            arbitrary_strategy()
+        }
+    }
+
+    /// Reads a single byte, but claims to write two.
+    #[derive(Debug, PartialEq)]
+    struct WrongSize(u8);
+
+    impl Readable for WrongSize {
+        fn read<R: io::Read>(reader: &mut R) -> io::Result<Self> {
+            u8::read(reader).map(Self)
+        }
+    }
+
+    impl Writeable for WrongSize {
+        fn write<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+            self.0.write(writer)
+        }
+
+        fn written_size(&self) -> usize {
+            2
+        }
+    }
+
+    /// Reads the rest of the input, but only keeps the first byte.
+    #[derive(Debug, PartialEq)]
+    struct DropsTrailing(u8);
+
+    impl Readable for DropsTrailing {
+        fn read<R: io::Read>(reader: &mut R) -> io::Result<Self> {
+            let value = u8::read(reader)?;
+            io::Read::read_to_end(reader, &mut Vec::new())?;
+            Ok(Self(value))
+        }
+    }
+
+    impl Writeable for DropsTrailing {
+        fn write<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+            self.0.write(writer)
+        }
+
+        fn written_size(&self) -> usize {
+            1
+        }
+    }
+
+    #[test]
+    fn value_roundtrip() {
+        assert_value_roundtrip(&(69u16, [1u32, 2], Some(true)));
+        assert_value_roundtrip(&WriteableVec::<u8, _>::new(vec![
+            WriteableString::<u16>::new("All your base".into()),
+            WriteableString::<u16>::new("are belong to us".into()),
+        ]));
+        assert_payload_value_roundtrip(&Message {
+            sequence: 2,
+            payload: WriteableBytes::new(b"hello".to_vec()),
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "was written in 1 bytes, but its written size is 2")]
+    fn wrong_written_size() {
+        assert_value_roundtrip(&WrongSize(1));
+    }
+
+    #[test]
+    #[should_panic(expected = "silently dropped a trailing byte")]
+    fn dropped_trailing_byte() {
+        assert_value_roundtrip(&DropsTrailing(1));
+    }
+
+    #[cfg(feature = "arbitrary")]
+    #[test]
+    fn generated_roundtrip() {
+        use crate::FixedString;
+
+        assert_roundtrip::<WriteableBytes<u8>>();
+        assert_roundtrip::<WriteableBytes<u32>>();
+        assert_roundtrip::<WriteableVec<u16, [u8; 3]>>();
+        assert_roundtrip::<WriteableString<u8>>();
+        assert_roundtrip::<FixedString<32>>();
+        assert_payload_roundtrip::<Message, 1>();
+    }
+
+    #[cfg(feature = "arbitrary")]
+    #[test]
+    fn generated_lengths_fit() {
+        use proptest::{
+            strategy::{Strategy, ValueTree},
+            test_runner::TestRunner,
+        };
+
+        let mut runner = TestRunner::deterministic();
+        let strategy = proptest::arbitrary::any::<WriteableBytes<u8>>();
+        for _ in 0..256 {
+            let bytes = strategy.new_tree(&mut runner).unwrap().current();
+            assert!(bytes.try_encoded_len().is_ok());
+        }
+    }
+}
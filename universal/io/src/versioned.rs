@@ -0,0 +1,411 @@
+//! Payloads that evolve by appending fields, without breaking readers of older versions.
+//!
+//! A [`Versioned`] payload is encoded as a version byte followed by the fields of that version.
+//! Each version only appends fields to the previous one, so a reader that knows versions up to
+//! [`VersionedPayload::VERSION`] can still decode newer versions: it reads the fields it knows and
+//! keeps the rest as opaque [`Extensions`], which are written back verbatim. A contract can then
+//! relay or store a newer payload without understanding, or losing, its new fields.
+//!
+//! ```
+//! use wormhole_io::{io, Readable, Versioned, VersionedPayload, Writeable};
+//!
+//! /// Version 1 has an amount, version 2 adds a fee.
+//! #[derive(Debug, Clone, PartialEq, Eq)]
+//! struct Transfer {
+//!     amount: u64,
+//!     fee: Option<u64>,
+//! }
+//!
+//! impl VersionedPayload for Transfer {
+//!     const VERSION: u8 = 2;
+//!
+//!     fn version(&self) -> u8 {
+//!         if self.fee.is_some() { 2 } else { 1 }
+//!     }
+//!
+//!     fn read_version<R: io::Read>(version: u8, reader: &mut R) -> io::Result<Self> {
+//!         let amount = u64::read(reader)?;
+//!         let fee = if version >= 2 { Some(u64::read(reader)?) } else { None };
+//!         Ok(Self { amount, fee })
+//!     }
+//!
+//!     fn write_fields<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+//!         self.amount.write(writer)?;
+//!         self.fee.map_or(Ok(()), |fee| fee.write(writer))
+//!     }
+//!
+//!     fn fields_written_size(&self) -> usize {
+//!         if self.fee.is_some() { 16 } else { 8 }
+//!     }
+//! }
+//!
+//! // Version 3 appends two bytes this reader does not know about.
+//! let encoded = [3, 0, 0, 0, 0, 0, 0, 0, 69, 0, 0, 0, 0, 0, 0, 0, 1, 0xbe, 0xef];
+//! let transfer = Versioned::<Transfer>::read(&mut &encoded[..]).unwrap();
+//! assert_eq!(transfer.version(), 3);
+//! assert_eq!(transfer.payload(), &Transfer { amount: 69, fee: Some(1) });
+//! assert_eq!(transfer.extensions().as_slice(), [0xbe, 0xef]);
+//!
+//! let mut written = Vec::new();
+//! transfer.write(&mut written).unwrap();
+//! assert_eq!(written, encoded);
+//! ```
+//!
+//! To give a versioned payload a type prefix, wrap it in a struct deriving
+//! [`TypePrefixedPayload`](crate::TypePrefixedPayload). Since the extensions take the rest of the
+//! input, [`read_slice`](crate::TypePrefixedPayload::read_slice) accepts newer versions with extra
+//! fields, unless [`VersionedPayload::EXTENSIBLE`] is `false`.
+
+use alloc::vec::Vec;
+
+use crate::{error, io, Readable, Writeable};
+
+/// Payload whose fields depend on a version, read and written by [`Versioned`].
+pub trait VersionedPayload: Sized {
+    /// Latest version this type knows the fields of.
+    const VERSION: u8;
+
+    /// Oldest version that can be read.
+    const MIN_VERSION: u8 = 1;
+
+    /// Whether versions newer than [`VERSION`](Self::VERSION) can be read, keeping any bytes after
+    /// the known fields as [`Extensions`]. Otherwise, newer versions fail to decode and bytes after
+    /// the fields are left unread, as they would be for any other type.
+    const EXTENSIBLE: bool = true;
+
+    /// Version of this value, which determines its fields.
+    fn version(&self) -> u8;
+
+    /// Read the fields of `version`, which is between [`MIN_VERSION`](Self::MIN_VERSION) and
+    /// [`VERSION`](Self::VERSION).
+    fn read_version<R: io::Read>(version: u8, reader: &mut R) -> io::Result<Self>;
+
+    /// Write the fields of [`version`](Self::version), without the version byte.
+    fn write_fields<W: io::Write>(&self, writer: &mut W) -> io::Result<()>;
+
+    /// Returns the number of bytes [write_fields](Self::write_fields) will produce.
+    fn fields_written_size(&self) -> usize;
+}
+
+/// A [`VersionedPayload`] with its version byte and, for versions newer than the payload type
+/// knows, the bytes of the fields it does not know.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Versioned<T> {
+    version: u8,
+    payload: T,
+    extensions: Extensions,
+}
+
+impl<T: VersionedPayload> Versioned<T> {
+    pub fn new(payload: T) -> Self {
+        Self {
+            version: payload.version(),
+            payload,
+            extensions: Extensions::default(),
+        }
+    }
+
+    /// Version that will be written: the version that was read, if it is newer than the payload
+    /// type knows, or else the version of the payload.
+    pub fn version(&self) -> u8 {
+        if self.version > T::VERSION {
+            self.version
+        } else {
+            self.payload.version()
+        }
+    }
+
+    pub fn payload(&self) -> &T {
+        &self.payload
+    }
+
+    /// Mutable access to the payload. Extensions are kept, so changing the version of a payload
+    /// read from a newer version is only meaningful if its extensions are cleared.
+    pub fn payload_mut(&mut self) -> &mut T {
+        &mut self.payload
+    }
+
+    pub fn into_payload(self) -> T {
+        self.payload
+    }
+
+    /// Fields of a newer version than the payload type knows, or any other bytes that followed
+    /// the known fields.
+    pub fn extensions(&self) -> &Extensions {
+        &self.extensions
+    }
+
+    pub fn extensions_mut(&mut self) -> &mut Extensions {
+        &mut self.extensions
+    }
+}
+
+impl<T: VersionedPayload> From<T> for Versioned<T> {
+    fn from(payload: T) -> Self {
+        Self::new(payload)
+    }
+}
+
+impl<T: VersionedPayload> Readable for Versioned<T> {
+    fn read<R>(reader: &mut R) -> io::Result<Self>
+    where
+        Self: Sized,
+        R: io::Read,
+    {
+        let version = u8::read(reader)?;
+        if version < T::MIN_VERSION || (version > T::VERSION && !T::EXTENSIBLE) {
+            return Err(error::unsupported_version(version));
+        }
+
+        let payload = T::read_version(version.min(T::VERSION), reader)?;
+        let extensions = if T::EXTENSIBLE {
+            Extensions::read(reader).map_err(|err| error::in_field(err, "extensions"))?
+        } else {
+            Extensions::default()
+        };
+
+        Ok(Self {
+            version,
+            payload,
+            extensions,
+        })
+    }
+}
+
+impl<T: VersionedPayload> Writeable for Versioned<T> {
+    fn write<W>(&self, writer: &mut W) -> io::Result<()>
+    where
+        W: io::Write,
+    {
+        self.version().write(writer)?;
+        self.payload.write_fields(writer)?;
+        self.extensions.write(writer)
+    }
+
+    fn written_size(&self) -> usize {
+        1 + self.payload.fields_written_size() + self.extensions.written_size()
+    }
+}
+
+/// Opaque bytes at the end of a payload, which reading keeps and writing re-emits verbatim. Reads
+/// all remaining input, so it must be the last field.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Extensions(Vec<u8>);
+
+impl Extensions {
+    pub fn new(bytes: Vec<u8>) -> Self {
+        Self(bytes)
+    }
+}
+
+impl From<Vec<u8>> for Extensions {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self(bytes)
+    }
+}
+
+impl From<Extensions> for Vec<u8> {
+    fn from(extensions: Extensions) -> Self {
+        extensions.0
+    }
+}
+
+impl core::ops::Deref for Extensions {
+    type Target = Vec<u8>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl core::ops::DerefMut for Extensions {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl Readable for Extensions {
+    fn read<R>(reader: &mut R) -> io::Result<Self>
+    where
+        Self: Sized,
+        R: io::Read,
+    {
+        let mut bytes = Vec::new();
+        let mut chunk = [0; 256];
+        loop {
+            match reader.read(&mut chunk) {
+                Ok(0) => return Ok(Self(bytes)),
+                Ok(n) => bytes.extend_from_slice(&chunk[..n]),
+                #[cfg(feature = "std")]
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => return Err(err),
+            }
+        }
+    }
+}
+
+impl Writeable for Extensions {
+    fn write<W>(&self, writer: &mut W) -> io::Result<()>
+    where
+        W: io::Write,
+    {
+        writer.write_all(&self.0)
+    }
+
+    fn written_size(&self) -> usize {
+        self.0.len()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{DecodeError, DecodeErrorKind, TypePrefixedPayload as _};
+    use hex_literal::hex;
+    use wormhole_io_derive::{Readable, TypePrefixedPayload, Writeable};
+
+    /// Version 1 has a recipient, version 2 adds a memo.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    struct Message {
+        recipient: [u8; 4],
+        memo: Option<u16>,
+    }
+
+    impl VersionedPayload for Message {
+        const VERSION: u8 = 2;
+
+        fn version(&self) -> u8 {
+            if self.memo.is_some() {
+                2
+            } else {
+                1
+            }
+        }
+
+        fn read_version<R: io::Read>(version: u8, reader: &mut R) -> io::Result<Self> {
+            let recipient = <[u8; 4]>::read(reader)?;
+            let memo = match version {
+                1 => None,
+                _ => Some(u16::read(reader)?),
+            };
+            Ok(Self { recipient, memo })
+        }
+
+        fn write_fields<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+            self.recipient.write(writer)?;
+            match self.memo {
+                Some(memo) => memo.write(writer),
+                None => Ok(()),
+            }
+        }
+
+        fn fields_written_size(&self) -> usize {
+            4 + self.memo.map_or(0, |_| 2)
+        }
+    }
+
+    /// Same fields as [`Message`], but rejects newer versions.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    struct StrictMessage(Message);
+
+    impl VersionedPayload for StrictMessage {
+        const VERSION: u8 = 2;
+        const EXTENSIBLE: bool = false;
+
+        fn version(&self) -> u8 {
+            self.0.version()
+        }
+
+        fn read_version<R: io::Read>(version: u8, reader: &mut R) -> io::Result<Self> {
+            Message::read_version(version, reader).map(Self)
+        }
+
+        fn write_fields<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+            self.0.write_fields(writer)
+        }
+
+        fn fields_written_size(&self) -> usize {
+            self.0.fields_written_size()
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq, Readable, Writeable, TypePrefixedPayload)]
+    #[payload(type = 7)]
+    struct Payload(Versioned<Message>);
+
+    #[derive(Debug, Clone, PartialEq, Eq, Readable, Writeable, TypePrefixedPayload)]
+    #[payload(type = 7)]
+    struct StrictPayload(Versioned<StrictMessage>);
+
+    #[test]
+    fn known_versions() {
+        let v1 = Versioned::new(Message {
+            recipient: hex!("deadbeef"),
+            memo: None,
+        });
+        crate::testing::assert_payload_value_roundtrip(&Payload(v1.clone()));
+        assert_eq!(
+            Payload::read_slice(&hex!("07 01 deadbeef")).unwrap(),
+            Payload(v1)
+        );
+
+        let v2 = Payload::read_slice(&hex!("07 02 deadbeef 0045")).unwrap();
+        assert_eq!(v2.0.version(), 2);
+        assert_eq!(v2.0.payload().memo, Some(69));
+        assert!(v2.0.extensions().is_empty());
+    }
+
+    #[test]
+    fn newer_version() {
+        let encoded = hex!("07 03 deadbeef 0045 c0ffee");
+
+        let v3 = Payload::read_slice(&encoded).unwrap();
+        assert_eq!(v3.0.version(), 3);
+        assert_eq!(v3.0.payload().memo, Some(69));
+        assert_eq!(v3.0.extensions().as_slice(), hex!("c0ffee"));
+
+        let mut modified = v3.clone();
+        modified.0.payload_mut().memo = Some(70);
+        let mut written = Vec::new();
+        modified.write_payload(&mut written).unwrap();
+        assert_eq!(written, hex!("07 03 deadbeef 0046 c0ffee"));
+        assert_eq!(modified.payload_written_size(), encoded.len());
+    }
+
+    #[test]
+    fn strict() {
+        assert!(StrictPayload::read_slice(&hex!("07 02 deadbeef 0045")).is_ok());
+
+        let err = DecodeError::from_io(
+            StrictPayload::read_slice(&hex!("07 03 deadbeef 0045")).unwrap_err(),
+        );
+        assert!(matches!(
+            err.kind(),
+            DecodeErrorKind::UnsupportedVersion { found: 3 }
+        ));
+        assert_eq!(
+            err.to_string(),
+            "StrictPayload.0: unsupported version 3 at offset 1"
+        );
+
+        let err = DecodeError::from_io(
+            StrictPayload::read_slice(&hex!("07 02 deadbeef 0045 00")).unwrap_err(),
+        );
+        assert!(matches!(
+            err.kind(),
+            DecodeErrorKind::InvalidLength {
+                expected: 8,
+                found: 9
+            }
+        ));
+    }
+
+    #[test]
+    fn too_old_version() {
+        let err = DecodeError::from_io(Payload::read_slice(&hex!("07 00 deadbeef")).unwrap_err());
+        assert!(matches!(
+            err.kind(),
+            DecodeErrorKind::UnsupportedVersion { found: 0 }
+        ));
+    }
+}
@@ -0,0 +1,40 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use wormhole_raw_vaas::cctp::{
+    CircleIntegrationDecree, CircleIntegrationGovPayload, WormholeCctpMessage, WormholeCctpPayload,
+};
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(payload) = WormholeCctpPayload::parse(data) {
+        if let WormholeCctpMessage::Deposit(deposit) = payload.message() {
+            deposit.token_address();
+            deposit.amount();
+            deposit.source_cctp_domain();
+            deposit.destination_cctp_domain();
+            deposit.cctp_nonce();
+            deposit.burn_source();
+            deposit.mint_recipient();
+            assert_eq!(deposit.payload().len(), usize::from(deposit.payload_len()));
+        }
+    }
+
+    if let Ok(gov) = CircleIntegrationGovPayload::parse(data) {
+        match gov.decree() {
+            CircleIntegrationDecree::UpdateWormholeFinality(inner) => {
+                inner.chain();
+                inner.finality();
+            }
+            CircleIntegrationDecree::RegisterEmitterAndDomain(inner) => {
+                inner.chain();
+                inner.foreign_chain();
+                inner.foreign_emitter();
+                inner.cctp_domain();
+            }
+            CircleIntegrationDecree::ContractUpgrade(inner) => {
+                inner.chain();
+                inner.implementation();
+            }
+        }
+    }
+});
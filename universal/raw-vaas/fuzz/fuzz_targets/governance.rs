@@ -0,0 +1,51 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use wormhole_raw_vaas::{
+    core::{CoreBridgeDecree, CoreBridgeGovPayload},
+    AnyGovernance, GovernanceMessage,
+};
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(message) = GovernanceMessage::parse(data) {
+        message.header().module();
+        message.decree();
+    }
+
+    if let Ok(gov) = AnyGovernance::parse(data) {
+        gov.module();
+        gov.target_chain();
+    }
+
+    if let Ok(gov) = CoreBridgeGovPayload::parse(data) {
+        match gov.decree() {
+            CoreBridgeDecree::ContractUpgrade(inner) => {
+                inner.chain();
+                inner.implementation();
+            }
+            CoreBridgeDecree::GuardianSetUpdate(inner) => {
+                inner.new_index();
+                let num_guardians = usize::from(inner.num_guardians());
+                for i in 0..num_guardians {
+                    assert_eq!(inner.try_guardian_at(i).ok(), inner.guardian_at(i));
+                }
+                assert_eq!(inner.guardian_at(num_guardians), None);
+                assert!(inner.try_guardian_at(num_guardians).is_err());
+            }
+            CoreBridgeDecree::SetMessageFee(inner) => {
+                inner.chain();
+                inner.fee();
+            }
+            CoreBridgeDecree::TransferFees(inner) => {
+                inner.chain();
+                inner.amount();
+                inner.recipient();
+            }
+            CoreBridgeDecree::RecoverChainId(inner) => {
+                inner.recovered_chain();
+                inner.evm_chain_id();
+                inner.new_chain();
+            }
+        }
+    }
+});
@@ -0,0 +1,55 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use wormhole_raw_vaas::token_bridge::{
+    TokenBridgeDecree, TokenBridgeGovPayload, TokenBridgeMessage, TokenBridgePayload,
+};
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(payload) = TokenBridgePayload::parse(data) {
+        match payload.message() {
+            TokenBridgeMessage::Transfer(transfer) => {
+                transfer.amount();
+                transfer.token_address();
+                transfer.token_chain();
+                transfer.recipient();
+                transfer.recipient_chain();
+                transfer.relayer_fee();
+            }
+            TokenBridgeMessage::Attestation(attestation) => {
+                attestation.token_address();
+                attestation.token_chain();
+                attestation.decimals();
+                attestation.symbol();
+                attestation.name();
+            }
+            TokenBridgeMessage::TransferWithMessage(transfer) => {
+                transfer.amount();
+                transfer.token_address();
+                transfer.token_chain();
+                transfer.redeemer();
+                transfer.redeemer_chain();
+                transfer.sender();
+                transfer.payload();
+            }
+        }
+    }
+
+    if let Ok(gov) = TokenBridgeGovPayload::parse(data) {
+        match gov.decree() {
+            TokenBridgeDecree::RegisterChain(inner) => {
+                inner.foreign_chain();
+                inner.foreign_emitter();
+            }
+            TokenBridgeDecree::ContractUpgrade(inner) => {
+                inner.chain();
+                inner.implementation();
+            }
+            TokenBridgeDecree::RecoverChainId(inner) => {
+                inner.recovered_chain();
+                inner.evm_chain_id();
+                inner.new_chain();
+            }
+        }
+    }
+});
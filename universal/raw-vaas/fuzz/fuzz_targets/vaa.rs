@@ -0,0 +1,30 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use wormhole_raw_vaas::{GuardianSetSig, Header, Vaa};
+
+fuzz_target!(|data: &[u8]| {
+    let _ = Header::parse(data);
+    let _ = GuardianSetSig::parse(data);
+
+    if let Ok(vaa) = Vaa::parse(data) {
+        vaa.version();
+        vaa.guardian_set_index();
+        vaa.signature_count();
+        for sig in vaa.signatures() {
+            sig.guardian_index();
+            sig.rs();
+            sig.v();
+            sig.signature();
+        }
+
+        let body = vaa.body();
+        body.timestamp();
+        body.nonce();
+        body.emitter_chain();
+        body.emitter_address();
+        body.sequence();
+        body.consistency_level();
+        assert_eq!(body.payload(), vaa.payload());
+    }
+});
@@ -0,0 +1,394 @@
+//! Annotated hex dumps of VAAs and their payloads, for debugging. Requires the `dump` feature.
+//!
+//! The header, each [`GuardianSetSig`](crate::GuardianSetSig), the body fields and the payload
+//! get a line each with offset, length, name, raw hex and decoded value (see
+//! [`wormhole_io::dump`]). Malformed input is dumped up to the field that could not be decoded.
+//!
+//! The payload is decoded by the first of these that matches:
+//! * Core Bridge, Token Bridge or Circle Integration governance, by governance module.
+//! * CCTP deposit, if it starts with 1 and its payload length accounts for the rest of the bytes.
+//! * Token Bridge message, if it starts with 1, 2 or 3.
+//!
+//! Otherwise the payload is dumped as raw bytes.
+
+use alloc::{boxed::Box, vec::Vec};
+
+use wormhole_io::{
+    dump::{dump_layout, Dump},
+    layout::{Field, IntEncoding, LayoutItem, Variant},
+};
+
+use crate::{cctp, core, token_bridge, Vaa};
+
+/// Dump a VAA, which may be truncated or otherwise malformed.
+pub fn dump(vaa: &[u8]) -> Dump {
+    dump_layout(&vaa_layout(payload_layout(payload_span(vaa))), vaa)
+}
+
+/// Dump a VAA payload, which may be truncated or otherwise malformed.
+pub fn dump_payload(payload: &[u8]) -> Dump {
+    dump_layout(&payload_layout(payload), payload)
+}
+
+impl Vaa<'_> {
+    /// Annotated hex dump of the VAA. See [`dump`](crate::dump).
+    pub fn dump(&self) -> Dump {
+        dump(self.span)
+    }
+}
+
+/// Payload bytes of a VAA, or whatever is left of them.
+fn payload_span(vaa: &[u8]) -> &[u8] {
+    let num_signatures = vaa.get(5).copied().unwrap_or_default();
+    let start = 6 + usize::from(num_signatures) * 66 + 51;
+    vaa.get(start..).unwrap_or_default()
+}
+
+fn vaa_layout(payload: LayoutItem) -> LayoutItem {
+    let signature = LayoutItem::structure(
+        "GuardianSetSig",
+        [
+            Field::new("guardian_index", LayoutItem::uint(1)),
+            Field::new("r", LayoutItem::bytes(32)),
+            Field::new("s", LayoutItem::bytes(32)),
+            Field::new("v", LayoutItem::uint(1)),
+        ],
+    );
+    let header = LayoutItem::structure(
+        "Header",
+        [
+            Field::new("version", LayoutItem::uint(1)),
+            Field::new("guardian_set_index", LayoutItem::uint(4)),
+            Field::new(
+                "signatures",
+                LayoutItem::Vec {
+                    len: IntEncoding::big(1),
+                    item: Box::new(signature),
+                },
+            ),
+        ],
+    );
+    let body = LayoutItem::structure(
+        "Body",
+        [
+            Field::new("timestamp", LayoutItem::uint(4)),
+            Field::new("nonce", LayoutItem::uint(4)),
+            Field::new("emitter_chain", LayoutItem::uint(2)),
+            Field::new("emitter_address", LayoutItem::bytes(32)),
+            Field::new("sequence", LayoutItem::uint(8)),
+            Field::new("consistency_level", LayoutItem::uint(1)),
+            Field::new("payload", payload),
+        ],
+    );
+
+    LayoutItem::structure(
+        "Vaa",
+        [Field::new("header", header), Field::new("body", body)],
+    )
+}
+
+fn payload_layout(payload: &[u8]) -> LayoutItem {
+    let module = payload.get(..32);
+    if module == Some(core::GOV_MODULE) {
+        governance_layout(core::GOV_MODULE, core_decree_layout())
+    } else if module == Some(token_bridge::GOV_MODULE) {
+        governance_layout(token_bridge::GOV_MODULE, token_bridge_decree_layout())
+    } else if module == Some(cctp::GOV_MODULE) {
+        governance_layout(cctp::GOV_MODULE, cctp_decree_layout())
+    } else if is_cctp_deposit(payload) {
+        cctp_message_layout()
+    } else if matches!(payload.first(), Some(1..=3)) {
+        token_bridge_message_layout()
+    } else {
+        LayoutItem::Remainder
+    }
+}
+
+fn is_cctp_deposit(payload: &[u8]) -> bool {
+    match payload.get(145..147) {
+        Some(&[hi, lo]) => {
+            payload[0] == 1 && payload.len() == 147 + usize::from(u16::from_be_bytes([hi, lo]))
+        }
+        _ => false,
+    }
+}
+
+fn governance_layout(module: &[u8; 32], decree: LayoutItem) -> LayoutItem {
+    LayoutItem::structure(
+        "GovernanceMessage",
+        [
+            Field::new(
+                "module",
+                LayoutItem::Constant {
+                    bytes: module.to_vec(),
+                },
+            ),
+            Field::new("decree", decree),
+        ],
+    )
+}
+
+fn u16_field(name: &'static str) -> Field {
+    Field::new(name, LayoutItem::uint(2))
+}
+
+fn bytes32_field(name: &'static str) -> Field {
+    Field::new(name, LayoutItem::bytes(32))
+}
+
+fn amount_field(name: &'static str) -> Field {
+    Field::new(name, LayoutItem::uint(32))
+}
+
+fn contract_upgrade_fields() -> Vec<Field> {
+    [u16_field("chain"), bytes32_field("implementation")].into()
+}
+
+fn recover_chain_id_fields() -> Vec<Field> {
+    [
+        u16_field("recovered_chain"),
+        amount_field("evm_chain_id"),
+        u16_field("new_chain"),
+    ]
+    .into()
+}
+
+fn core_decree_layout() -> LayoutItem {
+    LayoutItem::enumeration(
+        "CoreBridgeDecree",
+        IntEncoding::big(1),
+        [
+            Variant::new("ContractUpgrade", 1, contract_upgrade_fields()),
+            Variant::new(
+                "GuardianSetUpdate",
+                2,
+                [
+                    u16_field("chain"),
+                    Field::new("new_index", LayoutItem::uint(4)),
+                    Field::new(
+                        "guardians",
+                        LayoutItem::Vec {
+                            len: IntEncoding::big(1),
+                            item: Box::new(LayoutItem::bytes(20)),
+                        },
+                    ),
+                ],
+            ),
+            Variant::new(
+                "SetMessageFee",
+                3,
+                [u16_field("chain"), amount_field("fee")],
+            ),
+            Variant::new(
+                "TransferFees",
+                4,
+                [
+                    u16_field("chain"),
+                    amount_field("amount"),
+                    bytes32_field("recipient"),
+                ],
+            ),
+            Variant::new("RecoverChainId", 5, recover_chain_id_fields()),
+        ],
+    )
+}
+
+fn token_bridge_decree_layout() -> LayoutItem {
+    LayoutItem::enumeration(
+        "TokenBridgeDecree",
+        IntEncoding::big(1),
+        [
+            Variant::new(
+                "RegisterChain",
+                1,
+                [
+                    u16_field("chain"),
+                    u16_field("foreign_chain"),
+                    bytes32_field("foreign_emitter"),
+                ],
+            ),
+            Variant::new("ContractUpgrade", 2, contract_upgrade_fields()),
+            Variant::new("RecoverChainId", 3, recover_chain_id_fields()),
+        ],
+    )
+}
+
+fn cctp_decree_layout() -> LayoutItem {
+    LayoutItem::enumeration(
+        "CircleIntegrationDecree",
+        IntEncoding::big(1),
+        [
+            Variant::new(
+                "UpdateWormholeFinality",
+                1,
+                [
+                    u16_field("chain"),
+                    Field::new("finality", LayoutItem::uint(1)),
+                ],
+            ),
+            Variant::new(
+                "RegisterEmitterAndDomain",
+                2,
+                [
+                    u16_field("chain"),
+                    u16_field("foreign_chain"),
+                    bytes32_field("foreign_emitter"),
+                    Field::new("cctp_domain", LayoutItem::uint(4)),
+                ],
+            ),
+            Variant::new("ContractUpgrade", 3, contract_upgrade_fields()),
+        ],
+    )
+}
+
+fn token_bridge_message_layout() -> LayoutItem {
+    LayoutItem::enumeration(
+        "TokenBridgeMessage",
+        IntEncoding::big(1),
+        [
+            Variant::new(
+                "Transfer",
+                1,
+                [
+                    amount_field("amount"),
+                    bytes32_field("token_address"),
+                    u16_field("token_chain"),
+                    bytes32_field("recipient"),
+                    u16_field("recipient_chain"),
+                    amount_field("relayer_fee"),
+                ],
+            ),
+            Variant::new(
+                "Attestation",
+                2,
+                [
+                    bytes32_field("token_address"),
+                    u16_field("token_chain"),
+                    Field::new("decimals", LayoutItem::uint(1)),
+                    Field::new("symbol", LayoutItem::FixedString { size: 32 }),
+                    Field::new("name", LayoutItem::FixedString { size: 32 }),
+                ],
+            ),
+            Variant::new(
+                "TransferWithMessage",
+                3,
+                [
+                    amount_field("amount"),
+                    bytes32_field("token_address"),
+                    u16_field("token_chain"),
+                    bytes32_field("redeemer"),
+                    u16_field("redeemer_chain"),
+                    bytes32_field("sender"),
+                    Field::new("payload", LayoutItem::Remainder),
+                ],
+            ),
+        ],
+    )
+}
+
+fn cctp_message_layout() -> LayoutItem {
+    LayoutItem::enumeration(
+        "WormholeCctpMessage",
+        IntEncoding::big(1),
+        [Variant::new(
+            "Deposit",
+            1,
+            [
+                bytes32_field("token_address"),
+                amount_field("amount"),
+                Field::new("source_cctp_domain", LayoutItem::uint(4)),
+                Field::new("destination_cctp_domain", LayoutItem::uint(4)),
+                Field::new("cctp_nonce", LayoutItem::uint(8)),
+                bytes32_field("burn_source"),
+                bytes32_field("mint_recipient"),
+                Field::new(
+                    "payload",
+                    LayoutItem::LengthPrefixedBytes {
+                        len: IntEncoding::big(2),
+                    },
+                ),
+            ],
+        )],
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::string::ToString;
+    use hex_literal::hex;
+
+    const ATTESTATION: &[u8] = &hex!("01000000000100ff7edcd3facb7dd6e06e0bd3e178cfddd775208f3e09f0b68bba981b812258716e6e5cd42c0ba413586df1e4066e29a1a41f9a49ae05a58f5fa93590d165abf100000000007ce2ea3f000195f83a27e90c622a98c037353f271fd8f5f57b4dc18ebf5ff75a934724bd0491a43a1c0020f88a3e2002000000000000000000000000c02aaa39b223fe8d0a0e5c4f27ead9083c756cc200021257455448000000000000000000000000000000000000000000000000000000005772617070656420657468657200000000000000000000000000000000000000");
+
+    fn line<'a>(dump: &'a Dump, name: &str) -> (usize, usize, &'a str) {
+        let line = dump
+            .lines
+            .iter()
+            .find(|line| line.name == name)
+            .unwrap_or_else(|| panic!("no line for {name}"));
+        (line.offset, line.bytes.len(), &line.value)
+    }
+
+    #[test]
+    fn dump_attestation() {
+        let out = Vaa::parse(ATTESTATION).unwrap().dump();
+        assert!(out.is_complete());
+
+        assert_eq!(line(&out, "header.version"), (0, 1, "1"));
+        assert_eq!(line(&out, "header.signatures.len"), (5, 1, "1"));
+        assert_eq!(line(&out, "header.signatures[0].r"), (7, 32, ""));
+        assert_eq!(line(&out, "header.signatures[0].v"), (71, 1, "0"));
+        assert_eq!(line(&out, "body.nonce"), (76, 4, "2095245887"));
+        assert_eq!(
+            line(&out, "body.payload"),
+            (123, 1, "TokenBridgeMessage::Attestation")
+        );
+        assert_eq!(line(&out, "body.payload.decimals"), (158, 1, "18"));
+        assert_eq!(line(&out, "body.payload.symbol"), (159, 32, "\"WETH\""));
+        assert_eq!(
+            line(&out, "body.payload.name"),
+            (191, 32, "\"Wrapped ether\"")
+        );
+    }
+
+    #[test]
+    fn dump_governance() {
+        let payload = hex!("000000000000000000000000000000000000000000546f6b656e42726964676501000000020000000000000000000000003ee18b2214aff97000d974cf647e7c347e8fa585");
+
+        let out = dump_payload(&payload);
+        assert!(out.is_complete());
+        assert_eq!(
+            line(&out, "decree"),
+            (32, 1, "TokenBridgeDecree::RegisterChain")
+        );
+        assert_eq!(line(&out, "decree.foreign_chain"), (35, 2, "2"));
+    }
+
+    #[test]
+    fn dump_malformed() {
+        // Truncated in the middle of the Attestation's name.
+        let out = dump(&ATTESTATION[..200]);
+        let failure = out.failure.as_ref().unwrap();
+        assert_eq!(failure.offset, 191);
+        assert_eq!(failure.name, "body.payload.name");
+        assert_eq!(out.trailing.as_ref().unwrap().bytes, &ATTESTATION[191..200]);
+
+        // Extra byte after the payload.
+        let mut vaa = ATTESTATION.to_vec();
+        vaa.push(0xff);
+        let out = dump(&vaa);
+        assert!(out.failure.is_none());
+        assert_eq!(out.trailing.as_ref().unwrap().offset, 223);
+        let rendered = out.to_string();
+        let last_line = rendered.lines().last().unwrap();
+        assert_eq!(
+            last_line.split_whitespace().collect::<Vec<_>>(),
+            ["!!", "223", "1", "<trailing>", "ff"]
+        );
+
+        // Not even a header.
+        let out = dump(&hex!("01000000"));
+        assert_eq!(out.lines.len(), 1);
+        assert_eq!(out.failure.unwrap().name, "header.guardian_set_index");
+    }
+}
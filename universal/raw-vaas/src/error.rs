@@ -0,0 +1,247 @@
+use core::fmt;
+
+/// Describes why a span could not be parsed, or a value could not be written to a buffer.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ParseError {
+    /// The span (or buffer being written to) is shorter than the minimum length of the type.
+    TooShort { need: usize, got: usize },
+    /// The span does not have the exact length of the type, or the length its contents encode
+    /// (e.g. the payload length of a [`Deposit`](crate::cctp::Deposit)).
+    LengthMismatch { expected: usize, got: usize },
+    /// A type or decree discriminant did not match any variant of `kind`.
+    UnknownDiscriminant { kind: &'static str, value: u8 },
+    /// The governance module does not belong to the expected contract.
+    InvalidGovernanceModule,
+    /// A governance decree targets a chain other than all chains (0) or, where that is allowed,
+    /// the expected chain.
+    InvalidTargetChain { chain: u16 },
+    /// A governance VAA was not emitted by the governance emitter.
+    InvalidGovernanceEmitter,
+    /// An index is past the number of encoded elements.
+    IndexOutOfBounds { index: usize, len: usize },
+    /// The VAA version is not 1.
+    UnsupportedVersion { version: u8 },
+    /// Two signatures have the same guardian index.
+    DuplicateSignature { index: u8 },
+    /// Signatures are not ordered by ascending guardian index.
+    UnsortedSignatures { previous: u8, index: u8 },
+    /// A signature's guardian index is past the size of the guardian set.
+    GuardianIndexOutOfBounds { index: u8, len: usize },
+    /// A signature has a high `s`, see [`SECP256K1_HALF_ORDER`](crate::utils::SECP256K1_HALF_ORDER).
+    MalleableSignature { index: u8 },
+    /// A value is longer than its encoding allows, e.g. a token symbol over 32 bytes.
+    TooLong { max: usize, got: usize },
+    /// Fewer guardians signed than [quorum](crate::utils::quorum) requires.
+    NoQuorum { need: usize, got: usize },
+    /// A signature or its recovery id is malformed, or no public key can be recovered from it.
+    InvalidSignature,
+}
+
+impl ParseError {
+    /// A stable numeric code for the variant, e.g. for [`ProgramError::Custom`] or logs.
+    ///
+    /// Codes start at 1 and follow the declaration order. New variants get new codes, and
+    /// existing codes never change.
+    ///
+    /// [`ProgramError::Custom`]: https://docs.rs/solana-program/latest/solana_program/program_error/enum.ProgramError.html#variant.Custom
+    pub const fn code(&self) -> u32 {
+        match self {
+            Self::TooShort { .. } => 1,
+            Self::LengthMismatch { .. } => 2,
+            Self::UnknownDiscriminant { .. } => 3,
+            Self::InvalidGovernanceModule => 4,
+            Self::InvalidTargetChain { .. } => 5,
+            Self::InvalidGovernanceEmitter => 6,
+            Self::IndexOutOfBounds { .. } => 7,
+            Self::UnsupportedVersion { .. } => 8,
+            Self::DuplicateSignature { .. } => 9,
+            Self::UnsortedSignatures { .. } => 10,
+            Self::GuardianIndexOutOfBounds { .. } => 11,
+            Self::MalleableSignature { .. } => 12,
+            Self::TooLong { .. } => 13,
+            Self::NoQuorum { .. } => 14,
+            Self::InvalidSignature => 15,
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooShort { need, got } => {
+                write!(f, "span too short: need at least {need} bytes, got {got}")
+            }
+            Self::LengthMismatch { expected, got } => {
+                write!(f, "length mismatch: expected {expected} bytes, got {got}")
+            }
+            Self::UnknownDiscriminant { kind, value } => {
+                write!(f, "unknown {kind} discriminant: {value}")
+            }
+            Self::InvalidGovernanceModule => f.write_str("invalid governance module"),
+            Self::InvalidTargetChain { chain } => write!(f, "invalid target chain: {chain}"),
+            Self::InvalidGovernanceEmitter => f.write_str("invalid governance emitter"),
+            Self::IndexOutOfBounds { index, len } => {
+                write!(f, "index out of bounds: {index} >= {len}")
+            }
+            Self::UnsupportedVersion { version } => write!(f, "unsupported version: {version}"),
+            Self::DuplicateSignature { index } => {
+                write!(f, "duplicate signature for guardian {index}")
+            }
+            Self::UnsortedSignatures { previous, index } => {
+                write!(
+                    f,
+                    "signatures not sorted: guardian {index} after {previous}"
+                )
+            }
+            Self::GuardianIndexOutOfBounds { index, len } => {
+                write!(f, "guardian index out of bounds: {index} >= {len}")
+            }
+            Self::MalleableSignature { index } => {
+                write!(f, "malleable signature for guardian {index}")
+            }
+            Self::TooLong { max, got } => {
+                write!(f, "too long: at most {max} allowed, got {got}")
+            }
+            Self::NoQuorum { need, got } => {
+                write!(f, "no quorum: need {need} signatures, got {got}")
+            }
+            Self::InvalidSignature => f.write_str("invalid signature"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseError {}
+
+/// Parse failures are reported as [`ProgramError::Custom`] with the [code](ParseError::code) of
+/// the variant.
+///
+/// [`ProgramError::Custom`]: solana_program::program_error::ProgramError::Custom
+#[cfg(feature = "solana")]
+impl From<ParseError> for solana_program::program_error::ProgramError {
+    fn from(err: ParseError) -> Self {
+        Self::Custom(err.code())
+    }
+}
+
+/// Check that `span` is at least `need` bytes long.
+#[inline]
+pub(crate) fn check_min_len(span: &[u8], need: usize) -> Result<(), ParseError> {
+    if span.len() < need {
+        return Err(ParseError::TooShort {
+            need,
+            got: span.len(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Check that `span` is exactly `expected` bytes long.
+#[inline]
+pub(crate) fn check_len(span: &[u8], expected: usize) -> Result<(), ParseError> {
+    if span.len() != expected {
+        return Err(ParseError::LengthMismatch {
+            expected,
+            got: span.len(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Check that `buf` can hold `len` bytes and zero them, so fields that are not set are written
+/// as zero.
+#[inline]
+pub(crate) fn init_buf(buf: &mut [u8], len: usize) -> Result<(), ParseError> {
+    check_min_len(buf, len)?;
+    buf[..len].fill(0);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        cctp, core, core::GuardianSetUpdate, token_bridge, token_bridge::TokenBridgeMessage,
+        AnyGovernance, GovernanceMessage, Vaa,
+    };
+    use std::{string::ToString, vec::Vec};
+
+    #[test]
+    fn display() {
+        assert_eq!(
+            ParseError::TooShort { need: 6, got: 2 }.to_string(),
+            "span too short: need at least 6 bytes, got 2"
+        );
+        assert_eq!(
+            ParseError::UnknownDiscriminant {
+                kind: "TokenBridgeMessage",
+                value: 9
+            }
+            .to_string(),
+            "unknown TokenBridgeMessage discriminant: 9"
+        );
+    }
+
+    #[test]
+    fn codes() {
+        assert_eq!(ParseError::TooShort { need: 6, got: 2 }.code(), 1);
+        assert_eq!(ParseError::InvalidGovernanceModule.code(), 4);
+        assert_eq!(ParseError::InvalidSignature.code(), 15);
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            TokenBridgeMessage::parse(&[]).unwrap_err(),
+            ParseError::TooShort { need: 1, got: 0 }
+        );
+        assert_eq!(
+            TokenBridgeMessage::parse(&[4]).unwrap_err(),
+            ParseError::UnknownDiscriminant {
+                kind: "TokenBridgeMessage",
+                value: 4
+            }
+        );
+
+        let mut span = [0; 27];
+        span[1] = 2;
+        span[6] = 1;
+        assert_eq!(
+            GuardianSetUpdate::parse(&span).unwrap_err(),
+            ParseError::InvalidTargetChain { chain: 2 }
+        );
+    }
+
+    /// Every parser must reject truncated input instead of panicking. The fuzz targets in
+    /// `fuzz/` cover arbitrary input.
+    #[test]
+    fn truncated_input() {
+        let mut prefixes: Vec<Vec<u8>> = (0..=255).map(|b| [b].to_vec()).collect();
+        for module in [core::GOV_MODULE, token_bridge::GOV_MODULE, cctp::GOV_MODULE] {
+            prefixes.extend((0..=6).map(|decree| [&module[..], &[decree]].concat()));
+        }
+
+        for fill in [0, 0xff] {
+            for prefix in &prefixes {
+                let mut span = prefix.clone();
+                span.resize(512, fill);
+
+                for len in 0..=span.len() {
+                    let span = &span[..len];
+                    let _ = Vaa::parse(span);
+                    let _ = GovernanceMessage::parse(span);
+                    let _ = AnyGovernance::parse(span).map(|gov| gov.target_chain());
+                    let _ = core::CoreBridgeGovPayload::parse(span);
+                    let _ = token_bridge::TokenBridgeGovPayload::parse(span);
+                    let _ = token_bridge::TokenBridgePayload::parse(span);
+                    let _ = cctp::CircleIntegrationGovPayload::parse(span);
+                    let _ = cctp::WormholeCctpPayload::parse(span);
+                }
+            }
+        }
+    }
+}
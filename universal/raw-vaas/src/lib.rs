@@ -1,10 +1,30 @@
+#![no_std]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(any(test, feature = "std"))]
+extern crate std;
+
+mod error;
+pub use error::ParseError;
+
 mod protocol;
-pub use protocol::{GuardianSetSig, Header, Payload, Vaa};
+pub use protocol::{Body, BodyMut, GuardianSetSig, Header, Payload, Vaa};
+
+mod raw_payload;
+pub use raw_payload::RawPayload;
 
 mod payloads;
-pub use payloads::{cctp, core, token_bridge, GovernanceHeader, GovernanceMessage};
+pub use payloads::{
+    cctp, core, token_bridge, AnyGovernance, GovernanceHeader, GovernanceMessage, GOVERNANCE_CHAIN,
+    GOVERNANCE_EMITTER,
+};
 
 pub mod utils;
 
+#[cfg(feature = "dump")]
+pub mod dump;
+
 #[cfg(feature = "ruint")]
 pub mod support;
@@ -1,4 +1,6 @@
-use crate::Payload;
+use crate::error::{check_len, check_min_len};
+use crate::payloads::init_decree;
+use crate::{ParseError, Payload};
 
 pub(crate) const GOV_MODULE: &[u8; 32] =
     b"\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00CircleIntegration";
@@ -18,9 +20,9 @@ impl<'a> AsRef<[u8]> for CircleIntegrationGovPayload<'a> {
 }
 
 impl<'a> TryFrom<Payload<'a>> for CircleIntegrationGovPayload<'a> {
-    type Error = &'static str;
+    type Error = ParseError;
 
-    fn try_from(payload: Payload<'a>) -> Result<Self, &'static str> {
+    fn try_from(payload: Payload<'a>) -> Result<Self, ParseError> {
         Self::parse(payload.0)
     }
 }
@@ -34,13 +36,12 @@ impl<'a> CircleIntegrationGovPayload<'a> {
         self.decree
     }
 
-    pub fn parse(span: &'a [u8]) -> Result<Self, &'static str> {
-        if span.is_empty() {
-            return Err("CircleIntegrationGovPayload span too short. Need at least 1 byte");
-        }
+    pub fn parse(span: &'a [u8]) -> Result<Self, ParseError> {
+        // Governance module and decree type.
+        check_min_len(span, 33)?;
 
         if &span[..32] != GOV_MODULE {
-            return Err("Invalid CircleIntegration governance message");
+            return Err(ParseError::InvalidGovernanceModule);
         }
 
         let decree = CircleIntegrationDecree::parse(&span[32..])?;
@@ -68,9 +69,9 @@ impl<'a> AsRef<[u8]> for CircleIntegrationDecree<'a> {
 }
 
 impl<'a> TryFrom<&'a [u8]> for CircleIntegrationDecree<'a> {
-    type Error = &'static str;
+    type Error = ParseError;
 
-    fn try_from(span: &'a [u8]) -> Result<Self, &'static str> {
+    fn try_from(span: &'a [u8]) -> Result<Self, ParseError> {
         Self::parse(span)
     }
 }
@@ -80,6 +81,12 @@ impl<'a> CircleIntegrationDecree<'a> {
         self.as_ref()
     }
 
+    /// Chain the decree applies to, or 0 for all chains.
+    pub fn target_chain(&self) -> u16 {
+        let span = self.span();
+        u16::from_be_bytes([span[0], span[1]])
+    }
+
     pub fn update_wormhole_finality(&self) -> Option<&UpdateWormholeFinality> {
         match self {
             Self::UpdateWormholeFinality(inner) => Some(inner),
@@ -122,17 +129,18 @@ impl<'a> CircleIntegrationDecree<'a> {
         }
     }
 
-    pub fn parse(span: &'a [u8]) -> Result<Self, &'static str> {
-        if span.is_empty() {
-            return Err("CircleIntegrationDecree span too short. Need at least 1 byte");
-        }
+    pub fn parse(span: &'a [u8]) -> Result<Self, ParseError> {
+        check_min_len(span, 1)?;
 
         let decree = match span[0] {
             1 => Self::UpdateWormholeFinality(TryFrom::try_from(&span[1..])?),
             2 => Self::RegisterEmitterAndDomain(TryFrom::try_from(&span[1..])?),
             3 => Self::ContractUpgrade(TryFrom::try_from(&span[1..])?),
-            _ => {
-                return Err("Invalid CircleIntegration decree");
+            value => {
+                return Err(ParseError::UnknownDiscriminant {
+                    kind: "CircleIntegrationDecree",
+                    value,
+                });
             }
         };
 
@@ -151,9 +159,9 @@ impl AsRef<[u8]> for UpdateWormholeFinality<'_> {
 }
 
 impl<'a> TryFrom<&'a [u8]> for UpdateWormholeFinality<'a> {
-    type Error = &'static str;
+    type Error = ParseError;
 
-    fn try_from(span: &'a [u8]) -> Result<Self, &'static str> {
+    fn try_from(span: &'a [u8]) -> Result<Self, ParseError> {
         Self::parse(span)
     }
 }
@@ -167,10 +175,8 @@ impl<'a> UpdateWormholeFinality<'a> {
         self.0[2]
     }
 
-    pub fn parse(span: &'a [u8]) -> Result<Self, &'static str> {
-        if span.len() != 3 {
-            return Err("UpdateWormholeFinality span too short. Need exactly 3 bytes");
-        }
+    pub fn parse(span: &'a [u8]) -> Result<Self, ParseError> {
+        check_len(span, 3)?;
 
         Ok(Self(&span[..3]))
     }
@@ -187,9 +193,9 @@ impl AsRef<[u8]> for RegisterEmitterAndDomain<'_> {
 }
 
 impl<'a> TryFrom<&'a [u8]> for RegisterEmitterAndDomain<'a> {
-    type Error = &'static str;
+    type Error = ParseError;
 
-    fn try_from(span: &'a [u8]) -> Result<Self, &'static str> {
+    fn try_from(span: &'a [u8]) -> Result<Self, ParseError> {
         Self::parse(span)
     }
 }
@@ -211,10 +217,8 @@ impl<'a> RegisterEmitterAndDomain<'a> {
         u32::from_be_bytes(self.0[36..40].try_into().unwrap())
     }
 
-    pub fn parse(span: &'a [u8]) -> Result<Self, &'static str> {
-        if span.len() != 40 {
-            return Err("RegisterEmitterAndDomain span too short. Need exactly 40 bytes");
-        }
+    pub fn parse(span: &'a [u8]) -> Result<Self, ParseError> {
+        check_len(span, 40)?;
 
         Ok(Self(&span[..40]))
     }
@@ -231,9 +235,9 @@ impl AsRef<[u8]> for ContractUpgrade<'_> {
 }
 
 impl<'a> TryFrom<&'a [u8]> for ContractUpgrade<'a> {
-    type Error = &'static str;
+    type Error = ParseError;
 
-    fn try_from(span: &'a [u8]) -> Result<Self, &'static str> {
+    fn try_from(span: &'a [u8]) -> Result<Self, ParseError> {
         Self::parse(span)
     }
 }
@@ -247,18 +251,110 @@ impl<'a> ContractUpgrade<'a> {
         self.0[2..34].try_into().unwrap()
     }
 
-    pub fn parse(span: &'a [u8]) -> Result<Self, &'static str> {
-        if span.len() != 34 {
-            return Err("ContractUpgrade span too short. Need exactly 34 bytes");
-        }
+    pub fn parse(span: &'a [u8]) -> Result<Self, ParseError> {
+        check_len(span, 34)?;
 
         Ok(Self(&span[..34]))
     }
 }
 
+/// Writes a [`UpdateWormholeFinality`] governance payload, with its module and decree type, to a
+/// caller-provided buffer. Fields that are not set are zero.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct UpdateWormholeFinalityMut<'a>(&'a mut [u8]);
+
+impl<'a> UpdateWormholeFinalityMut<'a> {
+    pub fn new(buf: &'a mut [u8]) -> Result<Self, ParseError> {
+        init_decree(buf, GOV_MODULE, 1, 3)?;
+
+        Ok(Self(&mut buf[..36]))
+    }
+
+    pub fn set_chain(&mut self, chain: u16) -> &mut Self {
+        self.0[33..35].copy_from_slice(&chain.to_be_bytes());
+        self
+    }
+
+    pub fn set_finality(&mut self, finality: u8) -> &mut Self {
+        self.0[35] = finality;
+        self
+    }
+
+    /// Number of bytes written.
+    pub fn finish(self) -> usize {
+        self.0.len()
+    }
+}
+
+/// Writes a [`RegisterEmitterAndDomain`] governance payload, with its module and decree type, to a
+/// caller-provided buffer. Fields that are not set are zero.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct RegisterEmitterAndDomainMut<'a>(&'a mut [u8]);
+
+impl<'a> RegisterEmitterAndDomainMut<'a> {
+    pub fn new(buf: &'a mut [u8]) -> Result<Self, ParseError> {
+        init_decree(buf, GOV_MODULE, 2, 40)?;
+
+        Ok(Self(&mut buf[..73]))
+    }
+
+    pub fn set_chain(&mut self, chain: u16) -> &mut Self {
+        self.0[33..35].copy_from_slice(&chain.to_be_bytes());
+        self
+    }
+
+    pub fn set_foreign_chain(&mut self, foreign_chain: u16) -> &mut Self {
+        self.0[35..37].copy_from_slice(&foreign_chain.to_be_bytes());
+        self
+    }
+
+    pub fn set_foreign_emitter(&mut self, foreign_emitter: [u8; 32]) -> &mut Self {
+        self.0[37..69].copy_from_slice(&foreign_emitter);
+        self
+    }
+
+    pub fn set_cctp_domain(&mut self, cctp_domain: u32) -> &mut Self {
+        self.0[69..73].copy_from_slice(&cctp_domain.to_be_bytes());
+        self
+    }
+
+    /// Number of bytes written.
+    pub fn finish(self) -> usize {
+        self.0.len()
+    }
+}
+
+/// Writes a [`ContractUpgrade`] governance payload, with its module and decree type, to a
+/// caller-provided buffer. Fields that are not set are zero.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct ContractUpgradeMut<'a>(&'a mut [u8]);
+
+impl<'a> ContractUpgradeMut<'a> {
+    pub fn new(buf: &'a mut [u8]) -> Result<Self, ParseError> {
+        init_decree(buf, GOV_MODULE, 3, 34)?;
+
+        Ok(Self(&mut buf[..67]))
+    }
+
+    pub fn set_chain(&mut self, chain: u16) -> &mut Self {
+        self.0[33..35].copy_from_slice(&chain.to_be_bytes());
+        self
+    }
+
+    pub fn set_implementation(&mut self, implementation: [u8; 32]) -> &mut Self {
+        self.0[35..67].copy_from_slice(&implementation);
+        self
+    }
+
+    /// Number of bytes written.
+    pub fn finish(self) -> usize {
+        self.0.len()
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use crate::{cctp::CircleIntegrationGovPayload, Vaa};
+    use crate::{cctp::*, ParseError, Vaa};
     use hex_literal::hex;
 
     #[test]
@@ -287,6 +383,16 @@ mod test {
             hex!("0000000000000000000000002703483b1a5a7c577e8680de9df8be03c6f30e3c")
         );
         assert_eq!(registration.cctp_domain(), 3);
+
+        let mut buf = [0; 73];
+        let mut writer = RegisterEmitterAndDomainMut::new(&mut buf).unwrap();
+        writer
+            .set_chain(registration.chain())
+            .set_foreign_chain(registration.foreign_chain())
+            .set_foreign_emitter(registration.foreign_emitter())
+            .set_cctp_domain(registration.cctp_domain());
+        assert_eq!(writer.finish(), 73);
+        assert_eq!(buf, raw_vaa.payload().as_ref());
     }
 
     #[test]
@@ -308,7 +414,10 @@ mod test {
             .unwrap();
         assert_eq!(
             err,
-            "RegisterEmitterAndDomain span too short. Need exactly 40 bytes"
+            ParseError::LengthMismatch {
+                expected: 40,
+                got: 41
+            }
         );
     }
 
@@ -380,6 +489,6 @@ mod test {
         let err = CircleIntegrationGovPayload::try_from(raw_vaa.payload())
             .err()
             .unwrap();
-        assert_eq!(err, "Invalid CircleIntegration governance message");
+        assert_eq!(err, ParseError::InvalidGovernanceModule);
     }
 }
@@ -1,4 +1,5 @@
-use crate::Payload;
+use crate::error::{check_len, check_min_len, init_buf};
+use crate::{ParseError, Payload};
 
 /// A Wormhole CCTP payload with type flag
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
@@ -15,9 +16,9 @@ impl<'a> AsRef<[u8]> for WormholeCctpPayload<'a> {
 }
 
 impl<'a> TryFrom<Payload<'a>> for WormholeCctpPayload<'a> {
-    type Error = &'static str;
+    type Error = ParseError;
 
-    fn try_from(payload: Payload<'a>) -> Result<Self, &'static str> {
+    fn try_from(payload: Payload<'a>) -> Result<Self, ParseError> {
         Self::parse(payload.0)
     }
 }
@@ -31,10 +32,8 @@ impl<'a> WormholeCctpPayload<'a> {
         self.message
     }
 
-    pub fn parse(span: &'a [u8]) -> Result<Self, &'static str> {
-        if span.is_empty() {
-            return Err("WormholeCctpPayload span too short. Need at least 1 byte");
-        }
+    pub fn parse(span: &'a [u8]) -> Result<Self, ParseError> {
+        check_min_len(span, 1)?;
 
         let message = WormholeCctpMessage::parse(span)?;
 
@@ -50,9 +49,9 @@ pub enum WormholeCctpMessage<'a> {
 }
 
 impl<'a> TryFrom<Payload<'a>> for WormholeCctpMessage<'a> {
-    type Error = &'static str;
+    type Error = ParseError;
 
-    fn try_from(payload: Payload<'a>) -> Result<Self, &'static str> {
+    fn try_from(payload: Payload<'a>) -> Result<Self, ParseError> {
         Self::parse(payload.0)
     }
 }
@@ -85,15 +84,16 @@ impl<'a> WormholeCctpMessage<'a> {
         }
     }
 
-    pub fn parse(span: &'a [u8]) -> Result<Self, &'static str> {
-        if span.is_empty() {
-            return Err("WormholeCctpMessage span too short. Need at least 1 byte");
-        }
+    pub fn parse(span: &'a [u8]) -> Result<Self, ParseError> {
+        check_min_len(span, 1)?;
 
         match span[0] {
             1 => Ok(Self::Deposit(Deposit::parse(&span[1..])?)),
             2..=10 => Ok(Self::ReservedUnknown(&span[1..])),
-            _ => Err("Unknown WormholeCctpMessage type"),
+            value => Err(ParseError::UnknownDiscriminant {
+                kind: "WormholeCctpMessage",
+                value,
+            }),
         }
     }
 }
@@ -141,29 +141,117 @@ impl<'a> Deposit<'a> {
         u16::from_be_bytes(self.0[144..146].try_into().unwrap())
     }
 
-    pub fn payload(&'a self) -> Payload<'a> {
+    pub fn payload(&self) -> Payload<'a> {
         Payload::parse(&self.0[146..])
     }
 
-    pub fn parse(span: &'a [u8]) -> Result<Self, &'static str> {
-        if span.len() < 146 {
-            return Err("Deposit span too short. Need at least 146 bytes");
-        }
+    pub fn parse(span: &'a [u8]) -> Result<Self, ParseError> {
+        check_min_len(span, 146)?;
 
         let deposit = Self(span);
 
         // Check payload length vs actual payload.
-        if deposit.payload().len() != deposit.payload_len().into() {
-            return Err("Deposit payload length mismatch");
-        }
+        check_len(span, 146 + usize::from(deposit.payload_len()))?;
 
         Ok(deposit)
     }
 }
 
+/// Writes a [`Deposit`], with its type flag, to a caller-provided buffer. Fields that are not
+/// set are zero.
+///
+/// The payload can be copied with [`set_payload`](Self::set_payload), or written in place to
+/// [`payload_mut`](Self::payload_mut) followed by [`set_payload_len`](Self::set_payload_len).
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct DepositMut<'a> {
+    span: &'a mut [u8],
+    payload_len: usize,
+}
+
+impl<'a> DepositMut<'a> {
+    pub fn new(buf: &'a mut [u8]) -> Result<Self, ParseError> {
+        init_buf(buf, 147)?;
+        buf[0] = 1;
+
+        Ok(Self {
+            span: buf,
+            payload_len: 0,
+        })
+    }
+
+    pub fn set_token_address(&mut self, token_address: [u8; 32]) -> &mut Self {
+        self.span[1..33].copy_from_slice(&token_address);
+        self
+    }
+
+    pub fn set_amount(&mut self, amount: [u8; 32]) -> &mut Self {
+        self.span[33..65].copy_from_slice(&amount);
+        self
+    }
+
+    pub fn set_source_cctp_domain(&mut self, domain: u32) -> &mut Self {
+        self.span[65..69].copy_from_slice(&domain.to_be_bytes());
+        self
+    }
+
+    pub fn set_destination_cctp_domain(&mut self, domain: u32) -> &mut Self {
+        self.span[69..73].copy_from_slice(&domain.to_be_bytes());
+        self
+    }
+
+    pub fn set_cctp_nonce(&mut self, nonce: u64) -> &mut Self {
+        self.span[73..81].copy_from_slice(&nonce.to_be_bytes());
+        self
+    }
+
+    pub fn set_burn_source(&mut self, burn_source: [u8; 32]) -> &mut Self {
+        self.span[81..113].copy_from_slice(&burn_source);
+        self
+    }
+
+    pub fn set_mint_recipient(&mut self, mint_recipient: [u8; 32]) -> &mut Self {
+        self.span[113..145].copy_from_slice(&mint_recipient);
+        self
+    }
+
+    /// The rest of the buffer, where the payload is written.
+    pub fn payload_mut(&mut self) -> &mut [u8] {
+        &mut self.span[147..]
+    }
+
+    /// Set the length of a payload written to [`payload_mut`](Self::payload_mut), which is
+    /// encoded as a `u16`.
+    pub fn set_payload_len(&mut self, len: usize) -> Result<&mut Self, ParseError> {
+        let encoded = u16::try_from(len).map_err(|_| ParseError::TooLong {
+            max: u16::MAX.into(),
+            got: len,
+        })?;
+        check_min_len(self.span, 147 + len)?;
+
+        self.span[145..147].copy_from_slice(&encoded.to_be_bytes());
+        self.payload_len = len;
+        Ok(self)
+    }
+
+    pub fn set_payload(&mut self, payload: &[u8]) -> Result<&mut Self, ParseError> {
+        self.set_payload_len(payload.len())?;
+
+        self.span[147..(147 + payload.len())].copy_from_slice(payload);
+        Ok(self)
+    }
+
+    /// Number of bytes written.
+    pub fn finish(self) -> usize {
+        147 + self.payload_len
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use crate::{cctp::WormholeCctpPayload, Vaa};
+    use crate::{
+        cctp::{DepositMut, WormholeCctpPayload},
+        ParseError, Vaa,
+    };
     use hex_literal::hex;
 
     #[test]
@@ -208,6 +296,47 @@ mod test {
         );
         assert_eq!(deposit.payload_len(), 0);
         assert_eq!(<&[u8]>::from(deposit.payload()), &[]);
+
+        let mut buf = [0xff; 200];
+        let mut writer = DepositMut::new(&mut buf).unwrap();
+        writer
+            .set_token_address(deposit.token_address())
+            .set_amount(deposit.amount())
+            .set_source_cctp_domain(deposit.source_cctp_domain())
+            .set_destination_cctp_domain(deposit.destination_cctp_domain())
+            .set_cctp_nonce(deposit.cctp_nonce())
+            .set_burn_source(deposit.burn_source())
+            .set_mint_recipient(deposit.mint_recipient());
+        let len = writer.finish();
+        assert_eq!(&buf[..len], raw_vaa.payload().as_ref());
+
+        // With a payload, written in place.
+        let mut writer = DepositMut::new(&mut buf).unwrap();
+        writer.payload_mut()[..5].copy_from_slice(b"hello");
+        writer.set_payload_len(5).unwrap();
+        let len = writer.finish();
+        let deposit = WormholeCctpPayload::parse(&buf[..len])
+            .unwrap()
+            .message()
+            .to_deposit_unchecked();
+        assert_eq!(deposit.payload_len(), 5);
+        assert_eq!(deposit.payload().as_ref(), b"hello");
+
+        let mut writer = DepositMut::new(&mut buf).unwrap();
+        assert_eq!(
+            writer.set_payload(&[0; 54]).unwrap_err(),
+            ParseError::TooShort {
+                need: 201,
+                got: 200
+            }
+        );
+        assert_eq!(
+            writer.set_payload_len(65_536).unwrap_err(),
+            ParseError::TooLong {
+                max: 65_535,
+                got: 65_536
+            }
+        );
     }
 
     #[test]
@@ -228,6 +357,12 @@ mod test {
         let err = WormholeCctpPayload::try_from(raw_vaa.payload())
             .err()
             .unwrap();
-        assert_eq!(err, "Deposit payload length mismatch");
+        assert_eq!(
+            err,
+            ParseError::LengthMismatch {
+                expected: 147,
+                got: 146
+            }
+        );
     }
 }
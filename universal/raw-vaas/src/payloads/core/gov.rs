@@ -1,4 +1,6 @@
-use crate::Payload;
+use crate::error::{check_len, check_min_len};
+use crate::payloads::init_decree;
+use crate::{ParseError, Payload};
 
 pub(crate) const GOV_MODULE: &[u8; 32] = b"\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00Core";
 
@@ -17,9 +19,9 @@ impl<'a> AsRef<[u8]> for CoreBridgeGovPayload<'a> {
 }
 
 impl<'a> TryFrom<Payload<'a>> for CoreBridgeGovPayload<'a> {
-    type Error = &'static str;
+    type Error = ParseError;
 
-    fn try_from(payload: Payload<'a>) -> Result<Self, &'static str> {
+    fn try_from(payload: Payload<'a>) -> Result<Self, ParseError> {
         Self::parse(payload.0)
     }
 }
@@ -33,13 +35,12 @@ impl<'a> CoreBridgeGovPayload<'a> {
         self.decree
     }
 
-    pub fn parse(span: &'a [u8]) -> Result<Self, &'static str> {
-        if span.is_empty() {
-            return Err("CoreBridgeGovPayload span too short. Need at least 1 byte");
-        }
+    pub fn parse(span: &'a [u8]) -> Result<Self, ParseError> {
+        // Governance module and decree type.
+        check_min_len(span, 33)?;
 
         if &span[..32] != GOV_MODULE {
-            return Err("Invalid Core Bridge governance message");
+            return Err(ParseError::InvalidGovernanceModule);
         }
 
         let decree = CoreBridgeDecree::parse(&span[32..])?;
@@ -71,9 +72,9 @@ impl<'a> AsRef<[u8]> for CoreBridgeDecree<'a> {
 }
 
 impl<'a> TryFrom<&'a [u8]> for CoreBridgeDecree<'a> {
-    type Error = &'static str;
+    type Error = ParseError;
 
-    fn try_from(span: &'a [u8]) -> Result<Self, &'static str> {
+    fn try_from(span: &'a [u8]) -> Result<Self, ParseError> {
         Self::parse(span)
     }
 }
@@ -83,6 +84,12 @@ impl<'a> CoreBridgeDecree<'a> {
         self.as_ref()
     }
 
+    /// Chain the decree applies to, or 0 for all chains.
+    pub fn target_chain(&self) -> u16 {
+        let span = self.span();
+        u16::from_be_bytes([span[0], span[1]])
+    }
+
     pub fn contract_upgrade(&self) -> Option<&ContractUpgrade> {
         match self {
             CoreBridgeDecree::ContractUpgrade(inner) => Some(inner),
@@ -153,10 +160,8 @@ impl<'a> CoreBridgeDecree<'a> {
         }
     }
 
-    pub fn parse(span: &'a [u8]) -> Result<Self, &'static str> {
-        if span.is_empty() {
-            return Err("CoreBridgeDecree span too short. Need at least 1 byte");
-        }
+    pub fn parse(span: &'a [u8]) -> Result<Self, ParseError> {
+        check_min_len(span, 1)?;
 
         let decree = match span[0] {
             1 => Self::ContractUpgrade(TryFrom::try_from(&span[1..])?),
@@ -164,8 +169,11 @@ impl<'a> CoreBridgeDecree<'a> {
             3 => Self::SetMessageFee(TryFrom::try_from(&span[1..])?),
             4 => Self::TransferFees(TryFrom::try_from(&span[1..])?),
             5 => Self::RecoverChainId(TryFrom::try_from(&span[1..])?),
-            _ => {
-                return Err("Invalid Core Bridge decree");
+            value => {
+                return Err(ParseError::UnknownDiscriminant {
+                    kind: "CoreBridgeDecree",
+                    value,
+                });
             }
         };
 
@@ -184,9 +192,9 @@ impl<'a> AsRef<[u8]> for ContractUpgrade<'a> {
 }
 
 impl<'a> TryFrom<&'a [u8]> for ContractUpgrade<'a> {
-    type Error = &'static str;
+    type Error = ParseError;
 
-    fn try_from(span: &'a [u8]) -> Result<Self, &'static str> {
+    fn try_from(span: &'a [u8]) -> Result<Self, ParseError> {
         Self::parse(span)
     }
 }
@@ -200,10 +208,8 @@ impl<'a> ContractUpgrade<'a> {
         self.0[2..34].try_into().unwrap()
     }
 
-    pub fn parse(span: &'a [u8]) -> Result<Self, &'static str> {
-        if span.len() != 34 {
-            return Err("ContractUpgrade span too short. Need exactly 34 bytes");
-        }
+    pub fn parse(span: &'a [u8]) -> Result<Self, ParseError> {
+        check_len(span, 34)?;
 
         Ok(Self(&span[..34]))
     }
@@ -220,9 +226,9 @@ impl<'a> AsRef<[u8]> for GuardianSetUpdate<'a> {
 }
 
 impl<'a> TryFrom<&'a [u8]> for GuardianSetUpdate<'a> {
-    type Error = &'static str;
+    type Error = ParseError;
 
-    fn try_from(span: &'a [u8]) -> Result<Self, &'static str> {
+    fn try_from(span: &'a [u8]) -> Result<Self, ParseError> {
         Self::parse(span)
     }
 }
@@ -236,33 +242,35 @@ impl<'a> GuardianSetUpdate<'a> {
         self.0[6]
     }
 
-    pub fn try_guardian_at(&self, i: usize) -> Result<[u8; 20], &'static str> {
+    /// Like [`guardian_at`](Self::guardian_at), but reports an out-of-range index as
+    /// [`ParseError::IndexOutOfBounds`].
+    pub fn try_guardian_at(&self, i: usize) -> Result<[u8; 20], ParseError> {
+        self.guardian_at(i).ok_or(ParseError::IndexOutOfBounds {
+            index: i,
+            len: usize::from(self.num_guardians()),
+        })
+    }
+
+    /// Returns `None` if `i` is not less than [`num_guardians`](Self::num_guardians).
+    pub fn guardian_at(&self, i: usize) -> Option<[u8; 20]> {
         if i >= usize::from(self.num_guardians()) {
-            return Err("Exceeds number of encoded guardians");
+            return None;
         }
 
-        Ok(self.guardian_at(i))
+        Some(self.0[(7 + i * 20)..(7 + (i + 1) * 20)].try_into().unwrap())
     }
 
-    pub fn guardian_at(&self, i: usize) -> [u8; 20] {
-        self.0[(7 + i * 20)..(7 + (i + 1) * 20)].try_into().unwrap()
-    }
-
-    pub fn parse(span: &'a [u8]) -> Result<Self, &'static str> {
-        if span.len() < 27 {
-            return Err("GuardianSetUpdate span too short. Need at least 27 bytes (for at least 1 guardian)");
-        }
+    pub fn parse(span: &'a [u8]) -> Result<Self, ParseError> {
+        // At least one guardian.
+        check_min_len(span, 27)?;
 
-        if span[..2] != [0, 0] {
-            return Err("GuardianSetUpdate target chain must be 0");
+        let chain = u16::from_be_bytes([span[0], span[1]]);
+        if chain != 0 {
+            return Err(ParseError::InvalidTargetChain { chain });
         }
 
         let expected_len = 7 + usize::from(span[6]) * 20;
-        if span.len() != expected_len {
-            return Err(
-                "GuardianSetUpdate span too short. Need exactly 7 + num_guardians * 20 bytes",
-            );
-        }
+        check_len(span, expected_len)?;
 
         Ok(Self(&span[..expected_len]))
     }
@@ -279,9 +287,9 @@ impl<'a> AsRef<[u8]> for SetMessageFee<'a> {
 }
 
 impl<'a> TryFrom<&'a [u8]> for SetMessageFee<'a> {
-    type Error = &'static str;
+    type Error = ParseError;
 
-    fn try_from(span: &'a [u8]) -> Result<Self, &'static str> {
+    fn try_from(span: &'a [u8]) -> Result<Self, ParseError> {
         Self::parse(span)
     }
 }
@@ -295,10 +303,8 @@ impl<'a> SetMessageFee<'a> {
         self.0[2..34].try_into().unwrap()
     }
 
-    pub fn parse(span: &'a [u8]) -> Result<Self, &'static str> {
-        if span.len() != 34 {
-            return Err("SetMessageFee span too short. Need exactly 34 bytes");
-        }
+    pub fn parse(span: &'a [u8]) -> Result<Self, ParseError> {
+        check_len(span, 34)?;
 
         Ok(Self(&span[..34]))
     }
@@ -315,9 +321,9 @@ impl<'a> AsRef<[u8]> for TransferFees<'a> {
 }
 
 impl<'a> TryFrom<&'a [u8]> for TransferFees<'a> {
-    type Error = &'static str;
+    type Error = ParseError;
 
-    fn try_from(span: &'a [u8]) -> Result<Self, &'static str> {
+    fn try_from(span: &'a [u8]) -> Result<Self, ParseError> {
         Self::parse(span)
     }
 }
@@ -335,10 +341,8 @@ impl<'a> TransferFees<'a> {
         self.0[34..66].try_into().unwrap()
     }
 
-    pub fn parse(span: &'a [u8]) -> Result<Self, &'static str> {
-        if span.len() != 66 {
-            return Err("TransferFees span too short. Need exactly 66 bytes");
-        }
+    pub fn parse(span: &'a [u8]) -> Result<Self, ParseError> {
+        check_len(span, 66)?;
 
         Ok(Self(&span[..66]))
     }
@@ -355,9 +359,9 @@ impl<'a> AsRef<[u8]> for RecoverChainId<'a> {
 }
 
 impl<'a> TryFrom<&'a [u8]> for RecoverChainId<'a> {
-    type Error = &'static str;
+    type Error = ParseError;
 
-    fn try_from(span: &'a [u8]) -> Result<Self, &'static str> {
+    fn try_from(span: &'a [u8]) -> Result<Self, ParseError> {
         Self::parse(span)
     }
 }
@@ -375,18 +379,189 @@ impl<'a> RecoverChainId<'a> {
         u16::from_be_bytes(self.0[34..36].try_into().unwrap())
     }
 
-    pub fn parse(span: &'a [u8]) -> Result<Self, &'static str> {
-        if span.len() != 36 {
-            return Err("RecoverChainId span too short. Need exactly 36 bytes");
-        }
+    pub fn parse(span: &'a [u8]) -> Result<Self, ParseError> {
+        check_len(span, 36)?;
 
         Ok(Self(&span[..36]))
     }
 }
 
+/// Writes a [`ContractUpgrade`] governance payload, with its module and decree type, to a
+/// caller-provided buffer. Fields that are not set are zero.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct ContractUpgradeMut<'a>(&'a mut [u8]);
+
+impl<'a> ContractUpgradeMut<'a> {
+    pub fn new(buf: &'a mut [u8]) -> Result<Self, ParseError> {
+        init_decree(buf, GOV_MODULE, 1, 34)?;
+
+        Ok(Self(&mut buf[..67]))
+    }
+
+    pub fn set_chain(&mut self, chain: u16) -> &mut Self {
+        self.0[33..35].copy_from_slice(&chain.to_be_bytes());
+        self
+    }
+
+    pub fn set_implementation(&mut self, implementation: [u8; 32]) -> &mut Self {
+        self.0[35..67].copy_from_slice(&implementation);
+        self
+    }
+
+    /// Number of bytes written.
+    pub fn finish(self) -> usize {
+        self.0.len()
+    }
+}
+
+/// Writes a [`GuardianSetUpdate`] governance payload, with its module and decree type, to a
+/// caller-provided buffer. Fields that are not set are zero.
+///
+/// The decree applies to all chains, and at least one guardian must be pushed for the payload to
+/// be valid.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct GuardianSetUpdateMut<'a>(&'a mut [u8]);
+
+impl<'a> GuardianSetUpdateMut<'a> {
+    pub fn new(buf: &'a mut [u8]) -> Result<Self, ParseError> {
+        init_decree(buf, GOV_MODULE, 2, 7)?;
+
+        Ok(Self(buf))
+    }
+
+    pub fn set_new_index(&mut self, new_index: u32) -> &mut Self {
+        self.0[35..39].copy_from_slice(&new_index.to_be_bytes());
+        self
+    }
+
+    pub fn num_guardians(&self) -> u8 {
+        self.0[39]
+    }
+
+    pub fn push_guardian(&mut self, guardian: [u8; 20]) -> Result<&mut Self, ParseError> {
+        let num_guardians = self.num_guardians();
+        if num_guardians == u8::MAX {
+            return Err(ParseError::TooLong {
+                max: u8::MAX.into(),
+                got: usize::from(num_guardians) + 1,
+            });
+        }
+
+        let start = self.written_len();
+        check_min_len(self.0, start + 20)?;
+
+        self.0[start..(start + 20)].copy_from_slice(&guardian);
+        self.0[39] = num_guardians + 1;
+        Ok(self)
+    }
+
+    /// Number of bytes written.
+    pub fn finish(self) -> usize {
+        self.written_len()
+    }
+
+    fn written_len(&self) -> usize {
+        40 + usize::from(self.num_guardians()) * 20
+    }
+}
+
+/// Writes a [`SetMessageFee`] governance payload, with its module and decree type, to a
+/// caller-provided buffer. Fields that are not set are zero.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct SetMessageFeeMut<'a>(&'a mut [u8]);
+
+impl<'a> SetMessageFeeMut<'a> {
+    pub fn new(buf: &'a mut [u8]) -> Result<Self, ParseError> {
+        init_decree(buf, GOV_MODULE, 3, 34)?;
+
+        Ok(Self(&mut buf[..67]))
+    }
+
+    pub fn set_chain(&mut self, chain: u16) -> &mut Self {
+        self.0[33..35].copy_from_slice(&chain.to_be_bytes());
+        self
+    }
+
+    pub fn set_fee(&mut self, fee: [u8; 32]) -> &mut Self {
+        self.0[35..67].copy_from_slice(&fee);
+        self
+    }
+
+    /// Number of bytes written.
+    pub fn finish(self) -> usize {
+        self.0.len()
+    }
+}
+
+/// Writes a [`TransferFees`] governance payload, with its module and decree type, to a
+/// caller-provided buffer. Fields that are not set are zero.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct TransferFeesMut<'a>(&'a mut [u8]);
+
+impl<'a> TransferFeesMut<'a> {
+    pub fn new(buf: &'a mut [u8]) -> Result<Self, ParseError> {
+        init_decree(buf, GOV_MODULE, 4, 66)?;
+
+        Ok(Self(&mut buf[..99]))
+    }
+
+    pub fn set_chain(&mut self, chain: u16) -> &mut Self {
+        self.0[33..35].copy_from_slice(&chain.to_be_bytes());
+        self
+    }
+
+    pub fn set_amount(&mut self, amount: [u8; 32]) -> &mut Self {
+        self.0[35..67].copy_from_slice(&amount);
+        self
+    }
+
+    pub fn set_recipient(&mut self, recipient: [u8; 32]) -> &mut Self {
+        self.0[67..99].copy_from_slice(&recipient);
+        self
+    }
+
+    /// Number of bytes written.
+    pub fn finish(self) -> usize {
+        self.0.len()
+    }
+}
+
+/// Writes a [`RecoverChainId`] governance payload, with its module and decree type, to a
+/// caller-provided buffer. Fields that are not set are zero.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct RecoverChainIdMut<'a>(&'a mut [u8]);
+
+impl<'a> RecoverChainIdMut<'a> {
+    pub fn new(buf: &'a mut [u8]) -> Result<Self, ParseError> {
+        init_decree(buf, GOV_MODULE, 5, 36)?;
+
+        Ok(Self(&mut buf[..69]))
+    }
+
+    pub fn set_recovered_chain(&mut self, recovered_chain: u16) -> &mut Self {
+        self.0[33..35].copy_from_slice(&recovered_chain.to_be_bytes());
+        self
+    }
+
+    pub fn set_evm_chain_id(&mut self, evm_chain_id: [u8; 32]) -> &mut Self {
+        self.0[35..67].copy_from_slice(&evm_chain_id);
+        self
+    }
+
+    pub fn set_new_chain(&mut self, new_chain: u16) -> &mut Self {
+        self.0[67..69].copy_from_slice(&new_chain.to_be_bytes());
+        self
+    }
+
+    /// Number of bytes written.
+    pub fn finish(self) -> usize {
+        self.0.len()
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use crate::{core::CoreBridgeGovPayload, Vaa};
+    use crate::{core::*, ParseError, Vaa};
     use hex_literal::hex;
 
     #[test]
@@ -414,6 +589,14 @@ mod test {
             contract_upgrade.implementation(),
             hex!("5cdecd10d40cee2b1601e3adc3bbe914e3625925d0c9bd7f7ba0c153465978e7")
         );
+
+        let mut buf = [0xff; 80];
+        let mut writer = ContractUpgradeMut::new(&mut buf).unwrap();
+        writer
+            .set_chain(contract_upgrade.chain())
+            .set_implementation(contract_upgrade.implementation());
+        let len = writer.finish();
+        assert_eq!(&buf[..len], raw_vaa.payload().as_ref());
     }
 
     #[test]
@@ -433,7 +616,13 @@ mod test {
         let err = CoreBridgeGovPayload::try_from(raw_vaa.payload())
             .err()
             .unwrap();
-        assert_eq!(err, "ContractUpgrade span too short. Need exactly 34 bytes");
+        assert_eq!(
+            err,
+            ParseError::LengthMismatch {
+                expected: 34,
+                got: 35
+            }
+        );
     }
 
     #[test]
@@ -465,12 +654,32 @@ mod test {
         ];
         for (i, expected) in expected_guardians.iter().enumerate() {
             let guardian = guardian_set_update.guardian_at(i);
-            assert_eq!(guardian, *expected);
+            assert_eq!(guardian, Some(*expected));
         }
 
         // Try to access out of bounds.
+        assert_eq!(guardian_set_update.guardian_at(2), None);
         let err = guardian_set_update.try_guardian_at(2).err().unwrap();
-        assert_eq!(err, "Exceeds number of encoded guardians");
+        assert_eq!(err, ParseError::IndexOutOfBounds { index: 2, len: 2 });
+
+        let mut buf = [0xff; 80];
+        let mut writer = GuardianSetUpdateMut::new(&mut buf).unwrap();
+        writer.set_new_index(guardian_set_update.new_index());
+        for guardian in expected_guardians {
+            writer.push_guardian(guardian).unwrap();
+        }
+        assert_eq!(writer.num_guardians(), 2);
+        let len = writer.finish();
+        assert_eq!(&buf[..len], raw_vaa.payload().as_ref());
+
+        // No room for a third guardian.
+        let mut writer = GuardianSetUpdateMut::new(&mut buf).unwrap();
+        writer.push_guardian([1; 20]).unwrap();
+        writer.push_guardian([2; 20]).unwrap();
+        assert_eq!(
+            writer.push_guardian([3; 20]).unwrap_err(),
+            ParseError::TooShort { need: 100, got: 80 }
+        );
     }
 
     #[test]
@@ -492,7 +701,10 @@ mod test {
             .unwrap();
         assert_eq!(
             err,
-            "GuardianSetUpdate span too short. Need exactly 7 + num_guardians * 20 bytes"
+            ParseError::LengthMismatch {
+                expected: 47,
+                got: 48
+            }
         );
     }
 
@@ -521,6 +733,14 @@ mod test {
             set_message_fee.fee(),
             hex!("0000000000000000000000000000000000000000000000000000000000001b39")
         );
+
+        let mut buf = [0; 67];
+        let mut writer = SetMessageFeeMut::new(&mut buf).unwrap();
+        writer
+            .set_chain(set_message_fee.chain())
+            .set_fee(set_message_fee.fee());
+        assert_eq!(writer.finish(), 67);
+        assert_eq!(buf, raw_vaa.payload().as_ref());
     }
 
     #[test]
@@ -540,7 +760,13 @@ mod test {
         let err = CoreBridgeGovPayload::try_from(raw_vaa.payload())
             .err()
             .unwrap();
-        assert_eq!(err, "SetMessageFee span too short. Need exactly 34 bytes");
+        assert_eq!(
+            err,
+            ParseError::LengthMismatch {
+                expected: 34,
+                got: 35
+            }
+        );
     }
 
     #[test]
@@ -572,6 +798,15 @@ mod test {
             transfer_fees.recipient(),
             hex!("b2d526e2ab22d8cec062eb8e2b7b8d809803cb4ad11e98667927a1160b9fe408")
         );
+
+        let mut buf = [0; 99];
+        let mut writer = TransferFeesMut::new(&mut buf).unwrap();
+        writer
+            .set_chain(transfer_fees.chain())
+            .set_amount(transfer_fees.amount())
+            .set_recipient(transfer_fees.recipient());
+        assert_eq!(writer.finish(), 99);
+        assert_eq!(buf, raw_vaa.payload().as_ref());
     }
 
     #[test]
@@ -591,7 +826,13 @@ mod test {
         let err = CoreBridgeGovPayload::try_from(raw_vaa.payload())
             .err()
             .unwrap();
-        assert_eq!(err, "TransferFees span too short. Need exactly 66 bytes");
+        assert_eq!(
+            err,
+            ParseError::LengthMismatch {
+                expected: 66,
+                got: 67
+            }
+        );
     }
 
     #[test]
@@ -615,6 +856,6 @@ mod test {
         let err = CoreBridgeGovPayload::try_from(raw_vaa.payload())
             .err()
             .unwrap();
-        assert_eq!(err, "Invalid Core Bridge governance message");
+        assert_eq!(err, ParseError::InvalidGovernanceModule);
     }
 }
@@ -2,7 +2,18 @@ pub mod cctp;
 pub mod core;
 pub mod token_bridge;
 
-use crate::Payload;
+use crate::error::{check_min_len, init_buf};
+use crate::{ParseError, Payload};
+
+/// Chain of the emitter of governance VAAs.
+pub const GOVERNANCE_CHAIN: u16 = 1;
+
+/// Emitter address of governance VAAs.
+pub const GOVERNANCE_EMITTER: [u8; 32] = {
+    let mut emitter = [0; 32];
+    emitter[31] = 4;
+    emitter
+};
 
 /// A governance Message with header and type flag.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
@@ -20,7 +31,7 @@ impl<'a> AsRef<[u8]> for GovernanceMessage<'a> {
 }
 
 impl<'a> TryFrom<&'a [u8]> for GovernanceMessage<'a> {
-    type Error = &'static str;
+    type Error = ParseError;
 
     fn try_from(value: &'a [u8]) -> Result<Self, Self::Error> {
         Self::parse(value)
@@ -40,10 +51,8 @@ impl<'a> GovernanceMessage<'a> {
         self.decree
     }
 
-    pub fn parse(span: &'a [u8]) -> Result<Self, &'static str> {
-        if span.is_empty() {
-            return Err("GovernanceMessage span too short. Need at least 1 byte");
-        }
+    pub fn parse(span: &'a [u8]) -> Result<Self, ParseError> {
+        check_min_len(span, 1)?;
 
         let header = GovernanceHeader::parse(span)?;
         let decree = Payload::parse(&span[32..]);
@@ -80,9 +89,9 @@ impl<'a> AsRef<[u8]> for GovernanceHeader<'a> {
 }
 
 impl<'a> TryFrom<&'a [u8]> for GovernanceHeader<'a> {
-    type Error = &'static str;
+    type Error = ParseError;
 
-    fn try_from(span: &'a [u8]) -> Result<Self, &'static str> {
+    fn try_from(span: &'a [u8]) -> Result<Self, ParseError> {
         Self::parse(span)
     }
 }
@@ -92,11 +101,187 @@ impl<'a> GovernanceHeader<'a> {
         self.0[..32].try_into().unwrap()
     }
 
-    pub fn parse(span: &'a [u8]) -> Result<Self, &'static str> {
-        if span.len() < 32 {
-            return Err("GovernanceHeader span too short. Need at least 32 bytes");
-        }
+    pub fn parse(span: &'a [u8]) -> Result<Self, ParseError> {
+        check_min_len(span, 32)?;
 
         Ok(Self(&span[..32]))
     }
 }
+
+/// A governance message, dispatched on its module.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum AnyGovernance<'a> {
+    Core(core::CoreBridgeGovPayload<'a>),
+    TokenBridge(token_bridge::TokenBridgeGovPayload<'a>),
+    CircleIntegration(cctp::CircleIntegrationGovPayload<'a>),
+    /// A module this crate does not know about.
+    Unknown(GovernanceMessage<'a>),
+}
+
+impl<'a> AsRef<[u8]> for AnyGovernance<'a> {
+    fn as_ref(&self) -> &[u8] {
+        match self {
+            Self::Core(inner) => inner.as_ref(),
+            Self::TokenBridge(inner) => inner.as_ref(),
+            Self::CircleIntegration(inner) => inner.as_ref(),
+            Self::Unknown(inner) => inner.as_ref(),
+        }
+    }
+}
+
+impl<'a> TryFrom<Payload<'a>> for AnyGovernance<'a> {
+    type Error = ParseError;
+
+    fn try_from(payload: Payload<'a>) -> Result<Self, ParseError> {
+        Self::parse(payload.0)
+    }
+}
+
+impl<'a> AnyGovernance<'a> {
+    pub fn span(&self) -> &[u8] {
+        self.as_ref()
+    }
+
+    pub fn module(&self) -> [u8; 32] {
+        self.span()[..32].try_into().unwrap()
+    }
+
+    /// Chain the decree applies to, or 0 for all chains. Unknown modules may not have one.
+    pub fn target_chain(&self) -> Option<u16> {
+        match self {
+            Self::Core(inner) => Some(inner.decree().target_chain()),
+            Self::TokenBridge(inner) => Some(inner.decree().target_chain()),
+            Self::CircleIntegration(inner) => Some(inner.decree().target_chain()),
+            Self::Unknown(_) => None,
+        }
+    }
+
+    pub fn parse(span: &'a [u8]) -> Result<Self, ParseError> {
+        let module = GovernanceHeader::parse(span)?.0;
+
+        if module == core::GOV_MODULE {
+            core::CoreBridgeGovPayload::parse(span).map(Self::Core)
+        } else if module == token_bridge::GOV_MODULE {
+            token_bridge::TokenBridgeGovPayload::parse(span).map(Self::TokenBridge)
+        } else if module == cctp::GOV_MODULE {
+            cctp::CircleIntegrationGovPayload::parse(span).map(Self::CircleIntegration)
+        } else {
+            GovernanceMessage::parse(span).map(Self::Unknown)
+        }
+    }
+}
+
+/// Check that `buf` can hold a governance payload with a `len` byte decree and write its module
+/// and decree type.
+pub(crate) fn init_decree(
+    buf: &mut [u8],
+    module: &[u8; 32],
+    action: u8,
+    len: usize,
+) -> Result<(), ParseError> {
+    init_buf(buf, 33 + len)?;
+    buf[..32].copy_from_slice(module);
+    buf[32] = action;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Vaa;
+    use std::vec::Vec;
+
+    fn vaa_with(emitter_chain: u16, emitter_address: [u8; 32], payload: &[u8]) -> Vec<u8> {
+        // Header without signatures.
+        let mut vaa = [1, 0, 0, 0, 0, 0].to_vec();
+        vaa.extend_from_slice(&[0; 8]);
+        vaa.extend_from_slice(&emitter_chain.to_be_bytes());
+        vaa.extend_from_slice(&emitter_address);
+        vaa.extend_from_slice(&[0; 9]);
+        vaa.extend_from_slice(payload);
+        vaa
+    }
+
+    fn governance(module: &[u8; 32], decree: u8, target_chain: u16, rest: &[u8]) -> Vec<u8> {
+        [&module[..], &[decree], &target_chain.to_be_bytes(), rest].concat()
+    }
+
+    #[test]
+    fn dispatch_on_module() {
+        let payload = governance(core::GOV_MODULE, 1, 2, &[0xaa; 32]);
+        let gov = AnyGovernance::parse(&payload).unwrap();
+        assert!(matches!(gov, AnyGovernance::Core(_)));
+        assert_eq!(gov.module(), *core::GOV_MODULE);
+        assert_eq!(gov.target_chain(), Some(2));
+
+        let payload = governance(token_bridge::GOV_MODULE, 1, 0, &[0xbb; 34]);
+        let gov = AnyGovernance::parse(&payload).unwrap();
+        assert!(matches!(gov, AnyGovernance::TokenBridge(_)));
+        assert_eq!(gov.target_chain(), Some(0));
+
+        let payload = governance(cctp::GOV_MODULE, 1, 6, &[1]);
+        let gov = AnyGovernance::parse(&payload).unwrap();
+        assert!(matches!(gov, AnyGovernance::CircleIntegration(_)));
+        assert_eq!(gov.target_chain(), Some(6));
+
+        let payload = governance(&[0xcc; 32], 1, 6, &[]);
+        let gov = AnyGovernance::parse(&payload).unwrap();
+        assert!(matches!(gov, AnyGovernance::Unknown(_)));
+        assert_eq!(gov.target_chain(), None);
+
+        // Known modules are parsed strictly.
+        let payload = governance(core::GOV_MODULE, 1, 2, &[0xaa; 31]);
+        assert_eq!(
+            AnyGovernance::parse(&payload),
+            Err(ParseError::LengthMismatch {
+                expected: 34,
+                got: 33
+            })
+        );
+    }
+
+    #[test]
+    fn vaa_governance() {
+        let payload = governance(core::GOV_MODULE, 1, 2, &[0xaa; 32]);
+
+        let span = vaa_with(GOVERNANCE_CHAIN, GOVERNANCE_EMITTER, &payload);
+        let vaa = Vaa::parse(&span).unwrap();
+        let gov = vaa.governance(2).unwrap();
+        assert_eq!(
+            gov,
+            AnyGovernance::Core(core::CoreBridgeGovPayload::parse(&payload).unwrap())
+        );
+        assert_eq!(
+            vaa.governance(3),
+            Err(ParseError::InvalidTargetChain { chain: 2 })
+        );
+
+        let span = vaa_with(2, GOVERNANCE_EMITTER, &payload);
+        assert_eq!(
+            Vaa::parse(&span).unwrap().governance(2),
+            Err(ParseError::InvalidGovernanceEmitter)
+        );
+
+        let span = vaa_with(GOVERNANCE_CHAIN, [4; 32], &payload);
+        assert_eq!(
+            Vaa::parse(&span).unwrap().governance(2),
+            Err(ParseError::InvalidGovernanceEmitter)
+        );
+
+        // Decrees for all chains are accepted by every chain.
+        let payload = governance(token_bridge::GOV_MODULE, 2, 0, &[0xbb; 32]);
+        let span = vaa_with(GOVERNANCE_CHAIN, GOVERNANCE_EMITTER, &payload);
+        assert!(matches!(
+            Vaa::parse(&span).unwrap().governance(5),
+            Ok(AnyGovernance::TokenBridge(_))
+        ));
+
+        let payload = governance(&[0xcc; 32], 1, 0, &[]);
+        let span = vaa_with(GOVERNANCE_CHAIN, GOVERNANCE_EMITTER, &payload);
+        assert_eq!(
+            Vaa::parse(&span).unwrap().governance(5),
+            Err(ParseError::InvalidGovernanceModule)
+        );
+    }
+}
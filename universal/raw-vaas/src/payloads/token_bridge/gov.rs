@@ -1,4 +1,6 @@
-use crate::Payload;
+use crate::error::{check_len, check_min_len};
+use crate::payloads::init_decree;
+use crate::{ParseError, Payload};
 
 pub(crate) const GOV_MODULE: &[u8; 32] = b"\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00TokenBridge";
 
@@ -17,9 +19,9 @@ impl<'a> AsRef<[u8]> for TokenBridgeGovPayload<'a> {
 }
 
 impl<'a> TryFrom<Payload<'a>> for TokenBridgeGovPayload<'a> {
-    type Error = &'static str;
+    type Error = ParseError;
 
-    fn try_from(payload: Payload<'a>) -> Result<Self, &'static str> {
+    fn try_from(payload: Payload<'a>) -> Result<Self, ParseError> {
         Self::parse(payload.0)
     }
 }
@@ -33,13 +35,12 @@ impl<'a> TokenBridgeGovPayload<'a> {
         self.decree
     }
 
-    pub fn parse(span: &'a [u8]) -> Result<Self, &'static str> {
-        if span.is_empty() {
-            return Err("TokenBridgeGovPayload span too short. Need at least 1 byte");
-        }
+    pub fn parse(span: &'a [u8]) -> Result<Self, ParseError> {
+        // Governance module and decree type.
+        check_min_len(span, 33)?;
 
         if &span[..32] != GOV_MODULE {
-            return Err("Invalid Token Bridge governance message");
+            return Err(ParseError::InvalidGovernanceModule);
         }
 
         let decree = TokenBridgeDecree::parse(&span[32..])?;
@@ -67,9 +68,9 @@ impl AsRef<[u8]> for TokenBridgeDecree<'_> {
 }
 
 impl<'a> TryFrom<&'a [u8]> for TokenBridgeDecree<'a> {
-    type Error = &'static str;
+    type Error = ParseError;
 
-    fn try_from(span: &'a [u8]) -> Result<Self, &'static str> {
+    fn try_from(span: &'a [u8]) -> Result<Self, ParseError> {
         Self::parse(span)
     }
 }
@@ -79,6 +80,12 @@ impl<'a> TokenBridgeDecree<'a> {
         self.as_ref()
     }
 
+    /// Chain the decree applies to, or 0 for all chains.
+    pub fn target_chain(&self) -> u16 {
+        let span = self.span();
+        u16::from_be_bytes([span[0], span[1]])
+    }
+
     pub fn register_chain(&self) -> Option<&RegisterChain> {
         match self {
             TokenBridgeDecree::RegisterChain(inner) => Some(inner),
@@ -121,17 +128,18 @@ impl<'a> TokenBridgeDecree<'a> {
         }
     }
 
-    pub fn parse(span: &'a [u8]) -> Result<Self, &'static str> {
-        if span.is_empty() {
-            return Err("TokenBridgeDecree span too short. Need at least 1 byte");
-        }
+    pub fn parse(span: &'a [u8]) -> Result<Self, ParseError> {
+        check_min_len(span, 1)?;
 
         let decree = match span[0] {
             1 => Self::RegisterChain(TryFrom::try_from(&span[1..])?),
             2 => Self::ContractUpgrade(TryFrom::try_from(&span[1..])?),
             3 => Self::RecoverChainId(TryFrom::try_from(&span[1..])?),
-            _ => {
-                return Err("Invalid Token Bridge decree");
+            value => {
+                return Err(ParseError::UnknownDiscriminant {
+                    kind: "TokenBridgeDecree",
+                    value,
+                });
             }
         };
 
@@ -150,9 +158,9 @@ impl<'a> AsRef<[u8]> for RegisterChain<'a> {
 }
 
 impl<'a> TryFrom<&'a [u8]> for RegisterChain<'a> {
-    type Error = &'static str;
+    type Error = ParseError;
 
-    fn try_from(span: &'a [u8]) -> Result<Self, &'static str> {
+    fn try_from(span: &'a [u8]) -> Result<Self, ParseError> {
         Self::parse(span)
     }
 }
@@ -166,13 +174,12 @@ impl<'a> RegisterChain<'a> {
         self.0[4..36].try_into().unwrap()
     }
 
-    pub fn parse(span: &'a [u8]) -> Result<Self, &'static str> {
-        if span.len() != 36 {
-            return Err("RegisterChain span too short. Need exactly 36 bytes");
-        }
+    pub fn parse(span: &'a [u8]) -> Result<Self, ParseError> {
+        check_len(span, 36)?;
 
-        if span[..2] != [0, 0] {
-            return Err("RegisterChain target chain must be 0");
+        let chain = u16::from_be_bytes([span[0], span[1]]);
+        if chain != 0 {
+            return Err(ParseError::InvalidTargetChain { chain });
         }
 
         Ok(Self(&span[..36]))
@@ -190,9 +197,9 @@ impl<'a> AsRef<[u8]> for ContractUpgrade<'a> {
 }
 
 impl<'a> TryFrom<&'a [u8]> for ContractUpgrade<'a> {
-    type Error = &'static str;
+    type Error = ParseError;
 
-    fn try_from(span: &'a [u8]) -> Result<Self, &'static str> {
+    fn try_from(span: &'a [u8]) -> Result<Self, ParseError> {
         Self::parse(span)
     }
 }
@@ -206,10 +213,8 @@ impl<'a> ContractUpgrade<'a> {
         self.0[2..34].try_into().unwrap()
     }
 
-    pub fn parse(span: &'a [u8]) -> Result<Self, &'static str> {
-        if span.len() != 34 {
-            return Err("ContractUpgrade span too short. Need exactly 34 bytes");
-        }
+    pub fn parse(span: &'a [u8]) -> Result<Self, ParseError> {
+        check_len(span, 34)?;
 
         Ok(Self(&span[..34]))
     }
@@ -226,9 +231,9 @@ impl<'a> AsRef<[u8]> for RecoverChainId<'a> {
 }
 
 impl<'a> TryFrom<&'a [u8]> for RecoverChainId<'a> {
-    type Error = &'static str;
+    type Error = ParseError;
 
-    fn try_from(span: &'a [u8]) -> Result<Self, &'static str> {
+    fn try_from(span: &'a [u8]) -> Result<Self, ParseError> {
         Self::parse(span)
     }
 }
@@ -246,18 +251,107 @@ impl<'a> RecoverChainId<'a> {
         u16::from_be_bytes(self.0[34..36].try_into().unwrap())
     }
 
-    pub fn parse(span: &'a [u8]) -> Result<Self, &'static str> {
-        if span.len() != 36 {
-            return Err("RecoverChainId span too short. Need exactly 36 bytes");
-        }
+    pub fn parse(span: &'a [u8]) -> Result<Self, ParseError> {
+        check_len(span, 36)?;
 
         Ok(Self(&span[..36]))
     }
 }
 
+/// Writes a [`RegisterChain`] governance payload, with its module and decree type, to a
+/// caller-provided buffer. Fields that are not set are zero.
+///
+/// The decree applies to all chains.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct RegisterChainMut<'a>(&'a mut [u8]);
+
+impl<'a> RegisterChainMut<'a> {
+    pub fn new(buf: &'a mut [u8]) -> Result<Self, ParseError> {
+        init_decree(buf, GOV_MODULE, 1, 36)?;
+
+        Ok(Self(&mut buf[..69]))
+    }
+
+    pub fn set_foreign_chain(&mut self, foreign_chain: u16) -> &mut Self {
+        self.0[35..37].copy_from_slice(&foreign_chain.to_be_bytes());
+        self
+    }
+
+    pub fn set_foreign_emitter(&mut self, foreign_emitter: [u8; 32]) -> &mut Self {
+        self.0[37..69].copy_from_slice(&foreign_emitter);
+        self
+    }
+
+    /// Number of bytes written.
+    pub fn finish(self) -> usize {
+        self.0.len()
+    }
+}
+
+/// Writes a [`ContractUpgrade`] governance payload, with its module and decree type, to a
+/// caller-provided buffer. Fields that are not set are zero.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct ContractUpgradeMut<'a>(&'a mut [u8]);
+
+impl<'a> ContractUpgradeMut<'a> {
+    pub fn new(buf: &'a mut [u8]) -> Result<Self, ParseError> {
+        init_decree(buf, GOV_MODULE, 2, 34)?;
+
+        Ok(Self(&mut buf[..67]))
+    }
+
+    pub fn set_chain(&mut self, chain: u16) -> &mut Self {
+        self.0[33..35].copy_from_slice(&chain.to_be_bytes());
+        self
+    }
+
+    pub fn set_implementation(&mut self, implementation: [u8; 32]) -> &mut Self {
+        self.0[35..67].copy_from_slice(&implementation);
+        self
+    }
+
+    /// Number of bytes written.
+    pub fn finish(self) -> usize {
+        self.0.len()
+    }
+}
+
+/// Writes a [`RecoverChainId`] governance payload, with its module and decree type, to a
+/// caller-provided buffer. Fields that are not set are zero.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct RecoverChainIdMut<'a>(&'a mut [u8]);
+
+impl<'a> RecoverChainIdMut<'a> {
+    pub fn new(buf: &'a mut [u8]) -> Result<Self, ParseError> {
+        init_decree(buf, GOV_MODULE, 3, 36)?;
+
+        Ok(Self(&mut buf[..69]))
+    }
+
+    pub fn set_recovered_chain(&mut self, recovered_chain: u16) -> &mut Self {
+        self.0[33..35].copy_from_slice(&recovered_chain.to_be_bytes());
+        self
+    }
+
+    pub fn set_evm_chain_id(&mut self, evm_chain_id: [u8; 32]) -> &mut Self {
+        self.0[35..67].copy_from_slice(&evm_chain_id);
+        self
+    }
+
+    pub fn set_new_chain(&mut self, new_chain: u16) -> &mut Self {
+        self.0[67..69].copy_from_slice(&new_chain.to_be_bytes());
+        self
+    }
+
+    /// Number of bytes written.
+    pub fn finish(self) -> usize {
+        self.0.len()
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use crate::{token_bridge::TokenBridgeGovPayload, Vaa};
+    use crate::{token_bridge::*, ParseError, Vaa};
     use hex_literal::hex;
 
     #[test]
@@ -285,6 +379,14 @@ mod test {
             register_chain.foreign_emitter(),
             hex!("0000000000000000000000003ee18b2214aff97000d974cf647e7c347e8fa585")
         );
+
+        let mut buf = [0xff; 69];
+        let mut writer = RegisterChainMut::new(&mut buf).unwrap();
+        writer
+            .set_foreign_chain(register_chain.foreign_chain())
+            .set_foreign_emitter(register_chain.foreign_emitter());
+        assert_eq!(writer.finish(), 69);
+        assert_eq!(buf, raw_vaa.payload().as_ref());
     }
 
     #[test]
@@ -304,7 +406,13 @@ mod test {
         let err = TokenBridgeGovPayload::try_from(raw_vaa.payload())
             .err()
             .unwrap();
-        assert_eq!(err, "RegisterChain span too short. Need exactly 36 bytes");
+        assert_eq!(
+            err,
+            ParseError::LengthMismatch {
+                expected: 36,
+                got: 37
+            }
+        );
     }
 
     #[test]
@@ -332,6 +440,19 @@ mod test {
             contract_upgrade.implementation(),
             hex!("485edcc94dd21decbbac52610a008c1bc5c8e4859c4504fff7433ad876cb1263")
         );
+
+        let mut buf = [0; 67];
+        let mut writer = ContractUpgradeMut::new(&mut buf).unwrap();
+        writer
+            .set_chain(contract_upgrade.chain())
+            .set_implementation(contract_upgrade.implementation());
+        assert_eq!(writer.finish(), 67);
+        assert_eq!(buf, raw_vaa.payload().as_ref());
+
+        assert_eq!(
+            ContractUpgradeMut::new(&mut buf[..66]).unwrap_err(),
+            ParseError::TooShort { need: 67, got: 66 }
+        );
     }
 
     #[test]
@@ -351,7 +472,13 @@ mod test {
         let err = TokenBridgeGovPayload::try_from(raw_vaa.payload())
             .err()
             .unwrap();
-        assert_eq!(err, "ContractUpgrade span too short. Need exactly 34 bytes");
+        assert_eq!(
+            err,
+            ParseError::LengthMismatch {
+                expected: 34,
+                got: 35
+            }
+        );
     }
 
     #[test]
@@ -375,6 +502,6 @@ mod test {
         let err = TokenBridgeGovPayload::try_from(raw_vaa.payload())
             .err()
             .unwrap();
-        assert_eq!(err, "Invalid Token Bridge governance message");
+        assert_eq!(err, ParseError::InvalidGovernanceModule);
     }
 }
@@ -1,6 +1,8 @@
-use std::borrow::Cow;
+#[cfg(feature = "alloc")]
+use alloc::{borrow::Cow, string::String};
 
-use crate::Payload;
+use crate::error::{check_min_len, init_buf};
+use crate::{ParseError, Payload};
 
 /// A token bridge payload, with type flag
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
@@ -17,9 +19,9 @@ impl<'a> AsRef<[u8]> for TokenBridgePayload<'a> {
 }
 
 impl<'a> TryFrom<Payload<'a>> for TokenBridgePayload<'a> {
-    type Error = &'static str;
+    type Error = ParseError;
 
-    fn try_from(payload: Payload<'a>) -> Result<Self, &'static str> {
+    fn try_from(payload: Payload<'a>) -> Result<Self, ParseError> {
         Self::parse(payload.0)
     }
 }
@@ -33,10 +35,8 @@ impl<'a> TokenBridgePayload<'a> {
         self.message
     }
 
-    pub fn parse(span: &'a [u8]) -> Result<Self, &'static str> {
-        if span.is_empty() {
-            return Err("TokenBridgePayload span too short. Need at least 1 byte");
-        }
+    pub fn parse(span: &'a [u8]) -> Result<Self, ParseError> {
+        check_min_len(span, 1)?;
 
         let message = TokenBridgeMessage::parse(span)?;
 
@@ -53,9 +53,9 @@ pub enum TokenBridgeMessage<'a> {
 }
 
 impl<'a> TryFrom<Payload<'a>> for TokenBridgeMessage<'a> {
-    type Error = &'static str;
+    type Error = ParseError;
 
-    fn try_from(payload: Payload<'a>) -> Result<Self, &'static str> {
+    fn try_from(payload: Payload<'a>) -> Result<Self, ParseError> {
         Self::parse(payload.0)
     }
 }
@@ -117,10 +117,8 @@ impl<'a> TokenBridgeMessage<'a> {
         }
     }
 
-    pub fn parse(span: &'a [u8]) -> Result<Self, &'static str> {
-        if span.is_empty() {
-            return Err("TokenBridgeMessage span too short. Need at least 1 byte");
-        }
+    pub fn parse(span: &'a [u8]) -> Result<Self, ParseError> {
+        check_min_len(span, 1)?;
 
         match span[0] {
             1 => Ok(Self::Transfer(Transfer::parse(&span[1..])?)),
@@ -128,7 +126,10 @@ impl<'a> TokenBridgeMessage<'a> {
             3 => Ok(Self::TransferWithMessage(TransferWithMessage::parse(
                 &span[1..],
             )?)),
-            _ => Err("Unknown TokenBridgeMessage type"),
+            value => Err(ParseError::UnknownDiscriminant {
+                kind: "TokenBridgeMessage",
+                value,
+            }),
         }
     }
 }
@@ -168,10 +169,8 @@ impl<'a> Transfer<'a> {
         self.0[100..132].try_into().unwrap()
     }
 
-    pub fn parse(span: &'a [u8]) -> Result<Self, &'static str> {
-        if span.len() < 132 {
-            return Err("Transfer span too short. Need exactly 132 bytes");
-        }
+    pub fn parse(span: &'a [u8]) -> Result<Self, ParseError> {
+        check_min_len(span, 132)?;
 
         Ok(Self(&span[..132]))
     }
@@ -188,9 +187,9 @@ impl<'a> AsRef<[u8]> for Attestation<'a> {
 }
 
 impl<'a> TryFrom<&'a [u8]> for Attestation<'a> {
-    type Error = &'static str;
+    type Error = ParseError;
 
-    fn try_from(span: &'a [u8]) -> Result<Self, &'static str> {
+    fn try_from(span: &'a [u8]) -> Result<Self, ParseError> {
         Self::parse(span)
     }
 }
@@ -208,28 +207,38 @@ impl<'a> Attestation<'a> {
         self.0[34]
     }
 
+    /// Symbol with trailing zero padding removed.
+    pub fn symbol_bytes(&self) -> &'a [u8] {
+        trim_trailing_zeros(&self.0[35..67])
+    }
+
+    /// Symbol as encoded, zero padded to 32 bytes.
+    pub fn symbol_raw(&self) -> [u8; 32] {
+        self.0[35..67].try_into().unwrap()
+    }
+
+    #[cfg(feature = "alloc")]
     pub fn symbol(&self) -> Cow<'a, str> {
-        let idx = &self.0[35..67]
-            .iter()
-            .rposition(|x| *x != 0)
-            .map(|i| i + 1)
-            .unwrap_or_default();
-        String::from_utf8_lossy(&self.0[35..35 + idx])
+        String::from_utf8_lossy(self.symbol_bytes())
+    }
+
+    /// Name with trailing zero padding removed.
+    pub fn name_bytes(&self) -> &'a [u8] {
+        trim_trailing_zeros(&self.0[67..99])
     }
 
+    /// Name as encoded, zero padded to 32 bytes.
+    pub fn name_raw(&self) -> [u8; 32] {
+        self.0[67..99].try_into().unwrap()
+    }
+
+    #[cfg(feature = "alloc")]
     pub fn name(&self) -> Cow<'a, str> {
-        let idx = &self.0[67..99]
-            .iter()
-            .rposition(|x| *x != 0)
-            .map(|i| i + 1)
-            .unwrap_or_default();
-        String::from_utf8_lossy(&self.0[67..67 + idx])
+        String::from_utf8_lossy(self.name_bytes())
     }
 
-    pub fn parse(span: &'a [u8]) -> Result<Self, &'static str> {
-        if span.len() < 99 {
-            return Err("Attestation span too short. Need exactly 99 bytes");
-        }
+    pub fn parse(span: &'a [u8]) -> Result<Self, ParseError> {
+        check_min_len(span, 99)?;
 
         Ok(Self(&span[..99]))
     }
@@ -246,9 +255,9 @@ impl<'a> AsRef<[u8]> for TransferWithMessage<'a> {
 }
 
 impl<'a> TryFrom<&'a [u8]> for TransferWithMessage<'a> {
-    type Error = &'static str;
+    type Error = ParseError;
 
-    fn try_from(span: &'a [u8]) -> Result<Self, &'static str> {
+    fn try_from(span: &'a [u8]) -> Result<Self, ParseError> {
         Self::parse(span)
     }
 }
@@ -278,22 +287,215 @@ impl<'a> TransferWithMessage<'a> {
         self.0[100..132].try_into().unwrap()
     }
 
-    pub fn payload(&'a self) -> Payload<'a> {
+    pub fn payload(&self) -> Payload<'a> {
         Payload::parse(&self.0[132..])
     }
 
-    pub fn parse(span: &'a [u8]) -> Result<Self, &'static str> {
-        if span.len() < 132 {
-            return Err("TransferWithMessage span too short. Need at least 132 bytes");
-        }
+    pub fn parse(span: &'a [u8]) -> Result<Self, ParseError> {
+        check_min_len(span, 132)?;
 
         Ok(Self(span))
     }
 }
 
+/// Writes a [`Transfer`], with its type flag, to a caller-provided buffer. Fields that are not
+/// set are zero.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct TransferMut<'a>(&'a mut [u8]);
+
+impl<'a> TransferMut<'a> {
+    pub fn new(buf: &'a mut [u8]) -> Result<Self, ParseError> {
+        init_buf(buf, 133)?;
+        buf[0] = 1;
+
+        Ok(Self(&mut buf[..133]))
+    }
+
+    pub fn set_amount(&mut self, amount: [u8; 32]) -> &mut Self {
+        self.0[1..33].copy_from_slice(&amount);
+        self
+    }
+
+    pub fn set_token_address(&mut self, token_address: [u8; 32]) -> &mut Self {
+        self.0[33..65].copy_from_slice(&token_address);
+        self
+    }
+
+    pub fn set_token_chain(&mut self, token_chain: u16) -> &mut Self {
+        self.0[65..67].copy_from_slice(&token_chain.to_be_bytes());
+        self
+    }
+
+    pub fn set_recipient(&mut self, recipient: [u8; 32]) -> &mut Self {
+        self.0[67..99].copy_from_slice(&recipient);
+        self
+    }
+
+    pub fn set_recipient_chain(&mut self, recipient_chain: u16) -> &mut Self {
+        self.0[99..101].copy_from_slice(&recipient_chain.to_be_bytes());
+        self
+    }
+
+    pub fn set_relayer_fee(&mut self, relayer_fee: [u8; 32]) -> &mut Self {
+        self.0[101..133].copy_from_slice(&relayer_fee);
+        self
+    }
+
+    /// Number of bytes written.
+    pub fn finish(self) -> usize {
+        self.0.len()
+    }
+}
+
+/// Writes an [`Attestation`], with its type flag, to a caller-provided buffer. Fields that are
+/// not set are zero.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct AttestationMut<'a>(&'a mut [u8]);
+
+impl<'a> AttestationMut<'a> {
+    pub fn new(buf: &'a mut [u8]) -> Result<Self, ParseError> {
+        init_buf(buf, 100)?;
+        buf[0] = 2;
+
+        Ok(Self(&mut buf[..100]))
+    }
+
+    pub fn set_token_address(&mut self, token_address: [u8; 32]) -> &mut Self {
+        self.0[1..33].copy_from_slice(&token_address);
+        self
+    }
+
+    pub fn set_token_chain(&mut self, token_chain: u16) -> &mut Self {
+        self.0[33..35].copy_from_slice(&token_chain.to_be_bytes());
+        self
+    }
+
+    pub fn set_decimals(&mut self, decimals: u8) -> &mut Self {
+        self.0[35] = decimals;
+        self
+    }
+
+    /// Symbol of at most 32 bytes, padded with trailing zeros.
+    pub fn set_symbol(&mut self, symbol: &[u8]) -> Result<&mut Self, ParseError> {
+        write_padded(&mut self.0[36..68], symbol)?;
+        Ok(self)
+    }
+
+    /// Name of at most 32 bytes, padded with trailing zeros.
+    pub fn set_name(&mut self, name: &[u8]) -> Result<&mut Self, ParseError> {
+        write_padded(&mut self.0[68..100], name)?;
+        Ok(self)
+    }
+
+    /// Number of bytes written.
+    pub fn finish(self) -> usize {
+        self.0.len()
+    }
+}
+
+/// Writes a [`TransferWithMessage`], with its type flag, to a caller-provided buffer. Fields that
+/// are not set are zero.
+///
+/// The payload can be copied with [`set_payload`](Self::set_payload), or written in place to
+/// [`payload_mut`](Self::payload_mut) followed by [`set_payload_len`](Self::set_payload_len).
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct TransferWithMessageMut<'a> {
+    span: &'a mut [u8],
+    payload_len: usize,
+}
+
+impl<'a> TransferWithMessageMut<'a> {
+    pub fn new(buf: &'a mut [u8]) -> Result<Self, ParseError> {
+        init_buf(buf, 133)?;
+        buf[0] = 3;
+
+        Ok(Self {
+            span: buf,
+            payload_len: 0,
+        })
+    }
+
+    pub fn set_amount(&mut self, amount: [u8; 32]) -> &mut Self {
+        self.span[1..33].copy_from_slice(&amount);
+        self
+    }
+
+    pub fn set_token_address(&mut self, token_address: [u8; 32]) -> &mut Self {
+        self.span[33..65].copy_from_slice(&token_address);
+        self
+    }
+
+    pub fn set_token_chain(&mut self, token_chain: u16) -> &mut Self {
+        self.span[65..67].copy_from_slice(&token_chain.to_be_bytes());
+        self
+    }
+
+    pub fn set_redeemer(&mut self, redeemer: [u8; 32]) -> &mut Self {
+        self.span[67..99].copy_from_slice(&redeemer);
+        self
+    }
+
+    pub fn set_redeemer_chain(&mut self, redeemer_chain: u16) -> &mut Self {
+        self.span[99..101].copy_from_slice(&redeemer_chain.to_be_bytes());
+        self
+    }
+
+    pub fn set_sender(&mut self, sender: [u8; 32]) -> &mut Self {
+        self.span[101..133].copy_from_slice(&sender);
+        self
+    }
+
+    /// The rest of the buffer, where the payload is written.
+    pub fn payload_mut(&mut self) -> &mut [u8] {
+        &mut self.span[133..]
+    }
+
+    /// Set the length of a payload written to [`payload_mut`](Self::payload_mut).
+    pub fn set_payload_len(&mut self, len: usize) -> Result<&mut Self, ParseError> {
+        check_min_len(self.span, 133 + len)?;
+
+        self.payload_len = len;
+        Ok(self)
+    }
+
+    pub fn set_payload(&mut self, payload: &[u8]) -> Result<&mut Self, ParseError> {
+        self.set_payload_len(payload.len())?;
+
+        self.span[133..(133 + payload.len())].copy_from_slice(payload);
+        Ok(self)
+    }
+
+    /// Number of bytes written.
+    pub fn finish(self) -> usize {
+        133 + self.payload_len
+    }
+}
+
+fn write_padded(field: &mut [u8], value: &[u8]) -> Result<(), ParseError> {
+    if value.len() > field.len() {
+        return Err(ParseError::TooLong {
+            max: field.len(),
+            got: value.len(),
+        });
+    }
+
+    field.fill(0);
+    field[..value.len()].copy_from_slice(value);
+    Ok(())
+}
+
+fn trim_trailing_zeros(bytes: &[u8]) -> &[u8] {
+    let len = bytes
+        .iter()
+        .rposition(|x| *x != 0)
+        .map(|i| i + 1)
+        .unwrap_or_default();
+    &bytes[..len]
+}
+
 #[cfg(test)]
 mod test {
-    use crate::{token_bridge::TokenBridgePayload, Vaa};
+    use crate::{token_bridge::*, Body, BodyMut, ParseError, Vaa};
     use hex_literal::hex;
 
     #[test]
@@ -320,5 +522,89 @@ mod test {
         assert_eq!(attestation.decimals(), 18);
         assert_eq!(attestation.symbol(), "WETH");
         assert_eq!(attestation.name(), "Wrapped ether");
+        assert_eq!(attestation.symbol_bytes(), b"WETH");
+        assert_eq!(attestation.name_bytes(), b"Wrapped ether");
+        assert_eq!(&attestation.symbol_raw()[..5], b"WETH\0");
+        assert_eq!(attestation.name_raw()[13..], [0; 19]);
+
+        let mut buf = [0xff; 100];
+        let mut writer = AttestationMut::new(&mut buf).unwrap();
+        writer
+            .set_token_address(attestation.token_address())
+            .set_token_chain(attestation.token_chain())
+            .set_decimals(attestation.decimals())
+            .set_symbol(b"WETH")
+            .unwrap()
+            .set_name(b"Wrapped ether")
+            .unwrap();
+        assert_eq!(writer.finish(), 100);
+        assert_eq!(buf, raw_vaa.payload().as_ref());
+
+        let mut writer = AttestationMut::new(&mut buf).unwrap();
+        assert_eq!(
+            writer.set_symbol(&[0x41; 33]).unwrap_err(),
+            ParseError::TooLong { max: 32, got: 33 }
+        );
+    }
+
+    #[test]
+    fn transfer_with_message_in_body() {
+        let mut buf = [0; 256];
+
+        let mut body = BodyMut::new(&mut buf).unwrap();
+        body.set_timestamp(1)
+            .set_nonce(2)
+            .set_emitter_chain(2)
+            .set_emitter_address([0xee; 32])
+            .set_sequence(3)
+            .set_consistency_level(15);
+
+        let mut transfer = TransferWithMessageMut::new(body.payload_mut()).unwrap();
+        transfer
+            .set_amount([1; 32])
+            .set_token_address([2; 32])
+            .set_token_chain(2)
+            .set_redeemer([3; 32])
+            .set_redeemer_chain(1)
+            .set_sender([4; 32])
+            .set_payload(b"hello")
+            .unwrap();
+        let payload_len = transfer.finish();
+        assert_eq!(payload_len, 138);
+
+        body.set_payload_len(payload_len).unwrap();
+        let len = body.finish();
+        assert_eq!(len, 51 + 138);
+
+        let body = Body::parse(&buf[..len]).unwrap();
+        assert_eq!(body.timestamp(), 1);
+        assert_eq!(body.nonce(), 2);
+        assert_eq!(body.emitter_chain(), 2);
+        assert_eq!(body.emitter_address(), [0xee; 32]);
+        assert_eq!(body.sequence(), 3);
+        assert_eq!(body.consistency_level(), 15);
+
+        let transfer = body
+            .payload()
+            .parse_as::<TokenBridgeMessage>()
+            .unwrap()
+            .to_transfer_with_message_unchecked();
+        assert_eq!(transfer.amount(), [1; 32]);
+        assert_eq!(transfer.token_address(), [2; 32]);
+        assert_eq!(transfer.token_chain(), 2);
+        assert_eq!(transfer.redeemer(), [3; 32]);
+        assert_eq!(transfer.redeemer_chain(), 1);
+        assert_eq!(transfer.sender(), [4; 32]);
+        assert_eq!(transfer.payload().as_ref(), b"hello");
+
+        let mut body = BodyMut::new(&mut buf[..60]).unwrap();
+        assert_eq!(
+            TransferWithMessageMut::new(body.payload_mut()).unwrap_err(),
+            ParseError::TooShort { need: 133, got: 9 }
+        );
+        assert_eq!(
+            body.set_payload_len(10).unwrap_err(),
+            ParseError::TooShort { need: 61, got: 60 }
+        );
     }
 }
@@ -1,3 +1,6 @@
+use crate::error::{check_len, check_min_len, init_buf};
+use crate::{AnyGovernance, ParseError, RawPayload, GOVERNANCE_CHAIN, GOVERNANCE_EMITTER};
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub struct Vaa<'a> {
     pub(crate) span: &'a [u8],
@@ -12,7 +15,7 @@ impl<'a> AsRef<[u8]> for Vaa<'a> {
 }
 
 impl<'a> TryFrom<&'a [u8]> for Vaa<'a> {
-    type Error = &'static str;
+    type Error = ParseError;
 
     fn try_from(value: &'a [u8]) -> Result<Self, Self::Error> {
         Self::parse(value)
@@ -36,6 +39,10 @@ impl<'a> Vaa<'a> {
         self.header.signatures()
     }
 
+    pub fn header(&self) -> Header<'a> {
+        self.header
+    }
+
     pub fn body(&self) -> Body<'a> {
         self.body
     }
@@ -44,7 +51,78 @@ impl<'a> Vaa<'a> {
         self.body.payload()
     }
 
-    pub fn parse(span: &'a [u8]) -> Result<Self, &'static str> {
+    /// Parse the payload as governance, checking that it was emitted by the
+    /// [governance emitter](crate::GOVERNANCE_EMITTER), that its module is known and that it
+    /// targets all chains (0) or `expected_chain`.
+    pub fn governance(&self, expected_chain: u16) -> Result<AnyGovernance<'a>, ParseError> {
+        let body = self.body();
+        if body.emitter_chain() != GOVERNANCE_CHAIN || body.emitter_address() != GOVERNANCE_EMITTER
+        {
+            return Err(ParseError::InvalidGovernanceEmitter);
+        }
+
+        let governance = AnyGovernance::parse(self.payload().0)?;
+        match governance.target_chain() {
+            None => Err(ParseError::InvalidGovernanceModule),
+            Some(chain) if chain != 0 && chain != expected_chain => {
+                Err(ParseError::InvalidTargetChain { chain })
+            }
+            Some(_) => Ok(governance),
+        }
+    }
+
+    /// Parse the payload as `T`. See [`Payload::parse_as`].
+    pub fn payload_as<T: RawPayload<'a>>(&self) -> Result<T, ParseError> {
+        self.payload().parse_as()
+    }
+
+    /// Verify the signatures against a guardian set, given as the Ethereum addresses of its
+    /// guardians ordered by guardian index. Returns the indices of the guardians who signed, if
+    /// they reach [quorum](crate::utils::quorum).
+    ///
+    /// Signatures must be ordered by strictly ascending guardian index, so that no guardian is
+    /// counted twice. Signatures that do not recover to the guardian at their index do not count
+    /// towards quorum; if too few remain, this returns [`ParseError::NoQuorum`].
+    // available when `off-chain` feature is enabled
+    #[cfg(feature = "off-chain")]
+    pub fn verify(&self, guardians: &[[u8; 20]]) -> Result<alloc::vec::Vec<u8>, ParseError> {
+        let digest = self.body.double_digest();
+
+        let mut signers = alloc::vec::Vec::with_capacity(self.signature_count().into());
+        let mut last_index = None;
+        for sig in self.signatures() {
+            let index = sig.guardian_index();
+            match last_index {
+                Some(previous) if index == previous => {
+                    return Err(ParseError::DuplicateSignature { index });
+                }
+                Some(previous) if index < previous => {
+                    return Err(ParseError::UnsortedSignatures { previous, index });
+                }
+                _ => {}
+            }
+            last_index = Some(index);
+
+            let valid = guardians
+                .get(usize::from(index))
+                .is_some_and(|guardian| sig.recover_eth_address(&digest) == Ok(*guardian));
+            if valid {
+                signers.push(index);
+            }
+        }
+
+        let need = crate::utils::quorum(guardians.len());
+        if signers.len() < need {
+            return Err(ParseError::NoQuorum {
+                need,
+                got: signers.len(),
+            });
+        }
+
+        Ok(signers)
+    }
+
+    pub fn parse(span: &'a [u8]) -> Result<Self, ParseError> {
         let header = Header::parse(span)?;
         let body = Body::parse(&span[header.0.len()..])?;
 
@@ -62,7 +140,7 @@ impl<'a> AsRef<[u8]> for Header<'a> {
 }
 
 impl<'a> TryFrom<&'a [u8]> for Header<'a> {
-    type Error = &'static str;
+    type Error = ParseError;
 
     fn try_from(value: &'a [u8]) -> Result<Self, Self::Error> {
         Self::parse(value)
@@ -88,21 +166,30 @@ impl<'a> Header<'a> {
 
     pub fn signatures(&self) -> impl Iterator<Item = GuardianSetSig<'_>> {
         self.raw_signatures()
-            .chunks(66)
+            .chunks_exact(66)
             .map(GuardianSetSig::unchecked_from)
     }
 
-    pub fn parse(span: &'a [u8]) -> Result<Self, &'static str> {
-        if span.len() < 6 {
-            return Err("Header: invalid length. Expected at least 6 bytes.");
-        }
+    /// Check the header the way the core contracts would before verifying signatures:
+    /// * The version is 1.
+    /// * Guardian indices are strictly ascending, so no guardian signs twice.
+    /// * Guardian indices are less than `guardian_set_len`.
+    /// * No signature is malleable, i.e. has a high `s`.
+    pub fn validate(&self, guardian_set_len: usize) -> Result<(), ParseError> {
+        crate::utils::validate_header(
+            self.version(),
+            self.signatures().map(|sig| (sig.guardian_index(), sig.s())),
+            guardian_set_len,
+        )
+    }
+
+    pub fn parse(span: &'a [u8]) -> Result<Self, ParseError> {
+        check_min_len(span, 6)?;
 
         let expected_len = 6 + span[5] as usize * 66;
 
         // slice not long enough to contain all signatures
-        if span.len() < expected_len {
-            return Err("Header: Insufficient bytes to parse all signatures");
-        }
+        check_min_len(span, expected_len)?;
 
         Ok(Self(&span[..expected_len]))
     }
@@ -118,7 +205,7 @@ impl<'a> AsRef<[u8]> for Body<'a> {
 }
 
 impl<'a> TryFrom<&'a [u8]> for Body<'a> {
-    type Error = &'static str;
+    type Error = ParseError;
 
     fn try_from(value: &'a [u8]) -> Result<Self, Self::Error> {
         Self::parse(value)
@@ -158,10 +245,8 @@ impl<'a> Body<'a> {
         })
     }
 
-    pub fn parse(span: &'a [u8]) -> Result<Self, &'static str> {
-        if span.len() < 51 {
-            return Err("Body: invalid length. Expected at least 51 bytes.");
-        }
+    pub fn parse(span: &'a [u8]) -> Result<Self, ParseError> {
+        check_min_len(span, 51)?;
 
         Ok(Self(span))
     }
@@ -181,6 +266,84 @@ impl<'a> Body<'a> {
     }
 }
 
+/// Writes a [`Body`] to a caller-provided buffer. Fields that are not set are zero.
+///
+/// The payload can be copied with [`set_payload`](Self::set_payload), or written in place to
+/// [`payload_mut`](Self::payload_mut) (e.g. with
+/// [`TransferWithMessageMut`](crate::token_bridge::TransferWithMessageMut)) followed by
+/// [`set_payload_len`](Self::set_payload_len).
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct BodyMut<'a> {
+    span: &'a mut [u8],
+    payload_len: usize,
+}
+
+impl<'a> BodyMut<'a> {
+    pub fn new(buf: &'a mut [u8]) -> Result<Self, ParseError> {
+        init_buf(buf, 51)?;
+
+        Ok(Self {
+            span: buf,
+            payload_len: 0,
+        })
+    }
+
+    pub fn set_timestamp(&mut self, timestamp: u32) -> &mut Self {
+        self.span[0..4].copy_from_slice(&timestamp.to_be_bytes());
+        self
+    }
+
+    pub fn set_nonce(&mut self, nonce: u32) -> &mut Self {
+        self.span[4..8].copy_from_slice(&nonce.to_be_bytes());
+        self
+    }
+
+    pub fn set_emitter_chain(&mut self, chain: u16) -> &mut Self {
+        self.span[8..10].copy_from_slice(&chain.to_be_bytes());
+        self
+    }
+
+    pub fn set_emitter_address(&mut self, address: [u8; 32]) -> &mut Self {
+        self.span[10..42].copy_from_slice(&address);
+        self
+    }
+
+    pub fn set_sequence(&mut self, sequence: u64) -> &mut Self {
+        self.span[42..50].copy_from_slice(&sequence.to_be_bytes());
+        self
+    }
+
+    pub fn set_consistency_level(&mut self, consistency_level: u8) -> &mut Self {
+        self.span[50] = consistency_level;
+        self
+    }
+
+    /// The rest of the buffer, where the payload is written.
+    pub fn payload_mut(&mut self) -> &mut [u8] {
+        &mut self.span[51..]
+    }
+
+    /// Set the length of a payload written to [`payload_mut`](Self::payload_mut).
+    pub fn set_payload_len(&mut self, len: usize) -> Result<&mut Self, ParseError> {
+        check_min_len(self.span, 51 + len)?;
+
+        self.payload_len = len;
+        Ok(self)
+    }
+
+    pub fn set_payload(&mut self, payload: &[u8]) -> Result<&mut Self, ParseError> {
+        self.set_payload_len(payload.len())?;
+
+        self.span[51..(51 + payload.len())].copy_from_slice(payload);
+        Ok(self)
+    }
+
+    /// Number of bytes written.
+    pub fn finish(self) -> usize {
+        51 + self.payload_len
+    }
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub struct Payload<'a>(pub(crate) &'a [u8]);
 
@@ -202,6 +365,12 @@ impl<'a> Payload<'a> {
     pub fn is_empty(&self) -> bool {
         self.0.is_empty()
     }
+
+    /// Parse the payload as `T`, which can be any view in this crate or an app payload that
+    /// implements [`RawPayload`].
+    pub fn parse_as<T: RawPayload<'a>>(&self) -> Result<T, ParseError> {
+        T::parse(self.0)
+    }
 }
 
 impl<'a> From<&'a [u8]> for Payload<'a> {
@@ -250,14 +419,28 @@ impl<'a> GuardianSetSig<'a> {
         self.0[1..].try_into().unwrap()
     }
 
-    pub fn recovery_id(&self) -> u8 {
-        self.0[65]
+    /// Recover the Ethereum address of the guardian who signed `digest`, which for VAAs is the
+    /// double digest of the body.
+    // available when `off-chain` feature is enabled
+    #[cfg(feature = "off-chain")]
+    pub fn recover_eth_address(&self, digest: &[u8; 32]) -> Result<[u8; 20], ParseError> {
+        let message = libsecp256k1::Message::parse(digest);
+        let signature = libsecp256k1::Signature::parse_standard(&self.rs())
+            .map_err(|_| ParseError::InvalidSignature)?;
+        let recovery_id =
+            libsecp256k1::RecoveryId::parse(self.v()).map_err(|_| ParseError::InvalidSignature)?;
+
+        let pubkey = libsecp256k1::recover(&message, &signature, &recovery_id)
+            .map_err(|_| ParseError::InvalidSignature)?;
+
+        // The address is the last 20 bytes of the hash of the uncompressed public key, without
+        // its 0x04 prefix.
+        let hash = crate::utils::keccak256(&pubkey.serialize()[1..]);
+        Ok(hash[12..].try_into().unwrap())
     }
 
-    pub fn parse(span: &'a [u8]) -> Result<GuardianSetSig<'a>, &'static str> {
-        if span.len() != 66 {
-            return Err("expected exactly 66 bytes");
-        }
+    pub fn parse(span: &'a [u8]) -> Result<GuardianSetSig<'a>, ParseError> {
+        check_len(span, 66)?;
 
         Ok(Self(span))
     }
@@ -268,9 +451,80 @@ impl<'a> GuardianSetSig<'a> {
 }
 
 impl<'a> TryFrom<&'a [u8]> for GuardianSetSig<'a> {
-    type Error = &'static str;
+    type Error = ParseError;
 
     fn try_from(value: &'a [u8]) -> Result<Self, Self::Error> {
         Self::parse(value)
     }
 }
+
+#[cfg(all(test, feature = "off-chain"))]
+mod test {
+    use hex_literal::hex;
+
+    use super::*;
+
+    // https://github.com/wormhole-foundation/wormhole/blob/b09a644dac97fa8e037a16765728217ff3a1d057/clients/js/parse_tests/token-bridge-transfer-1.expected
+    const TRANSFER: &[u8] = &hex!("010000000001007d204ad9447c4dfd6be62406e7f5a05eec96300da4048e70ff530cfb52aec44807e98194990710ff166eb1b2eac942d38bc1cd6018f93662a6578d985e87c8d0016221346b0000b8bd0001c69a1b1a65dd336bf1df6a77afb501fc25db7fc0938cb08595a9ef473265cb4f0000000000000003200100000000000000000000000000000000000000000000000000000002540be400165809739240a0ac03b98440fe8985548e3aa683cd0d4d9df5b5659669faa3010001000000000000000000000000c10820983f33456ce7beb3a046f5a83fa34f027d00020000000000000000000000000000000000000000000000000000000000000000");
+
+    // The first devnet guardian.
+    const GUARDIAN: [u8; 20] = hex!("befa429d57cd18b7f8a4d91a2da9ab4af05d0fbe");
+
+    #[test]
+    fn digest() {
+        let vaa = Vaa::parse(TRANSFER).unwrap();
+        assert_eq!(
+            vaa.body().digest(),
+            crate::utils::keccak256(&TRANSFER[72..])
+        );
+        assert_eq!(
+            vaa.body().double_digest(),
+            hex!("2862e5873955ea104bb3e122831bdc43bbcb413da5b1123514640b950d038967")
+        );
+    }
+
+    #[test]
+    fn recover_eth_address() {
+        let vaa = Vaa::parse(TRANSFER).unwrap();
+        let sig = vaa.signatures().next().unwrap();
+
+        let digest = vaa.body().double_digest();
+        assert_eq!(sig.recover_eth_address(&digest), Ok(GUARDIAN));
+        assert_ne!(sig.recover_eth_address(&vaa.body().digest()), Ok(GUARDIAN));
+    }
+
+    #[test]
+    fn verify() {
+        let vaa = Vaa::parse(TRANSFER).unwrap();
+        assert_eq!(vaa.verify(&[GUARDIAN]), Ok(alloc::vec![0]));
+
+        // Wrong guardian, or quorum out of reach.
+        assert_eq!(
+            vaa.verify(&[[0; 20]]),
+            Err(ParseError::NoQuorum { need: 1, got: 0 })
+        );
+        assert_eq!(
+            vaa.verify(&[GUARDIAN, [1; 20]]),
+            Err(ParseError::NoQuorum { need: 2, got: 1 })
+        );
+        assert_eq!(
+            vaa.verify(&[]),
+            Err(ParseError::NoQuorum { need: 1, got: 0 })
+        );
+    }
+
+    #[test]
+    fn verify_repeated_signature() {
+        // The same signature twice.
+        let mut buf = TRANSFER[..6].to_vec();
+        buf[5] = 2;
+        buf.extend_from_slice(&TRANSFER[6..72]);
+        buf.extend_from_slice(&TRANSFER[6..]);
+
+        let vaa = Vaa::parse(&buf).unwrap();
+        assert_eq!(
+            vaa.verify(&[GUARDIAN, [1; 20]]),
+            Err(ParseError::DuplicateSignature { index: 0 })
+        );
+    }
+}
@@ -0,0 +1,154 @@
+use crate::{
+    cctp, core, token_bridge, AnyGovernance, Body, GovernanceHeader, GovernanceMessage,
+    GuardianSetSig, Header, ParseError, Payload, Vaa,
+};
+
+/// A zero-copy view that can be parsed from a span, which it borrows.
+///
+/// This is implemented by every view in this crate. Implement it for your own payloads to parse
+/// them with [`Payload::parse_as`] and [`Vaa::payload_as`], e.g. the inner payload of a
+/// [`TransferWithMessage`](token_bridge::TransferWithMessage) or a CCTP
+/// [`Deposit`](cctp::Deposit).
+///
+/// Like the parsers in this crate, implementations should not panic on any input.
+pub trait RawPayload<'a>: Sized {
+    fn parse(span: &'a [u8]) -> Result<Self, ParseError>;
+}
+
+impl<'a> RawPayload<'a> for &'a [u8] {
+    fn parse(span: &'a [u8]) -> Result<Self, ParseError> {
+        Ok(span)
+    }
+}
+
+impl<'a> RawPayload<'a> for Payload<'a> {
+    fn parse(span: &'a [u8]) -> Result<Self, ParseError> {
+        Ok(Payload::parse(span))
+    }
+}
+
+macro_rules! impl_raw_payload {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl<'a> RawPayload<'a> for $ty {
+                fn parse(span: &'a [u8]) -> Result<Self, ParseError> {
+                    <$ty>::parse(span)
+                }
+            }
+        )*
+    };
+}
+
+impl_raw_payload!(
+    Vaa<'a>,
+    Header<'a>,
+    Body<'a>,
+    GuardianSetSig<'a>,
+    GovernanceMessage<'a>,
+    GovernanceHeader<'a>,
+    AnyGovernance<'a>,
+    core::CoreBridgeGovPayload<'a>,
+    core::CoreBridgeDecree<'a>,
+    core::ContractUpgrade<'a>,
+    core::GuardianSetUpdate<'a>,
+    core::SetMessageFee<'a>,
+    core::TransferFees<'a>,
+    core::RecoverChainId<'a>,
+    token_bridge::TokenBridgeGovPayload<'a>,
+    token_bridge::TokenBridgeDecree<'a>,
+    token_bridge::RegisterChain<'a>,
+    token_bridge::ContractUpgrade<'a>,
+    token_bridge::RecoverChainId<'a>,
+    token_bridge::TokenBridgePayload<'a>,
+    token_bridge::TokenBridgeMessage<'a>,
+    token_bridge::Transfer<'a>,
+    token_bridge::Attestation<'a>,
+    token_bridge::TransferWithMessage<'a>,
+    cctp::CircleIntegrationGovPayload<'a>,
+    cctp::CircleIntegrationDecree<'a>,
+    cctp::UpdateWormholeFinality<'a>,
+    cctp::RegisterEmitterAndDomain<'a>,
+    cctp::ContractUpgrade<'a>,
+    cctp::WormholeCctpPayload<'a>,
+    cctp::WormholeCctpMessage<'a>,
+    cctp::Deposit<'a>,
+);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::error::check_len;
+    use crate::token_bridge::TokenBridgeMessage;
+    use std::vec::Vec;
+
+    /// An app payload carried by a Token Bridge transfer with message.
+    #[derive(Debug, PartialEq, Eq)]
+    struct Swap<'a>(&'a [u8]);
+
+    impl<'a> Swap<'a> {
+        fn min_amount_out(&self) -> u64 {
+            u64::from_be_bytes(self.0[1..9].try_into().unwrap())
+        }
+    }
+
+    impl<'a> RawPayload<'a> for Swap<'a> {
+        fn parse(span: &'a [u8]) -> Result<Self, ParseError> {
+            check_len(span, 9)?;
+            if span[0] != 7 {
+                return Err(ParseError::UnknownDiscriminant {
+                    kind: "Swap",
+                    value: span[0],
+                });
+            }
+
+            Ok(Self(span))
+        }
+    }
+
+    fn transfer_with_message_vaa(payload: &[u8]) -> Vec<u8> {
+        // Header without signatures, zeroed body fields, then the Token Bridge message.
+        let mut vaa = [1, 0, 0, 0, 0, 0].to_vec();
+        vaa.extend_from_slice(&[0; 51]);
+        vaa.push(3);
+        vaa.extend_from_slice(&[0; 132]);
+        vaa.extend_from_slice(payload);
+        vaa
+    }
+
+    #[test]
+    fn parse_nested_payload() {
+        let vaa = transfer_with_message_vaa(&[7, 0, 0, 0, 0, 0, 0, 0x30, 0x39]);
+        let vaa = Vaa::parse(&vaa).unwrap();
+
+        let message = vaa.payload_as::<TokenBridgeMessage>().unwrap();
+        let swap = message
+            .transfer_with_message()
+            .unwrap()
+            .payload()
+            .parse_as::<Swap>()
+            .unwrap();
+        assert_eq!(swap.min_amount_out(), 12345);
+    }
+
+    #[test]
+    fn parse_nested_payload_error() {
+        let vaa = transfer_with_message_vaa(&[8, 0, 0, 0, 0, 0, 0, 0x30, 0x39]);
+        let vaa = Vaa::parse(&vaa).unwrap();
+
+        let message = vaa
+            .payload_as::<TokenBridgeMessage>()
+            .unwrap()
+            .to_transfer_with_message_unchecked();
+        assert_eq!(
+            message.payload().parse_as::<Swap>(),
+            Err(ParseError::UnknownDiscriminant {
+                kind: "Swap",
+                value: 8
+            })
+        );
+        assert_eq!(
+            message.payload().parse_as::<&[u8]>(),
+            Ok(&[8, 0, 0, 0, 0, 0, 0, 0x30, 0x39][..])
+        );
+    }
+}
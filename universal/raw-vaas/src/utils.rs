@@ -1,5 +1,128 @@
+use crate::ParseError;
+
 /// Return the number of guardians to reach quorum.
 #[inline]
 pub fn quorum(n: usize) -> usize {
     (n * 2) / 3 + 1
 }
+
+/// Half the order of the secp256k1 curve. Signatures with a larger `s` are malleable: `n - s`
+/// is also a valid signature for the same message.
+pub const SECP256K1_HALF_ORDER: [u8; 32] = [
+    0x7f, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0x5d, 0x57, 0x6e, 0x73, 0x57, 0xa4, 0x50, 0x1d, 0xdf, 0xe9, 0x2f, 0x46, 0x68, 0x1b, 0x20, 0xa0,
+];
+
+/// Check the version and signatures of a VAA header, given the guardian index and `s` of each
+/// signature. See [`Header::validate`](crate::Header::validate).
+pub fn validate_header(
+    version: u8,
+    signatures: impl IntoIterator<Item = (u8, [u8; 32])>,
+    guardian_set_len: usize,
+) -> Result<(), ParseError> {
+    if version != 1 {
+        return Err(ParseError::UnsupportedVersion { version });
+    }
+
+    let mut previous = None;
+    for (index, s) in signatures {
+        match previous {
+            Some(previous) if index == previous => {
+                return Err(ParseError::DuplicateSignature { index });
+            }
+            Some(previous) if index < previous => {
+                return Err(ParseError::UnsortedSignatures { previous, index });
+            }
+            _ => {}
+        }
+        if usize::from(index) >= guardian_set_len {
+            return Err(ParseError::GuardianIndexOutOfBounds {
+                index,
+                len: guardian_set_len,
+            });
+        }
+        // Big-endian, so comparing bytes compares the numbers.
+        if s > SECP256K1_HALF_ORDER {
+            return Err(ParseError::MalleableSignature { index });
+        }
+
+        previous = Some(index);
+    }
+
+    Ok(())
+}
+
+/// Simple keccak256 hash.
+#[inline]
+#[cfg(feature = "off-chain")]
+pub fn keccak256(buf: impl AsRef<[u8]>) -> [u8; 32] {
+    use sha3::{Digest, Keccak256};
+
+    Keccak256::digest(buf).into()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Header;
+    use std::vec::Vec;
+
+    fn header(version: u8, signatures: &[(u8, [u8; 32])]) -> Vec<u8> {
+        let mut header = [version, 0, 0, 0, 4, signatures.len() as u8].to_vec();
+        for (index, s) in signatures {
+            header.push(*index);
+            header.extend_from_slice(&[1; 32]);
+            header.extend_from_slice(s);
+            header.push(0);
+        }
+        header
+    }
+
+    fn validate(version: u8, signatures: &[(u8, [u8; 32])], len: usize) -> Result<(), ParseError> {
+        Header::parse(&header(version, signatures))
+            .unwrap()
+            .validate(len)
+    }
+
+    #[test]
+    fn validate_header() {
+        let low_s = [0x11; 32];
+        assert_eq!(
+            validate(1, &[(0, low_s), (2, low_s), (18, low_s)], 19),
+            Ok(())
+        );
+        assert_eq!(validate(1, &[(0, SECP256K1_HALF_ORDER)], 1), Ok(()));
+        assert_eq!(validate(1, &[], 19), Ok(()));
+
+        assert_eq!(
+            validate(2, &[(0, low_s)], 19),
+            Err(ParseError::UnsupportedVersion { version: 2 })
+        );
+        assert_eq!(
+            validate(1, &[(0, low_s), (3, low_s), (3, low_s)], 19),
+            Err(ParseError::DuplicateSignature { index: 3 })
+        );
+        assert_eq!(
+            validate(1, &[(4, low_s), (3, low_s)], 19),
+            Err(ParseError::UnsortedSignatures {
+                previous: 4,
+                index: 3
+            })
+        );
+        assert_eq!(
+            validate(1, &[(0, low_s), (19, low_s)], 19),
+            Err(ParseError::GuardianIndexOutOfBounds { index: 19, len: 19 })
+        );
+        assert_eq!(
+            validate(1, &[(0, low_s)], 0),
+            Err(ParseError::GuardianIndexOutOfBounds { index: 0, len: 0 })
+        );
+
+        let mut high_s = SECP256K1_HALF_ORDER;
+        high_s[31] += 1;
+        assert_eq!(
+            validate(1, &[(0, low_s), (1, high_s)], 19),
+            Err(ParseError::MalleableSignature { index: 1 })
+        );
+    }
+}
@@ -1,10 +1,19 @@
 pub use wormhole_io::{Readable, Writeable};
 
+pub use ruint::aliases::{U256, U64};
+
+mod primitives;
+pub use primitives::{Address, FixedBytes, B256};
+
+// available when `raw` feature is enabled
+#[cfg(feature = "raw")]
+pub use wormhole_raw_vaas::ParseError;
+
 pub mod utils;
 pub use utils::{keccak256, quorum};
 
 pub mod payloads;
-pub use payloads::{PayloadKind, TypePrefixedPayload};
+pub use payloads::{PayloadKind, TypePrefixedPayload, MAX_PAYLOAD_SIZE};
 
 mod protocol;
 pub use protocol::{
@@ -14,3 +23,6 @@ pub use protocol::{
 };
 
 mod support;
+// available when `raw` feature is enabled
+#[cfg(feature = "raw")]
+pub use support::raw::{Encoded, RawToOwned};
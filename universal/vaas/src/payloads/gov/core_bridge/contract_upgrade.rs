@@ -1,19 +1,21 @@
-use crate::{Readable, TypePrefixedPayload, Writeable};
-use alloy_primitives::FixedBytes;
+use crate::{FixedBytes, Readable, TypePrefixedPayload, Writeable};
+use wormhole_io::{
+    layout::{Field, LayoutItem},
+    Layout,
+};
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct ContractUpgrade {
     pub chain: u16,
     pub implementation: FixedBytes<32>,
 }
 
-impl TypePrefixedPayload for ContractUpgrade {
-    const TYPE: Option<u8> = Some(1);
+impl TypePrefixedPayload<1> for ContractUpgrade {
+    const TYPE: Option<[u8; 1]> = Some([1]);
 }
 
 impl Readable for ContractUpgrade {
-    const SIZE: Option<usize> = Some(2 + 32);
-
     fn read<R>(reader: &mut R) -> std::io::Result<Self>
     where
         Self: Sized,
@@ -36,6 +38,18 @@ impl Writeable for ContractUpgrade {
     }
 
     fn written_size(&self) -> usize {
-        <Self as Readable>::SIZE.unwrap()
+        2 + 32
+    }
+}
+
+impl Layout for ContractUpgrade {
+    fn layout() -> LayoutItem {
+        LayoutItem::structure(
+            "ContractUpgrade",
+            [
+                Field::new("chain", LayoutItem::uint(2)),
+                Field::new("implementation", LayoutItem::bytes(32)),
+            ],
+        )
     }
 }
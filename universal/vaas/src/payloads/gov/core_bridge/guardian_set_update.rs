@@ -1,27 +1,53 @@
-use crate::{Readable, TypePrefixedPayload, Writeable};
-use alloy_primitives::{Address, FixedBytes};
+use crate::{Address, Readable, TypePrefixedPayload, Writeable};
+use wormhole_io::{
+    layout::{Field, IntEncoding, LayoutItem},
+    Layout,
+};
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct GuardianSetUpdate {
-    _gap: FixedBytes<2>, // This should never be encoded with anything.
+    _gap: [u8; 2], // This should never be encoded with anything.
     pub new_index: u32,
     pub guardians: Vec<Address>,
 }
 
-impl TypePrefixedPayload for GuardianSetUpdate {
-    const TYPE: Option<u8> = Some(2);
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for GuardianSetUpdate {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Self {
+            _gap: [0; 2],
+            new_index: u.arbitrary()?,
+            // The guardian count is encoded as a u8.
+            guardians: crate::support::arbitrary::arbitrary_vec(u, u8::MAX.into())?,
+        })
+    }
 }
 
-impl Readable for GuardianSetUpdate {
-    const SIZE: Option<usize> = None;
+#[cfg(feature = "raw")]
+impl From<wormhole_raw_vaas::core::GuardianSetUpdate<'_>> for GuardianSetUpdate {
+    fn from(raw: wormhole_raw_vaas::core::GuardianSetUpdate<'_>) -> Self {
+        Self {
+            _gap: [0; 2],
+            new_index: raw.new_index(),
+            guardians: (0..usize::from(raw.num_guardians()))
+                .filter_map(|i| raw.guardian_at(i).map(Address::from))
+                .collect(),
+        }
+    }
+}
+
+impl TypePrefixedPayload<1> for GuardianSetUpdate {
+    const TYPE: Option<[u8; 1]> = Some([2]);
+}
 
+impl Readable for GuardianSetUpdate {
     fn read<R>(reader: &mut R) -> std::io::Result<Self>
     where
         Self: Sized,
         R: std::io::Read,
     {
-        let _gap = FixedBytes::<2>::read(reader)?;
-        if _gap != FixedBytes::ZERO {
+        let _gap = <[u8; 2]>::read(reader)?;
+        if _gap != [0; 2] {
             return Err(std::io::Error::new(
                 std::io::ErrorKind::InvalidData,
                 "Invalid guardian set update",
@@ -29,9 +55,9 @@ impl Readable for GuardianSetUpdate {
         }
         let new_index = u32::read(reader)?;
         let num_guardians = u8::read(reader)?;
-        let guardians: Vec<_> = (0..num_guardians)
-            .filter_map(|_| Address::read(reader).ok())
-            .collect();
+        let guardians = (0..num_guardians)
+            .map(|_| Readable::read(reader))
+            .collect::<Result<_, _>>()?;
 
         Ok(Self {
             _gap,
@@ -61,3 +87,34 @@ impl Writeable for GuardianSetUpdate {
         2 + 4 + 1 + self.guardians.len() * 20
     }
 }
+
+impl Layout for GuardianSetUpdate {
+    fn layout() -> LayoutItem {
+        LayoutItem::structure(
+            "GuardianSetUpdate",
+            [
+                Field::new("new_index", LayoutItem::uint(4)).padded(2),
+                Field::new(
+                    "guardians",
+                    LayoutItem::Vec {
+                        len: IntEncoding::big(1),
+                        item: Box::new(LayoutItem::bytes(20)),
+                    },
+                ),
+            ],
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn truncated_guardians() {
+        // Two guardians, but only one and a half encoded.
+        let mut encoded = vec![0, 0, 0, 0, 0, 1, 2];
+        encoded.extend_from_slice(&[0xaa; 30]);
+        assert!(GuardianSetUpdate::read(&mut encoded.as_slice()).is_err());
+    }
+}
@@ -1,5 +1,10 @@
 mod contract_upgrade;
+use crate::payloads::layout_variant;
 pub use contract_upgrade::ContractUpgrade;
+use wormhole_io::{
+    layout::{IntEncoding, LayoutItem},
+    Layout,
+};
 
 mod guardian_set_update;
 pub use guardian_set_update::GuardianSetUpdate;
@@ -13,8 +18,7 @@ pub use set_message_fee::SetMessageFee;
 mod transfer_fees;
 pub use transfer_fees::TransferFees;
 
-use crate::{Readable, TypePrefixedPayload, Writeable};
-use alloy_primitives::FixedBytes;
+use crate::{FixedBytes, Readable, TypePrefixedPayload, Writeable};
 use hex_literal::hex;
 
 /// A.K.A. "Core".
@@ -23,6 +27,7 @@ pub const GOVERNANCE_MODULE: FixedBytes<32> = FixedBytes(hex!(
 ));
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum Decree {
     ContractUpgrade(ContractUpgrade),
     GuardianSetUpdate(GuardianSetUpdate),
@@ -31,8 +36,8 @@ pub enum Decree {
     RecoverChainId(RecoverChainId),
 }
 
-impl TypePrefixedPayload for Decree {
-    const TYPE: Option<u8> = None;
+impl TypePrefixedPayload<1> for Decree {
+    const TYPE: Option<[u8; 1]> = None;
 }
 
 impl Writeable for Decree {
@@ -61,26 +66,24 @@ impl Writeable for Decree {
 }
 
 impl Readable for Decree {
-    const SIZE: Option<usize> = None;
-
     fn read<R>(reader: &mut R) -> std::io::Result<Self>
     where
         R: std::io::Read,
     {
-        let decree = match Some(u8::read(reader)?) {
-            <ContractUpgrade as TypePrefixedPayload>::TYPE => {
+        let decree = match Some(<[u8; 1]>::read(reader)?) {
+            <ContractUpgrade as TypePrefixedPayload<1>>::TYPE => {
                 Decree::ContractUpgrade(Readable::read(reader)?)
             }
-            <GuardianSetUpdate as TypePrefixedPayload>::TYPE => {
+            <GuardianSetUpdate as TypePrefixedPayload<1>>::TYPE => {
                 Decree::GuardianSetUpdate(Readable::read(reader)?)
             }
-            <SetMessageFee as TypePrefixedPayload>::TYPE => {
+            <SetMessageFee as TypePrefixedPayload<1>>::TYPE => {
                 Decree::SetMessageFee(Readable::read(reader)?)
             }
-            <TransferFees as TypePrefixedPayload>::TYPE => {
+            <TransferFees as TypePrefixedPayload<1>>::TYPE => {
                 Decree::TransferFees(Readable::read(reader)?)
             }
-            <RecoverChainId as TypePrefixedPayload>::TYPE => {
+            <RecoverChainId as TypePrefixedPayload<1>>::TYPE => {
                 Decree::RecoverChainId(Readable::read(reader)?)
             }
             _ => {
@@ -94,3 +97,19 @@ impl Readable for Decree {
         Ok(decree)
     }
 }
+
+impl Layout for Decree {
+    fn layout() -> LayoutItem {
+        LayoutItem::enumeration(
+            "Decree",
+            IntEncoding::big(1),
+            [
+                layout_variant::<ContractUpgrade>("ContractUpgrade"),
+                layout_variant::<GuardianSetUpdate>("GuardianSetUpdate"),
+                layout_variant::<SetMessageFee>("SetMessageFee"),
+                layout_variant::<TransferFees>("TransferFees"),
+                layout_variant::<RecoverChainId>("RecoverChainId"),
+            ],
+        )
+    }
+}
@@ -1,20 +1,24 @@
+use crate::U256;
 use crate::{Readable, TypePrefixedPayload, Writeable};
-use alloy_primitives::U256;
+use wormhole_io::{
+    layout::{Field, LayoutItem},
+    Layout,
+};
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct RecoverChainId {
     pub recovered_chain: u16,
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = crate::support::arbitrary::arbitrary_u256))]
     pub evm_chain_id: U256,
     pub new_chain: u16,
 }
 
-impl TypePrefixedPayload for RecoverChainId {
-    const TYPE: Option<u8> = Some(5);
+impl TypePrefixedPayload<1> for RecoverChainId {
+    const TYPE: Option<[u8; 1]> = Some([5]);
 }
 
 impl Readable for RecoverChainId {
-    const SIZE: Option<usize> = Some(2 + 32 + 2);
-
     fn read<R>(reader: &mut R) -> std::io::Result<Self>
     where
         Self: Sized,
@@ -39,6 +43,19 @@ impl Writeable for RecoverChainId {
     }
 
     fn written_size(&self) -> usize {
-        <Self as Readable>::SIZE.unwrap()
+        2 + 32 + 2
+    }
+}
+
+impl Layout for RecoverChainId {
+    fn layout() -> LayoutItem {
+        LayoutItem::structure(
+            "RecoverChainId",
+            [
+                Field::new("recovered_chain", LayoutItem::uint(2)),
+                Field::new("evm_chain_id", LayoutItem::uint(32)),
+                Field::new("new_chain", LayoutItem::uint(2)),
+            ],
+        )
     }
 }
@@ -1,19 +1,23 @@
+use crate::U256;
 use crate::{Readable, TypePrefixedPayload, Writeable};
-use alloy_primitives::U256;
+use wormhole_io::{
+    layout::{Field, LayoutItem},
+    Layout,
+};
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct SetMessageFee {
     pub chain: u16,
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = crate::support::arbitrary::arbitrary_u256))]
     pub fee: U256,
 }
 
-impl TypePrefixedPayload for SetMessageFee {
-    const TYPE: Option<u8> = Some(3);
+impl TypePrefixedPayload<1> for SetMessageFee {
+    const TYPE: Option<[u8; 1]> = Some([3]);
 }
 
 impl Readable for SetMessageFee {
-    const SIZE: Option<usize> = Some(2 + 32);
-
     fn read<R>(reader: &mut R) -> std::io::Result<Self>
     where
         Self: Sized,
@@ -36,6 +40,18 @@ impl Writeable for SetMessageFee {
     }
 
     fn written_size(&self) -> usize {
-        <Self as Readable>::SIZE.unwrap()
+        2 + 32
+    }
+}
+
+impl Layout for SetMessageFee {
+    fn layout() -> LayoutItem {
+        LayoutItem::structure(
+            "SetMessageFee",
+            [
+                Field::new("chain", LayoutItem::uint(2)),
+                Field::new("fee", LayoutItem::uint(32)),
+            ],
+        )
     }
 }
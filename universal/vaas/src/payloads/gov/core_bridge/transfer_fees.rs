@@ -1,20 +1,24 @@
-use crate::{Readable, TypePrefixedPayload, Writeable};
-use alloy_primitives::{FixedBytes, U256};
+use crate::U256;
+use crate::{FixedBytes, Readable, TypePrefixedPayload, Writeable};
+use wormhole_io::{
+    layout::{Field, LayoutItem},
+    Layout,
+};
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct TransferFees {
     pub chain: u16,
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = crate::support::arbitrary::arbitrary_u256))]
     pub amount: U256,
     pub recipient: FixedBytes<32>,
 }
 
-impl TypePrefixedPayload for TransferFees {
-    const TYPE: Option<u8> = Some(4);
+impl TypePrefixedPayload<1> for TransferFees {
+    const TYPE: Option<[u8; 1]> = Some([4]);
 }
 
 impl Readable for TransferFees {
-    const SIZE: Option<usize> = Some(2 + 32 + 32);
-
     fn read<R>(reader: &mut R) -> std::io::Result<Self>
     where
         Self: Sized,
@@ -39,6 +43,19 @@ impl Writeable for TransferFees {
     }
 
     fn written_size(&self) -> usize {
-        <Self as Readable>::SIZE.unwrap()
+        2 + 32 + 32
+    }
+}
+
+impl Layout for TransferFees {
+    fn layout() -> LayoutItem {
+        LayoutItem::structure(
+            "TransferFees",
+            [
+                Field::new("chain", LayoutItem::uint(2)),
+                Field::new("amount", LayoutItem::uint(32)),
+                Field::new("recipient", LayoutItem::bytes(32)),
+            ],
+        )
     }
 }
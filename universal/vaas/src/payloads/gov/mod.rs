@@ -2,14 +2,17 @@ pub mod core_bridge;
 pub use core_bridge::{
     ContractUpgrade, GuardianSetUpdate, RecoverChainId, SetMessageFee, TransferFees,
 };
+use wormhole_io::{
+    layout::{payload_layout, Field, LayoutItem},
+    Layout,
+};
 
 pub mod token_bridge;
 pub use token_bridge::RegisterChain;
 
-use alloy_primitives::FixedBytes;
 use hex_literal::hex;
 
-use crate::{Readable, TypePrefixedPayload, Writeable};
+use crate::{FixedBytes, Readable, TypePrefixedPayload, Writeable};
 
 pub const GOVERNANCE_CHAIN: u16 = 1;
 pub const GOVERNANCE_EMITTER: FixedBytes<32> = FixedBytes(hex!(
@@ -31,19 +34,18 @@ pub const GOVERNANCE_EMITTER: FixedBytes<32> = FixedBytes(hex!(
 ///
 /// [specification]: https://docs.wormhole.com/wormhole/explore-wormhole/vaa#governance
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct GovernanceHeader {
     pub module: FixedBytes<32>,
 }
 
 impl Readable for GovernanceHeader {
-    const SIZE: Option<usize> = Some(32);
-
     fn read<R>(reader: &mut R) -> std::io::Result<Self>
     where
         R: std::io::Read,
     {
         Ok(Self {
-            module: FixedBytes::read(reader)?,
+            module: Readable::read(reader)?,
         })
     }
 }
@@ -57,21 +59,22 @@ impl Writeable for GovernanceHeader {
     }
 
     fn written_size(&self) -> usize {
-        <Self as Readable>::SIZE.unwrap()
+        32
     }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct GovernanceMessage<P> {
     pub header: GovernanceHeader,
     pub decree: P,
 }
 
-impl<P: TypePrefixedPayload> TypePrefixedPayload for GovernanceMessage<P> {
-    const TYPE: Option<u8> = None;
+impl<P: TypePrefixedPayload<1>> TypePrefixedPayload<1> for GovernanceMessage<P> {
+    const TYPE: Option<[u8; 1]> = None;
 }
 
-impl<P: TypePrefixedPayload> Writeable for GovernanceMessage<P> {
+impl<P: TypePrefixedPayload<1>> Writeable for GovernanceMessage<P> {
     fn write<W>(&self, writer: &mut W) -> std::io::Result<()>
     where
         W: std::io::Write,
@@ -85,9 +88,7 @@ impl<P: TypePrefixedPayload> Writeable for GovernanceMessage<P> {
     }
 }
 
-impl<P: TypePrefixedPayload> Readable for GovernanceMessage<P> {
-    const SIZE: Option<usize> = None;
-
+impl<P: TypePrefixedPayload<1>> Readable for GovernanceMessage<P> {
     fn read<R>(reader: &mut R) -> std::io::Result<Self>
     where
         Self: Sized,
@@ -99,3 +100,24 @@ impl<P: TypePrefixedPayload> Readable for GovernanceMessage<P> {
         })
     }
 }
+
+impl Layout for GovernanceHeader {
+    fn layout() -> LayoutItem {
+        LayoutItem::structure(
+            "GovernanceHeader",
+            [Field::new("module", LayoutItem::bytes(32))],
+        )
+    }
+}
+
+impl<P: TypePrefixedPayload<1> + Layout> Layout for GovernanceMessage<P> {
+    fn layout() -> LayoutItem {
+        LayoutItem::structure(
+            "GovernanceMessage",
+            [
+                Field::new("header", GovernanceHeader::layout()),
+                Field::new("decree", payload_layout::<P, 1>()),
+            ],
+        )
+    }
+}
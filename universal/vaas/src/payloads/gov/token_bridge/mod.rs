@@ -1,5 +1,10 @@
 mod register_chain;
+use crate::payloads::layout_variant;
 pub use register_chain::RegisterChain;
+use wormhole_io::{
+    layout::{IntEncoding, LayoutItem},
+    Layout,
+};
 
 mod recover_chain_id;
 pub use recover_chain_id::RecoverChainId;
@@ -7,8 +12,7 @@ pub use recover_chain_id::RecoverChainId;
 mod contract_upgrade;
 pub use contract_upgrade::ContractUpgrade;
 
-use crate::{Readable, TypePrefixedPayload, Writeable};
-use alloy_primitives::FixedBytes;
+use crate::{FixedBytes, Readable, TypePrefixedPayload, Writeable};
 use hex_literal::hex;
 
 /// A.K.A. "TokenBridge".
@@ -17,14 +21,15 @@ pub const GOVERNANCE_MODULE: FixedBytes<32> = FixedBytes(hex!(
 ));
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum Decree {
     RegisterChain(RegisterChain),
     ContractUpgrade(ContractUpgrade),
     RecoverChainId(RecoverChainId),
 }
 
-impl TypePrefixedPayload for Decree {
-    const TYPE: Option<u8> = None;
+impl TypePrefixedPayload<1> for Decree {
+    const TYPE: Option<[u8; 1]> = None;
 }
 
 impl Writeable for Decree {
@@ -49,20 +54,18 @@ impl Writeable for Decree {
 }
 
 impl Readable for Decree {
-    const SIZE: Option<usize> = None;
-
     fn read<R>(reader: &mut R) -> std::io::Result<Self>
     where
         R: std::io::Read,
     {
-        let decree = match Some(u8::read(reader)?) {
-            <RegisterChain as TypePrefixedPayload>::TYPE => {
+        let decree = match Some(<[u8; 1]>::read(reader)?) {
+            <RegisterChain as TypePrefixedPayload<1>>::TYPE => {
                 Decree::RegisterChain(Readable::read(reader)?)
             }
-            <ContractUpgrade as TypePrefixedPayload>::TYPE => {
+            <ContractUpgrade as TypePrefixedPayload<1>>::TYPE => {
                 Decree::ContractUpgrade(Readable::read(reader)?)
             }
-            <RecoverChainId as TypePrefixedPayload>::TYPE => {
+            <RecoverChainId as TypePrefixedPayload<1>>::TYPE => {
                 Decree::RecoverChainId(Readable::read(reader)?)
             }
             _ => {
@@ -76,3 +79,17 @@ impl Readable for Decree {
         Ok(decree)
     }
 }
+
+impl Layout for Decree {
+    fn layout() -> LayoutItem {
+        LayoutItem::enumeration(
+            "Decree",
+            IntEncoding::big(1),
+            [
+                layout_variant::<RegisterChain>("RegisterChain"),
+                layout_variant::<ContractUpgrade>("ContractUpgrade"),
+                layout_variant::<RecoverChainId>("RecoverChainId"),
+            ],
+        )
+    }
+}
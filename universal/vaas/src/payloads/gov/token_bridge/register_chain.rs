@@ -1,5 +1,8 @@
-use crate::{Readable, TypePrefixedPayload, Writeable};
-use alloy_primitives::FixedBytes;
+use crate::{FixedBytes, Readable, TypePrefixedPayload, Writeable};
+use wormhole_io::{
+    layout::{Field, LayoutItem},
+    Layout,
+};
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct RegisterChain {
@@ -9,25 +12,45 @@ pub struct RegisterChain {
     /// empty.
     ///
     /// [`Governanceheader`]: crate::payloads::gov::GovernanceHeader
-    _gap: FixedBytes<2>,
+    _gap: [u8; 2],
     pub foreign_chain: u16,
     pub foreign_emitter: FixedBytes<32>,
 }
 
-impl TypePrefixedPayload for RegisterChain {
-    const TYPE: Option<u8> = Some(1);
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for RegisterChain {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Self {
+            _gap: [0; 2],
+            foreign_chain: u.arbitrary()?,
+            foreign_emitter: u.arbitrary()?,
+        })
+    }
 }
 
-impl Readable for RegisterChain {
-    const SIZE: Option<usize> = Some(2 + 2 + 32);
+#[cfg(feature = "raw")]
+impl From<wormhole_raw_vaas::token_bridge::RegisterChain<'_>> for RegisterChain {
+    fn from(raw: wormhole_raw_vaas::token_bridge::RegisterChain<'_>) -> Self {
+        Self {
+            _gap: [0; 2],
+            foreign_chain: raw.foreign_chain(),
+            foreign_emitter: raw.foreign_emitter().into(),
+        }
+    }
+}
 
+impl TypePrefixedPayload<1> for RegisterChain {
+    const TYPE: Option<[u8; 1]> = Some([1]);
+}
+
+impl Readable for RegisterChain {
     fn read<R>(reader: &mut R) -> std::io::Result<Self>
     where
         Self: Sized,
         R: std::io::Read,
     {
-        let _gap = FixedBytes::<2>::read(reader)?;
-        if _gap != FixedBytes::ZERO {
+        let _gap = <[u8; 2]>::read(reader)?;
+        if _gap != [0; 2] {
             return Err(std::io::Error::new(
                 std::io::ErrorKind::InvalidData,
                 "Invalid register chain",
@@ -53,6 +76,18 @@ impl Writeable for RegisterChain {
     }
 
     fn written_size(&self) -> usize {
-        <Self as Readable>::SIZE.unwrap()
+        2 + 2 + 32
+    }
+}
+
+impl Layout for RegisterChain {
+    fn layout() -> LayoutItem {
+        LayoutItem::structure(
+            "RegisterChain",
+            [
+                Field::new("foreign_chain", LayoutItem::uint(2)).padded(2),
+                Field::new("foreign_emitter", LayoutItem::bytes(32)),
+            ],
+        )
     }
 }
@@ -1,9 +1,13 @@
 use crate::{Readable, Writeable};
+use wormhole_io::{
+    layout::{Field, IntEncoding, LayoutItem},
+    Layout,
+};
 
 use std::io;
 
-impl crate::payloads::TypePrefixedPayload for Message {
-    const TYPE: Option<u8> = Some(0xbb);
+impl crate::payloads::TypePrefixedPayload<1> for Message {
+    const TYPE: Option<[u8; 1]> = Some([0xbb]);
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -18,8 +22,6 @@ pub struct Message {
 }
 
 impl Readable for Message {
-    const SIZE: Option<usize> = None;
-
     fn read<R>(reader: &mut R) -> io::Result<Self>
     where
         R: io::Read,
@@ -72,7 +74,30 @@ impl Writeable for Message {
     }
 
     fn written_size(&self) -> usize {
-        1 + 8 + 2 + 2 + self.sender.len() + 2 + self.target.len() + 2 + self.body.len()
+        1 + 1 + 8 + 2 + 2 + self.target.len() + 2 + self.sender.len() + 2 + self.body.len()
+    }
+}
+
+impl Layout for Message {
+    fn layout() -> LayoutItem {
+        LayoutItem::structure(
+            "Message",
+            [
+                Field::new("version", LayoutItem::uint(1)),
+                Field::new("message_ty", LayoutItem::uint(1)),
+                Field::new("index", LayoutItem::uint(8)),
+                Field::new("target_chain", LayoutItem::uint(2)),
+                Field::new("target", u16_prefixed()),
+                Field::new("sender", u16_prefixed()),
+                Field::new("body", u16_prefixed()),
+            ],
+        )
+    }
+}
+
+fn u16_prefixed() -> LayoutItem {
+    LayoutItem::LengthPrefixedBytes {
+        len: IntEncoding::big(2),
     }
 }
 
@@ -98,5 +123,6 @@ mod test {
             hex!("7fa9385be102ac3eac297483dd6233d62b3e1496")
         );
         assert_eq!(message.body, hex!("9abc"));
+        assert_eq!(message.written_size(), 42);
     }
 }
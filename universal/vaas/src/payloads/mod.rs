@@ -4,64 +4,15 @@ use std::io;
 pub use message::Message;
 
 use crate::{Readable, Writeable};
+pub use wormhole_io::TypePrefixedPayload;
+use wormhole_io::{
+    layout::{Field, LayoutItem, Variant},
+    BoundedReader, Layout,
+};
 
 pub mod gov;
 pub mod token_bridge;
 
-/// Trait to capture common payload behavior. We do not recommend overwriting
-/// any trait methods. Simply set the type constant and implement [`Readable`]
-/// and [`Writeable`].
-pub trait TypePrefixedPayload:
-    crate::Readable + crate::Writeable + Clone + std::fmt::Debug
-{
-    const TYPE: Option<u8>;
-
-    /// Read the payload, including the type prefix.
-    fn read_typed<R: io::Read>(reader: &mut R) -> Result<Self, io::Error> {
-        let payload_type = u8::read(reader)?;
-        if payload_type == Self::TYPE.expect("Called write_typed on untyped payload") {
-            Self::read(reader)
-        } else {
-            Err(io::Error::new(
-                io::ErrorKind::InvalidData,
-                "Invalid payload type",
-            ))
-        }
-    }
-
-    /// Write the payload, including the type prefix.
-    fn write_typed<W: io::Write>(&self, writer: &mut W) -> Result<(), io::Error> {
-        Self::TYPE
-            .expect("Called write_typed on untyped payload")
-            .write(writer)?;
-        Writeable::write(self, writer)
-    }
-
-    /// Read the payload, including the type prefix if applicable.
-    fn read_payload<R: io::Read>(reader: &mut R) -> Result<Self, io::Error> {
-        match Self::TYPE {
-            Some(_) => Self::read_typed(reader),
-            None => Readable::read(reader),
-        }
-    }
-
-    /// Write the payload, including the type prefix if applicable.
-    fn write_payload<W: io::Write>(&self, writer: &mut W) -> Result<(), io::Error> {
-        match Self::TYPE {
-            Some(_) => self.write_typed(writer),
-            None => Writeable::write(self, writer),
-        }
-    }
-
-    /// Returns the size of the payload, including the type prefix.
-    fn payload_written_size(&self) -> usize {
-        match Self::TYPE {
-            Some(_) => self.written_size() + 1,
-            None => self.written_size(),
-        }
-    }
-}
-
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(
     feature = "serde",
@@ -75,16 +26,20 @@ pub enum PayloadKind {
     Json(serde_json::Value),
 }
 
-impl Readable for PayloadKind {
-    const SIZE: Option<usize> = None;
+/// Largest payload [`PayloadKind`] reads. Anything longer is rejected rather than buffered.
+pub const MAX_PAYLOAD_SIZE: usize = 1 << 20;
 
+/// Reads the rest of the input, up to [`MAX_PAYLOAD_SIZE`] bytes.
+impl Readable for PayloadKind {
     fn read<R>(reader: &mut R) -> io::Result<Self>
     where
         Self: Sized,
         R: io::Read,
     {
+        let mut reader = BoundedReader::new(reader, MAX_PAYLOAD_SIZE);
         let mut buf = vec![];
-        reader.read_to_end(&mut buf)?;
+        io::Read::read_to_end(&mut reader, &mut buf)?;
+        reader.check_end()?;
         Ok(Self::Binary(buf))
     }
 }
@@ -113,6 +68,32 @@ impl Writeable for PayloadKind {
     }
 }
 
-impl TypePrefixedPayload for PayloadKind {
-    const TYPE: Option<u8> = None;
+impl TypePrefixedPayload<1> for PayloadKind {
+    const TYPE: Option<[u8; 1]> = None;
+}
+
+/// Variant of a payload enum, whose discriminant is the type prefix of `P`.
+pub(crate) fn layout_variant<P: TypePrefixedPayload<1> + Layout>(name: &'static str) -> Variant {
+    let [discriminant] = P::TYPE.expect("payload enum variants must have a type prefix");
+    let fields = match P::layout() {
+        LayoutItem::Struct { fields, .. } => fields,
+        layout => vec![Field::new("0", layout)],
+    };
+    Variant::new(name, discriminant.into(), fields)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn payload_size_limit() {
+        let payload = vec![0xaa; MAX_PAYLOAD_SIZE];
+        let read = PayloadKind::read(&mut payload.as_slice()).unwrap();
+        assert_eq!(read, PayloadKind::Binary(payload));
+
+        let payload = vec![0xaa; MAX_PAYLOAD_SIZE + 1];
+        let err = PayloadKind::read(&mut payload.as_slice()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
 }
@@ -1,10 +1,14 @@
-use alloy_primitives::FixedBytes;
+use wormhole_io::{
+    layout::{Field, LayoutItem},
+    Layout,
+};
 
-use crate::{Readable, TypePrefixedPayload, Writeable};
+use crate::{FixedBytes, Readable, TypePrefixedPayload, Writeable};
 
 use std::io;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct Attestation {
     pub token_address: FixedBytes<32>,
     pub token_chain: u16,
@@ -24,13 +28,11 @@ impl Attestation {
     }
 }
 
-impl TypePrefixedPayload for Attestation {
-    const TYPE: Option<u8> = Some(2);
+impl TypePrefixedPayload<1> for Attestation {
+    const TYPE: Option<[u8; 1]> = Some([2]);
 }
 
 impl Readable for Attestation {
-    const SIZE: Option<usize> = Some(32 + 2 + 1 + 32 + 32);
-
     fn read<R>(reader: &mut R) -> io::Result<Self>
     where
         Self: Sized,
@@ -48,7 +50,7 @@ impl Readable for Attestation {
 
 impl Writeable for Attestation {
     fn written_size(&self) -> usize {
-        <Self as Readable>::SIZE.unwrap()
+        32 + 2 + 1 + 32 + 32
     }
 
     fn write<W>(&self, writer: &mut W) -> io::Result<()>
@@ -74,20 +76,34 @@ fn fixed32_to_string(fixed: FixedBytes<32>) -> String {
     String::from_utf8_lossy(&fixed[..idx]).into_owned()
 }
 
+impl Layout for Attestation {
+    fn layout() -> LayoutItem {
+        LayoutItem::structure(
+            "Attestation",
+            [
+                Field::new("token_address", LayoutItem::bytes(32)),
+                Field::new("token_chain", LayoutItem::uint(2)),
+                Field::new("decimals", LayoutItem::uint(1)),
+                Field::new("symbol", LayoutItem::bytes(32)),
+                Field::new("name", LayoutItem::bytes(32)),
+            ],
+        )
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use alloy_primitives::{FixedBytes, U64};
     use hex_literal::hex;
 
     use crate::{
         payloads::token_bridge::{attestation::fixed32_to_string, TokenBridgeMessage},
-        Readable, Vaa, Writeable,
+        FixedBytes, Readable, TypePrefixedPayload, Vaa, U64,
     };
 
     #[test]
     fn unicode_truncation_empty() {
-        let converted = FixedBytes::<32>::ZERO;
-        let recovered = fixed32_to_string(converted);
+        let converted = [0; 32];
+        let recovered = fixed32_to_string(converted.into());
         assert_eq!(recovered, String::new());
     }
 
@@ -97,9 +113,9 @@ mod test {
         let converted = {
             let mut out = [0; 32];
             out[..input.len()].copy_from_slice(input.as_bytes());
-            FixedBytes::<32>::from(out)
+            out
         };
-        let recovered = fixed32_to_string(converted);
+        let recovered = fixed32_to_string(converted.into());
         assert_eq!(recovered, String::from("🔥"));
     }
 
@@ -109,9 +125,9 @@ mod test {
         let converted = {
             let mut out = [0; 32];
             out.copy_from_slice(input.as_bytes());
-            FixedBytes::<32>::from(out)
+            out
         };
-        let recovered = fixed32_to_string(converted);
+        let recovered = fixed32_to_string(converted.into());
         assert_eq!(recovered, String::from("🔥🔥🔥🔥🔥🔥🔥🔥"));
     }
 
@@ -121,9 +137,9 @@ mod test {
         let converted = {
             let mut out = [0; 32];
             out.copy_from_slice(&input.as_bytes()[..32]);
-            FixedBytes::<32>::from(out)
+            out
         };
-        let recovered = fixed32_to_string(converted);
+        let recovered = fixed32_to_string(converted.into());
         assert_eq!(recovered, String::from("🔥🔥🔥🔥🔥🔥🔥🔥"));
     }
 
@@ -133,9 +149,9 @@ mod test {
         let converted = {
             let mut out = [0; 32];
             out.copy_from_slice(&input.as_bytes()[..32]);
-            FixedBytes::<32>::from(out)
+            out
         };
-        let recovered = fixed32_to_string(converted);
+        let recovered = fixed32_to_string(converted.into());
         assert_eq!(recovered, String::from("0000000000000000000000000000000�"));
     }
 
@@ -152,18 +168,22 @@ mod test {
         assert_eq!(vaa.body.emitter_chain, 1);
         assert_eq!(
             vaa.body.emitter_address,
-            hex!("95f83a27e90c622a98c037353f271fd8f5f57b4dc18ebf5ff75a934724bd0491")
+            FixedBytes(hex!(
+                "95f83a27e90c622a98c037353f271fd8f5f57b4dc18ebf5ff75a934724bd0491"
+            ))
         );
         assert_eq!(vaa.body.sequence, U64::from(11833801757748136510u64));
         assert_eq!(vaa.body.consistency_level, 32);
 
-        let msg = vaa.body.read_payload::<TokenBridgeMessage>().unwrap();
+        let msg = vaa.body.read_payload::<TokenBridgeMessage, 1>().unwrap();
         assert_eq!(msg.to_vec(), vaa.body.payload_bytes().unwrap());
 
         if let TokenBridgeMessage::Attestation(attestation) = msg {
             assert_eq!(
                 attestation.token_address,
-                hex!("000000000000000000000000c02aaa39b223fe8d0a0e5c4f27ead9083c756cc2")
+                FixedBytes(hex!(
+                    "000000000000000000000000c02aaa39b223fe8d0a0e5c4f27ead9083c756cc2"
+                ))
             );
             assert_eq!(attestation.token_chain, 2);
             assert_eq!(attestation.decimals, 18);
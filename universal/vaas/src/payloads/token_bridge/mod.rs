@@ -1,5 +1,10 @@
 mod attestation;
+use crate::payloads::layout_variant;
 pub use attestation::Attestation;
+use wormhole_io::{
+    layout::{IntEncoding, LayoutItem},
+    Layout,
+};
 
 mod transfer;
 pub use transfer::Transfer;
@@ -12,6 +17,7 @@ use crate::payloads::{Readable, TypePrefixedPayload, Writeable};
 // TODO: make normalizer struct for norm amount/relayer_fee.
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum TokenBridgeMessage {
     Transfer(Transfer),
     Attestation(Attestation),
@@ -19,21 +25,19 @@ pub enum TokenBridgeMessage {
 }
 
 impl Readable for TokenBridgeMessage {
-    const SIZE: Option<usize> = None;
-
     fn read<R>(reader: &mut R) -> std::io::Result<Self>
     where
         Self: Sized,
         R: std::io::Read,
     {
-        match Some(u8::read(reader)?) {
-            <Transfer as TypePrefixedPayload>::TYPE => {
+        match Some(<[u8; 1]>::read(reader)?) {
+            <Transfer as TypePrefixedPayload<1>>::TYPE => {
                 Ok(TokenBridgeMessage::Transfer(Readable::read(reader)?))
             }
-            <Attestation as TypePrefixedPayload>::TYPE => {
+            <Attestation as TypePrefixedPayload<1>>::TYPE => {
                 Ok(TokenBridgeMessage::Attestation(Readable::read(reader)?))
             }
-            <TransferWithMessage as TypePrefixedPayload>::TYPE => Ok(
+            <TransferWithMessage as TypePrefixedPayload<1>>::TYPE => Ok(
                 TokenBridgeMessage::TransferWithMessage(Readable::read(reader)?),
             ),
             _ => Err(std::io::Error::new(
@@ -65,6 +69,20 @@ impl Writeable for TokenBridgeMessage {
     }
 }
 
-impl TypePrefixedPayload for TokenBridgeMessage {
-    const TYPE: Option<u8> = None;
+impl TypePrefixedPayload<1> for TokenBridgeMessage {
+    const TYPE: Option<[u8; 1]> = None;
+}
+
+impl Layout for TokenBridgeMessage {
+    fn layout() -> LayoutItem {
+        LayoutItem::enumeration(
+            "TokenBridgeMessage",
+            IntEncoding::big(1),
+            [
+                layout_variant::<Transfer>("Transfer"),
+                layout_variant::<Attestation>("Attestation"),
+                layout_variant::<TransferWithMessage>("TransferWithMessage"),
+            ],
+        )
+    }
 }
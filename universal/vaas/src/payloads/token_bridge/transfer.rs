@@ -1,10 +1,14 @@
-use alloy_primitives::FixedBytes;
+use wormhole_io::{
+    layout::{Field, LayoutItem},
+    Layout,
+};
 
-use crate::{EncodedAmount, Readable, TypePrefixedPayload, Writeable};
+use crate::{EncodedAmount, FixedBytes, Readable, TypePrefixedPayload, Writeable};
 
 use std::io;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct Transfer {
     pub norm_amount: EncodedAmount,
     pub token_address: FixedBytes<32>,
@@ -14,12 +18,11 @@ pub struct Transfer {
     pub norm_relayer_fee: EncodedAmount,
 }
 
-impl TypePrefixedPayload for Transfer {
-    const TYPE: Option<u8> = Some(1);
+impl TypePrefixedPayload<1> for Transfer {
+    const TYPE: Option<[u8; 1]> = Some([1]);
 }
 
 impl Readable for Transfer {
-    const SIZE: Option<usize> = Some(32 + 32 + 2 + 32 + 2 + 32);
     fn read<R>(reader: &mut R) -> io::Result<Self>
     where
         Self: Sized,
@@ -52,15 +55,30 @@ impl Writeable for Transfer {
     }
 
     fn written_size(&self) -> usize {
-        <Self as Readable>::SIZE.unwrap()
+        32 + 32 + 2 + 32 + 2 + 32
+    }
+}
+
+impl Layout for Transfer {
+    fn layout() -> LayoutItem {
+        LayoutItem::structure(
+            "Transfer",
+            [
+                Field::new("norm_amount", EncodedAmount::layout()),
+                Field::new("token_address", LayoutItem::bytes(32)),
+                Field::new("token_chain", LayoutItem::uint(2)),
+                Field::new("recipient", LayoutItem::bytes(32)),
+                Field::new("recipient_chain", LayoutItem::uint(2)),
+                Field::new("norm_relayer_fee", EncodedAmount::layout()),
+            ],
+        )
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{payloads::token_bridge::TokenBridgeMessage, Vaa};
-    use alloy_primitives::U64;
+    use crate::{payloads::token_bridge::TokenBridgeMessage, Vaa, U64};
     use hex_literal::hex;
 
     // https://github.com/wormhole-foundation/wormhole/blob/b09a644dac97fa8e037a16765728217ff3a1d057/clients/js/parse_tests/token-bridge-transfer-1.expected
@@ -74,23 +92,27 @@ mod tests {
         assert_eq!(vaa.header.guardian_set_index, 0);
         assert_eq!(vaa.header.signatures.len(), 1);
         assert_eq!(vaa.header.signatures[0].guardian_set_index, 0);
-        assert_eq!(vaa.header.signatures[0].signature, hex!("7d204ad9447c4dfd6be62406e7f5a05eec96300da4048e70ff530cfb52aec44807e98194990710ff166eb1b2eac942d38bc1cd6018f93662a6578d985e87c8d001"));
+        assert_eq!(vaa.header.signatures[0].signature, FixedBytes(hex!("7d204ad9447c4dfd6be62406e7f5a05eec96300da4048e70ff530cfb52aec44807e98194990710ff166eb1b2eac942d38bc1cd6018f93662a6578d985e87c8d001")));
 
         assert_eq!(vaa.body.timestamp, 1646343275);
         assert_eq!(vaa.body.nonce, 47293);
         assert_eq!(vaa.body.emitter_chain, 1);
         assert_eq!(
             vaa.body.emitter_address,
-            hex!("c69a1b1a65dd336bf1df6a77afb501fc25db7fc0938cb08595a9ef473265cb4f")
+            FixedBytes(hex!(
+                "c69a1b1a65dd336bf1df6a77afb501fc25db7fc0938cb08595a9ef473265cb4f"
+            ))
         );
         assert_eq!(vaa.body.sequence, U64::from(3));
         assert_eq!(vaa.body.consistency_level, 32);
         assert_eq!(
             vaa.body.double_digest(),
-            hex!("2862e5873955ea104bb3e122831bdc43bbcb413da5b1123514640b950d038967")
+            FixedBytes(hex!(
+                "2862e5873955ea104bb3e122831bdc43bbcb413da5b1123514640b950d038967"
+            ))
         );
 
-        let msg = vaa.body.read_payload::<TokenBridgeMessage>().unwrap();
+        let msg = vaa.body.read_payload::<TokenBridgeMessage, 1>().unwrap();
 
         assert_eq!(msg.to_vec(), vaa.body.payload_bytes().unwrap());
 
@@ -99,15 +121,13 @@ mod tests {
                 transfer,
                 &Transfer {
                     norm_amount: EncodedAmount::from(10000000000u64),
-                    token_address: hex!(
+                    token_address: FixedBytes(hex!(
                         "165809739240a0ac03b98440fe8985548e3aa683cd0d4d9df5b5659669faa301"
-                    )
-                    .into(),
+                    )),
                     token_chain: 1,
-                    recipient: hex!(
+                    recipient: FixedBytes(hex!(
                         "000000000000000000000000c10820983f33456ce7beb3a046f5a83fa34f027d"
-                    )
-                    .into(),
+                    )),
                     recipient_chain: 2,
                     norm_relayer_fee: EncodedAmount::ZERO,
                 }
@@ -138,28 +158,36 @@ mod tests {
         assert_eq!(vaa.body.emitter_chain, 1);
         assert_eq!(
             vaa.body.emitter_address,
-            hex!("ec7372995d5cc8732397fb0ad35c0121e0eaa90d26f828a534cab54391b3a4f5")
+            FixedBytes(hex!(
+                "ec7372995d5cc8732397fb0ad35c0121e0eaa90d26f828a534cab54391b3a4f5"
+            ))
         );
         assert_eq!(vaa.body.sequence, U64::from(110277));
         assert_eq!(vaa.body.consistency_level, 32);
         assert_eq!(
             vaa.body.double_digest(),
-            hex!("c90519b2bdfacac401d2d2c15a329d4e33e8ca15862685f0220ddc6074d7def5")
+            FixedBytes(hex!(
+                "c90519b2bdfacac401d2d2c15a329d4e33e8ca15862685f0220ddc6074d7def5"
+            ))
         );
 
-        let msg = vaa.body.read_payload::<TokenBridgeMessage>().unwrap();
+        let msg = vaa.body.read_payload::<TokenBridgeMessage, 1>().unwrap();
         assert_eq!(msg.to_vec(), vaa.body.payload_bytes().unwrap());
 
         if let TokenBridgeMessage::Transfer(transfer) = msg {
             assert_eq!(transfer.norm_amount, EncodedAmount::from(4100000000u64));
             assert_eq!(
                 transfer.token_address,
-                hex!("069b8857feab8184fb687f634618c035dac439dc1aeb3b5598a0f00000000001")
+                FixedBytes(hex!(
+                    "069b8857feab8184fb687f634618c035dac439dc1aeb3b5598a0f00000000001"
+                ))
             );
             assert_eq!(transfer.token_chain, 1);
             assert_eq!(
                 transfer.recipient,
-                hex!("000000000000000000000000efd4aa8f954ebdea82b8757c029fc8475a45e9cd")
+                FixedBytes(hex!(
+                    "000000000000000000000000efd4aa8f954ebdea82b8757c029fc8475a45e9cd"
+                ))
             );
             assert_eq!(transfer.recipient_chain, 2);
             assert_eq!(transfer.norm_relayer_fee, EncodedAmount::ZERO);
@@ -167,4 +195,30 @@ mod tests {
             panic!("wrong message type");
         }
     }
+
+    #[test]
+    fn transfer_layout() {
+        let layout = wormhole_io::layout::payload_layout::<Transfer, 1>();
+        assert_eq!(layout.size(), Some(133));
+
+        let LayoutItem::Struct { fields, .. } = layout else {
+            panic!("expected a struct");
+        };
+        let offsets = fields
+            .iter()
+            .map(|field| (field.name, field.offset.unwrap()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            offsets,
+            [
+                ("type", 0),
+                ("norm_amount", 1),
+                ("token_address", 33),
+                ("token_chain", 65),
+                ("recipient", 67),
+                ("recipient_chain", 99),
+                ("norm_relayer_fee", 101),
+            ]
+        );
+    }
 }
@@ -1,10 +1,14 @@
-use alloy_primitives::FixedBytes;
+use wormhole_io::{
+    layout::{Field, LayoutItem},
+    Layout,
+};
 
-use crate::{EncodedAmount, Readable, TypePrefixedPayload, Writeable};
+use crate::{EncodedAmount, FixedBytes, Readable, TypePrefixedPayload, Writeable};
 
 use std::io;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct TransferWithMessage {
     pub norm_amount: EncodedAmount,
     pub token_address: FixedBytes<32>,
@@ -15,13 +19,11 @@ pub struct TransferWithMessage {
     pub payload: Vec<u8>,
 }
 
-impl TypePrefixedPayload for TransferWithMessage {
-    const TYPE: Option<u8> = Some(3);
+impl TypePrefixedPayload<1> for TransferWithMessage {
+    const TYPE: Option<[u8; 1]> = Some([3]);
 }
 
 impl Readable for TransferWithMessage {
-    const SIZE: Option<usize> = None;
-
     fn read<R>(reader: &mut R) -> io::Result<Self>
     where
         Self: Sized,
@@ -63,3 +65,20 @@ impl Writeable for TransferWithMessage {
         Ok(())
     }
 }
+
+impl Layout for TransferWithMessage {
+    fn layout() -> LayoutItem {
+        LayoutItem::structure(
+            "TransferWithMessage",
+            [
+                Field::new("norm_amount", EncodedAmount::layout()),
+                Field::new("token_address", LayoutItem::bytes(32)),
+                Field::new("token_chain", LayoutItem::uint(2)),
+                Field::new("redeemer", LayoutItem::bytes(32)),
+                Field::new("redeemer_chain", LayoutItem::uint(2)),
+                Field::new("sender", LayoutItem::bytes(32)),
+                Field::new("payload", LayoutItem::Remainder),
+            ],
+        )
+    }
+}
@@ -0,0 +1,297 @@
+//! Fixed-size byte types shaped like their `alloy-primitives` counterparts.
+//!
+//! alloy-primitives cannot be resolved next to solana-program 1.18 (their zeroize requirements
+//! conflict), so this crate defines the subset of [`FixedBytes`] and [`Address`] it uses. Field
+//! and constant types, `.0`, `From<[u8; N]>` and the hex formatting match alloy, so code
+//! written against the alloy-typed API keeps compiling. [`U64`](crate::U64) and
+//! [`U256`](crate::U256) are the `ruint` types alloy re-exports.
+
+use std::{
+    fmt,
+    ops::{Deref, DerefMut},
+    str::FromStr,
+};
+
+use crate::{Readable, Writeable};
+
+/// A byte array of fixed length `N`.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[repr(transparent)]
+pub struct FixedBytes<const N: usize>(pub [u8; N]);
+
+/// 32 bytes, e.g. an emitter address or a hash.
+pub type B256 = FixedBytes<32>;
+
+impl<const N: usize> FixedBytes<N> {
+    /// All zeros.
+    pub const ZERO: Self = Self([0; N]);
+
+    pub const fn new(bytes: [u8; N]) -> Self {
+        Self(bytes)
+    }
+
+    /// Copy `src` into a new value.
+    ///
+    /// # Panics
+    ///
+    /// If `src` is not `N` bytes long.
+    pub fn from_slice(src: &[u8]) -> Self {
+        Self(src.try_into().expect("slice length must be N"))
+    }
+
+    pub const fn as_slice(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl<const N: usize> Default for FixedBytes<N> {
+    fn default() -> Self {
+        Self::ZERO
+    }
+}
+
+impl<const N: usize> Deref for FixedBytes<N> {
+    type Target = [u8; N];
+
+    fn deref(&self) -> &[u8; N] {
+        &self.0
+    }
+}
+
+impl<const N: usize> DerefMut for FixedBytes<N> {
+    fn deref_mut(&mut self) -> &mut [u8; N] {
+        &mut self.0
+    }
+}
+
+impl<const N: usize> AsRef<[u8]> for FixedBytes<N> {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl<const N: usize> From<[u8; N]> for FixedBytes<N> {
+    fn from(bytes: [u8; N]) -> Self {
+        Self(bytes)
+    }
+}
+
+impl<const N: usize> From<FixedBytes<N>> for [u8; N] {
+    fn from(bytes: FixedBytes<N>) -> Self {
+        bytes.0
+    }
+}
+
+impl<const N: usize> fmt::Display for FixedBytes<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "0x{}", hex::encode(self.0))
+    }
+}
+
+impl<const N: usize> fmt::Debug for FixedBytes<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+/// Parses hex, with or without a `0x` prefix.
+impl<const N: usize> FromStr for FixedBytes<N> {
+    type Err = hex::FromHexError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut bytes = [0; N];
+        hex::decode_to_slice(s.strip_prefix("0x").unwrap_or(s), &mut bytes)?;
+        Ok(Self(bytes))
+    }
+}
+
+impl<const N: usize> Readable for FixedBytes<N> {
+    fn read<R>(reader: &mut R) -> std::io::Result<Self>
+    where
+        Self: Sized,
+        R: std::io::Read,
+    {
+        <[u8; N]>::read(reader).map(Self)
+    }
+}
+
+impl<const N: usize> Writeable for FixedBytes<N> {
+    fn write<W>(&self, writer: &mut W) -> std::io::Result<()>
+    where
+        W: std::io::Write,
+    {
+        self.0.write(writer)
+    }
+
+    fn written_size(&self) -> usize {
+        N
+    }
+}
+
+// available when `serde` feature is enabled
+#[cfg(feature = "serde")]
+impl<const N: usize> serde::Serialize for FixedBytes<N> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+// available when `serde` feature is enabled
+#[cfg(feature = "serde")]
+impl<'de, const N: usize> serde::Deserialize<'de> for FixedBytes<N> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = <std::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// A 20-byte Ethereum address, e.g. a guardian key.
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(transparent)]
+pub struct Address(pub FixedBytes<20>);
+
+impl Address {
+    /// All zeros.
+    pub const ZERO: Self = Self(FixedBytes::ZERO);
+
+    pub const fn new(bytes: [u8; 20]) -> Self {
+        Self(FixedBytes(bytes))
+    }
+
+    /// Copy `src` into a new address.
+    ///
+    /// # Panics
+    ///
+    /// If `src` is not 20 bytes long.
+    pub fn from_slice(src: &[u8]) -> Self {
+        Self(FixedBytes::from_slice(src))
+    }
+
+    /// The [EIP-55] mixed-case hex encoding, with a `0x` prefix.
+    ///
+    /// [EIP-55]: https://eips.ethereum.org/EIPS/eip-55
+    pub fn to_checksum(&self) -> String {
+        let lower = hex::encode(self.0);
+        let hash = crate::keccak256(&lower);
+
+        let mut out = String::with_capacity(42);
+        out.push_str("0x");
+        for (i, c) in lower.chars().enumerate() {
+            let nibble = hash[i / 2] >> (4 * (1 - i % 2)) & 0xf;
+            out.push(if nibble >= 8 {
+                c.to_ascii_uppercase()
+            } else {
+                c
+            });
+        }
+        out
+    }
+}
+
+impl Deref for Address {
+    type Target = FixedBytes<20>;
+
+    fn deref(&self) -> &FixedBytes<20> {
+        &self.0
+    }
+}
+
+impl DerefMut for Address {
+    fn deref_mut(&mut self) -> &mut FixedBytes<20> {
+        &mut self.0
+    }
+}
+
+impl AsRef<[u8]> for Address {
+    fn as_ref(&self) -> &[u8] {
+        self.0.as_ref()
+    }
+}
+
+impl From<[u8; 20]> for Address {
+    fn from(bytes: [u8; 20]) -> Self {
+        Self::new(bytes)
+    }
+}
+
+impl From<FixedBytes<20>> for Address {
+    fn from(bytes: FixedBytes<20>) -> Self {
+        Self(bytes)
+    }
+}
+
+impl From<Address> for [u8; 20] {
+    fn from(address: Address) -> Self {
+        address.0 .0
+    }
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_checksum())
+    }
+}
+
+impl fmt::Debug for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+/// Parses hex, with or without a `0x` prefix. The checksum is not verified.
+impl FromStr for Address {
+    type Err = hex::FromHexError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse().map(Self)
+    }
+}
+
+impl Readable for Address {
+    fn read<R>(reader: &mut R) -> std::io::Result<Self>
+    where
+        Self: Sized,
+        R: std::io::Read,
+    {
+        FixedBytes::read(reader).map(Self)
+    }
+}
+
+impl Writeable for Address {
+    fn write<W>(&self, writer: &mut W) -> std::io::Result<()>
+    where
+        W: std::io::Write,
+    {
+        self.0.write(writer)
+    }
+
+    fn written_size(&self) -> usize {
+        20
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use hex_literal::hex;
+
+    use super::*;
+
+    #[test]
+    fn hex_formatting() {
+        let bytes = FixedBytes(hex!("00ff10"));
+        assert_eq!(bytes.to_string(), "0x00ff10");
+        assert_eq!("0x00ff10".parse(), Ok(bytes));
+        assert_eq!("00ff10".parse(), Ok(bytes));
+        assert!("0x00ff".parse::<FixedBytes<3>>().is_err());
+
+        // From EIP-55.
+        let address = Address::new(hex!("5aaeb6053f3e94c9b9a09f33669435e7ef1beaed"));
+        assert_eq!(
+            address.to_string(),
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed"
+        );
+    }
+}
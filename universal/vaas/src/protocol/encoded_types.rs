@@ -1,6 +1,7 @@
-use alloy_primitives::{ruint::UintTryFrom, U256, U8};
+use ruint::{aliases::U8, UintTryFrom};
+use wormhole_io::{layout::LayoutItem, Layout};
 
-use crate::{Readable, Writeable};
+use crate::{Readable, Writeable, U256};
 
 use std::io;
 
@@ -14,7 +15,11 @@ const TEN: U256 = U256::from_limbs([10, 0, 0, 0]);
 /// some residual amount after the transfer). For inbound transfers, this amount
 /// will be denormalized (scaled by the same decimal difference).
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
-pub struct EncodedAmount(pub U256);
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct EncodedAmount(
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = crate::support::arbitrary::arbitrary_u256))]
+    pub U256,
+);
 
 impl EncodedAmount {
     pub const ZERO: Self = Self(U256::ZERO);
@@ -62,8 +67,6 @@ where
 }
 
 impl Readable for EncodedAmount {
-    const SIZE: Option<usize> = Some(32);
-
     fn read<R>(reader: &mut R) -> io::Result<Self>
     where
         Self: Sized,
@@ -75,7 +78,7 @@ impl Readable for EncodedAmount {
 
 impl Writeable for EncodedAmount {
     fn written_size(&self) -> usize {
-        <Self as Readable>::SIZE.unwrap()
+        32
     }
 
     fn write<W>(&self, writer: &mut W) -> io::Result<()>
@@ -91,6 +94,12 @@ fn pow10(power: u8) -> U256 {
     TEN.pow(U256::from(U8::from(power)))
 }
 
+impl Layout for EncodedAmount {
+    fn layout() -> LayoutItem {
+        LayoutItem::uint(32)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -1,10 +1,14 @@
-use alloy_primitives::FixedBytes;
+use wormhole_io::{
+    layout::{Field, LayoutItem},
+    Layout,
+};
 
-use crate::{Readable, Writeable};
+use crate::{FixedBytes, Readable, Writeable};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct GuardianSetSig {
     pub guardian_set_index: u8,
     #[cfg_attr(
@@ -22,11 +26,13 @@ impl GuardianSetSig {
     pub fn recovery_id(&self) -> u8 {
         self.signature[64]
     }
+
+    pub fn s(&self) -> [u8; 32] {
+        self.signature[32..64].try_into().unwrap()
+    }
 }
 
 impl Readable for GuardianSetSig {
-    const SIZE: Option<usize> = Some(66);
-
     fn read<R>(reader: &mut R) -> std::io::Result<Self>
     where
         Self: Sized,
@@ -53,6 +59,18 @@ impl Writeable for GuardianSetSig {
     }
 
     fn written_size(&self) -> usize {
-        <Self as Readable>::SIZE.unwrap()
+        66
+    }
+}
+
+impl Layout for GuardianSetSig {
+    fn layout() -> LayoutItem {
+        LayoutItem::structure(
+            "GuardianSetSig",
+            [
+                Field::new("guardian_set_index", LayoutItem::uint(1)),
+                Field::new("signature", LayoutItem::bytes(65)),
+            ],
+        )
     }
 }
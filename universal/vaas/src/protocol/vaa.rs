@@ -1,8 +1,13 @@
-use alloy_primitives::{FixedBytes, U64};
+use wormhole_io::{
+    layout::{Field, IntEncoding, LayoutItem},
+    Layout,
+};
 
+#[cfg(feature = "raw")]
+use crate::ParseError;
 use crate::{
     payloads::{self, PayloadKind},
-    utils, TypePrefixedPayload,
+    utils, FixedBytes, TypePrefixedPayload, U64,
 };
 pub use crate::{GuardianSetSig, Readable, Writeable};
 
@@ -18,8 +23,6 @@ pub struct Vaa {
 }
 
 impl Readable for Vaa {
-    const SIZE: Option<usize> = None;
-
     fn read<R>(reader: &mut R) -> io::Result<Self>
     where
         Self: Sized,
@@ -55,6 +58,22 @@ pub struct VaaHeader {
     pub signatures: Vec<GuardianSetSig>,
 }
 
+impl VaaHeader {
+    /// Check the version and signatures like
+    /// [`wormhole_raw_vaas::Header::validate`], which lists the rules.
+    // available when `raw` feature is enabled
+    #[cfg(feature = "raw")]
+    pub fn validate(&self, guardian_set_len: usize) -> Result<(), ParseError> {
+        wormhole_raw_vaas::utils::validate_header(
+            self.version,
+            self.signatures
+                .iter()
+                .map(|sig| (sig.guardian_set_index, sig.s())),
+            guardian_set_len,
+        )
+    }
+}
+
 impl Writeable for VaaHeader {
     fn write<W>(&self, writer: &mut W) -> io::Result<()>
     where
@@ -70,13 +89,11 @@ impl Writeable for VaaHeader {
     }
 
     fn written_size(&self) -> usize {
-        1 + 4 + (self.signatures.len() * <GuardianSetSig as Readable>::SIZE.unwrap())
+        1 + 4 + 1 + self.signatures.len() * 66
     }
 }
 
 impl Readable for VaaHeader {
-    const SIZE: Option<usize> = None;
-
     fn read<R>(reader: &mut R) -> io::Result<Self>
     where
         R: io::Read,
@@ -108,7 +125,15 @@ pub struct VaaBody {
     pub timestamp: u32,
     pub nonce: u32,
     pub emitter_chain: u16,
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::support::serde::fixed_bytes_as_array")
+    )]
     pub emitter_address: FixedBytes<32>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::support::serde::u64_as_string")
+    )]
     pub sequence: U64,
     pub consistency_level: u8,
 
@@ -128,7 +153,7 @@ impl Writeable for VaaBody {
         self.nonce.write(writer)?;
         self.emitter_chain.write(writer)?;
         self.emitter_address.write(writer)?;
-        self.sequence.into_limbs()[0].write(writer)?;
+        self.sequence.write(writer)?;
         self.consistency_level.write(writer)?;
         self.payload.write(writer)?;
         Ok(())
@@ -136,8 +161,6 @@ impl Writeable for VaaBody {
 }
 
 impl Readable for VaaBody {
-    const SIZE: Option<usize> = None;
-
     fn read<R>(reader: &mut R) -> io::Result<Self>
     where
         R: io::Read,
@@ -147,7 +170,7 @@ impl Readable for VaaBody {
             nonce: Readable::read(reader)?,
             emitter_chain: Readable::read(reader)?,
             emitter_address: Readable::read(reader)?,
-            sequence: U64::from_limbs([Readable::read(reader)?]),
+            sequence: Readable::read(reader)?,
             consistency_level: Readable::read(reader)?,
             payload: Readable::read(reader)?,
         })
@@ -163,7 +186,7 @@ impl VaaBody {
         }
     }
 
-    pub fn read_payload<P: TypePrefixedPayload>(&self) -> Option<P> {
+    pub fn read_payload<P: TypePrefixedPayload<N>, const N: usize>(&self) -> Option<P> {
         let mut p = self.payload_bytes()?;
         let deser = P::read_payload(&mut p).ok()?;
 
@@ -177,7 +200,9 @@ impl VaaBody {
 
     #[inline]
     pub fn digest(&self) -> FixedBytes<32> {
-        utils::keccak256(self.to_vec())
+        let mut buf = Vec::with_capacity(self.written_size());
+        self.write(&mut buf).expect("no alloc failure");
+        utils::keccak256(buf)
     }
 
     #[inline]
@@ -186,10 +211,177 @@ impl VaaBody {
     }
 
     #[cfg(feature = "serde")]
-    pub fn deser_payload<P: TypePrefixedPayload + serde::de::DeserializeOwned>(&self) -> Option<P> {
+    pub fn deser_payload<P, const N: usize>(&self) -> Option<P>
+    where
+        P: TypePrefixedPayload<N> + serde::de::DeserializeOwned,
+    {
         match &self.payload {
             PayloadKind::Json(value) => serde_json::from_value(value.clone()).ok(),
             _ => None,
         }
     }
 }
+
+impl Layout for Vaa {
+    fn layout() -> LayoutItem {
+        LayoutItem::structure(
+            "Vaa",
+            [
+                Field::new("header", VaaHeader::layout()),
+                Field::new("body", VaaBody::layout()),
+            ],
+        )
+    }
+}
+
+impl Layout for VaaHeader {
+    fn layout() -> LayoutItem {
+        LayoutItem::structure(
+            "VaaHeader",
+            [
+                Field::new("version", LayoutItem::uint(1)),
+                Field::new("guardian_set_index", LayoutItem::uint(4)),
+                Field::new(
+                    "signatures",
+                    LayoutItem::Vec {
+                        len: IntEncoding::big(1),
+                        item: Box::new(GuardianSetSig::layout()),
+                    },
+                ),
+            ],
+        )
+    }
+}
+
+impl Layout for VaaBody {
+    fn layout() -> LayoutItem {
+        LayoutItem::structure(
+            "VaaBody",
+            [
+                Field::new("timestamp", LayoutItem::uint(4)),
+                Field::new("nonce", LayoutItem::uint(4)),
+                Field::new("emitter_chain", LayoutItem::uint(2)),
+                Field::new("emitter_address", LayoutItem::bytes(32)),
+                Field::new("sequence", LayoutItem::uint(8)),
+                Field::new("consistency_level", LayoutItem::uint(1)),
+                Field::new("payload", LayoutItem::Remainder),
+            ],
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use hex_literal::hex;
+
+    use super::*;
+
+    #[derive(
+        Debug, Clone, PartialEq, Eq, Readable, Writeable, wormhole_io::TypePrefixedPayload,
+    )]
+    #[payload(type = b"\x99NTT")]
+    struct NativeTokenTransfer {
+        amount: u64,
+        to_chain: u16,
+    }
+
+    fn body_with(payload: Vec<u8>) -> VaaBody {
+        VaaBody {
+            timestamp: 1,
+            nonce: 2,
+            emitter_chain: 3,
+            emitter_address: FixedBytes([4; 32]),
+            sequence: U64::from(5),
+            consistency_level: 6,
+            payload: PayloadKind::Binary(payload),
+        }
+    }
+
+    #[test]
+    fn read_multi_byte_prefixed_payload() {
+        let body = body_with(hex!("994e5454000000000000002a0002").to_vec());
+        assert_eq!(
+            body.read_payload::<NativeTokenTransfer, 4>(),
+            Some(NativeTokenTransfer {
+                amount: 42,
+                to_chain: 2,
+            })
+        );
+
+        // A payload of another type, or with trailing bytes, is not read.
+        let other = body_with(hex!("994e5455000000000000002a0002").to_vec());
+        assert_eq!(other.read_payload::<NativeTokenTransfer, 4>(), None);
+        let trailing = body_with(hex!("994e5454000000000000002a000200").to_vec());
+        assert_eq!(trailing.read_payload::<NativeTokenTransfer, 4>(), None);
+    }
+
+    #[test]
+    fn body_roundtrip() {
+        let body = body_with(hex!("994e5454000000000000002a0002").to_vec());
+        let mut encoded = Vec::new();
+        body.write(&mut encoded).unwrap();
+
+        assert_eq!(encoded.len(), body.written_size());
+        assert_eq!(VaaBody::read(&mut encoded.as_slice()).unwrap(), body);
+    }
+
+    #[cfg(feature = "raw")]
+    #[test]
+    fn header_roundtrip() {
+        let header = VaaHeader {
+            version: 1,
+            guardian_set_index: 4,
+            signatures: vec![GuardianSetSig {
+                guardian_set_index: 0,
+                signature: FixedBytes([1; 65]),
+            }],
+        };
+        let mut encoded = Vec::new();
+        header.write(&mut encoded).unwrap();
+
+        assert_eq!(encoded.len(), header.written_size());
+        assert_eq!(VaaHeader::read(&mut encoded.as_slice()).unwrap(), header);
+    }
+
+    #[cfg(feature = "raw")]
+    #[test]
+    fn validate_header() {
+        let sig = |guardian_set_index, s: [u8; 32]| {
+            let mut signature = [0; 65];
+            signature[32..64].copy_from_slice(&s);
+            GuardianSetSig {
+                guardian_set_index,
+                signature: signature.into(),
+            }
+        };
+        let header = |signatures| VaaHeader {
+            version: 1,
+            guardian_set_index: 4,
+            signatures,
+        };
+
+        assert_eq!(
+            header(vec![sig(0, [0x11; 32]), sig(5, [0x11; 32])]).validate(19),
+            Ok(())
+        );
+        assert_eq!(
+            header(vec![sig(5, [0x11; 32]), sig(0, [0x11; 32])]).validate(19),
+            Err(ParseError::UnsortedSignatures {
+                previous: 5,
+                index: 0
+            })
+        );
+        assert_eq!(
+            header(vec![sig(0, [0xff; 32])]).validate(19),
+            Err(ParseError::MalleableSignature { index: 0 })
+        );
+        assert_eq!(
+            VaaHeader {
+                version: 2,
+                ..header(vec![])
+            }
+            .validate(19),
+            Err(ParseError::UnsupportedVersion { version: 2 })
+        );
+    }
+}
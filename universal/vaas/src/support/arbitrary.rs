@@ -0,0 +1,149 @@
+#![cfg(feature = "arbitrary")]
+//! [`arbitrary::Arbitrary`] for types whose encoding constrains their fields (except those with
+//! private fields, which are implemented next to the type), and
+//! [`proptest::arbitrary::Arbitrary`] for every VAA and payload type. Generated values always
+//! encode, and decode back to themselves.
+
+use arbitrary::{Arbitrary, Result, Unstructured};
+
+use crate::{
+    payloads::{
+        gov::{self, core_bridge, token_bridge as token_bridge_gov},
+        token_bridge, Message,
+    },
+    EncodedAmount, GuardianSetSig, PayloadKind, Vaa, VaaBody, VaaHeader, U256, U64,
+};
+
+/// Generate at most `max` elements.
+pub(crate) fn arbitrary_vec<'a, T: Arbitrary<'a>>(
+    u: &mut Unstructured<'a>,
+    max: usize,
+) -> Result<Vec<T>> {
+    let len = u.arbitrary_len::<T>()?.min(max);
+    (0..len).map(|_| T::arbitrary(u)).collect()
+}
+
+/// [`U256`] only implements [`Arbitrary`] with ruint's `arbitrary` feature, which this crate does
+/// not enable.
+pub(crate) fn arbitrary_u256(u: &mut Unstructured) -> Result<U256> {
+    u.arbitrary().map(U256::from_limbs)
+}
+
+impl<'a> Arbitrary<'a> for Vaa {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Self {
+            header: u.arbitrary()?,
+            body: u.arbitrary()?,
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for VaaHeader {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Self {
+            version: u.arbitrary()?,
+            guardian_set_index: u.arbitrary()?,
+            // The signature count is encoded as a u8.
+            signatures: arbitrary_vec(u, u8::MAX.into())?,
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for VaaBody {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Self {
+            timestamp: u.arbitrary()?,
+            nonce: u.arbitrary()?,
+            emitter_chain: u.arbitrary()?,
+            emitter_address: u.arbitrary()?,
+            sequence: U64::from(u.arbitrary::<u64>()?),
+            consistency_level: u.arbitrary()?,
+            payload: u.arbitrary()?,
+        })
+    }
+}
+
+/// Only binary payloads, since JSON payloads cannot be encoded.
+impl<'a> Arbitrary<'a> for PayloadKind {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        u.arbitrary().map(Self::Binary)
+    }
+}
+
+impl<'a> Arbitrary<'a> for Message {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        // Each of the byte strings is prefixed with its length as a u16.
+        Ok(Self {
+            version: u.arbitrary()?,
+            message_ty: u.arbitrary()?,
+            index: u.arbitrary()?,
+            target_chain: u.arbitrary()?,
+            target: arbitrary_vec(u, u16::MAX.into())?,
+            sender: arbitrary_vec(u, u16::MAX.into())?,
+            body: arbitrary_vec(u, u16::MAX.into())?,
+        })
+    }
+}
+
+macro_rules! impl_proptest_arbitrary {
+    ([$($generics:tt)*] $type:ty) => {
+        impl<$($generics)*> proptest::arbitrary::Arbitrary for $type
+        where
+            Self: for<'a> Arbitrary<'a> + 'static,
+        {
+            type Parameters = ();
+            type Strategy = proptest::strategy::BoxedStrategy<Self>;
+
+            fn arbitrary_with(_: ()) -> Self::Strategy {
+                wormhole_io::testing::arbitrary_strategy()
+            }
+        }
+    };
+}
+
+impl_proptest_arbitrary!([] Vaa);
+impl_proptest_arbitrary!([] VaaHeader);
+impl_proptest_arbitrary!([] VaaBody);
+impl_proptest_arbitrary!([] GuardianSetSig);
+impl_proptest_arbitrary!([] PayloadKind);
+impl_proptest_arbitrary!([] EncodedAmount);
+impl_proptest_arbitrary!([] Message);
+
+impl_proptest_arbitrary!([] token_bridge::Transfer);
+impl_proptest_arbitrary!([] token_bridge::Attestation);
+impl_proptest_arbitrary!([] token_bridge::TransferWithMessage);
+impl_proptest_arbitrary!([] token_bridge::TokenBridgeMessage);
+
+impl_proptest_arbitrary!([] gov::GovernanceHeader);
+impl_proptest_arbitrary!([P: core::fmt::Debug] gov::GovernanceMessage<P>);
+impl_proptest_arbitrary!([] core_bridge::Decree);
+impl_proptest_arbitrary!([] core_bridge::ContractUpgrade);
+impl_proptest_arbitrary!([] core_bridge::GuardianSetUpdate);
+impl_proptest_arbitrary!([] core_bridge::SetMessageFee);
+impl_proptest_arbitrary!([] core_bridge::TransferFees);
+impl_proptest_arbitrary!([] core_bridge::RecoverChainId);
+impl_proptest_arbitrary!([] token_bridge_gov::Decree);
+impl_proptest_arbitrary!([] token_bridge_gov::ContractUpgrade);
+impl_proptest_arbitrary!([] token_bridge_gov::RegisterChain);
+impl_proptest_arbitrary!([] token_bridge_gov::RecoverChainId);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use wormhole_io::testing::assert_roundtrip;
+
+    #[test]
+    fn generated_vaas_roundtrip() {
+        assert_roundtrip::<Vaa>();
+        assert_roundtrip::<VaaHeader>();
+        assert_roundtrip::<GuardianSetSig>();
+    }
+
+    #[test]
+    fn generated_payloads_roundtrip() {
+        assert_roundtrip::<Message>();
+        assert_roundtrip::<token_bridge::TokenBridgeMessage>();
+        assert_roundtrip::<gov::GovernanceMessage<core_bridge::Decree>>();
+        assert_roundtrip::<gov::GovernanceMessage<token_bridge_gov::Decree>>();
+    }
+}
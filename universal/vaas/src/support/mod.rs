@@ -1 +1,4 @@
+pub(crate) mod arbitrary;
+#[cfg(feature = "raw")]
+pub(crate) mod raw;
 pub(crate) mod serde;
@@ -0,0 +1,512 @@
+//! Conversions from the zero-copy views of [`wormhole_raw_vaas`] into the owned types of this
+//! crate (except those with private fields, which are implemented next to the type), and raw
+//! views of owned types kept with their encoding in [`Encoded`].
+//!
+//! Raw views are validated when parsed, so the conversions cannot fail.
+
+use std::ops::Deref;
+
+use wormhole_raw_vaas as raw;
+
+use crate::{
+    payloads::{
+        gov::{core_bridge, token_bridge as token_bridge_gov, GovernanceHeader, GovernanceMessage},
+        token_bridge,
+    },
+    EncodedAmount, GuardianSetSig, ParseError, PayloadKind, Vaa, VaaBody, VaaHeader, Writeable,
+    U256, U64,
+};
+
+/// Convert a raw view into the owned type of this crate, the same as [`From`].
+///
+pub trait RawToOwned {
+    type Owned;
+
+    fn into_owned(self) -> Self::Owned;
+}
+
+macro_rules! impl_raw_to_owned {
+    ($($raw:ty => $owned:ty),* $(,)?) => {
+        $(
+            impl<'a> RawToOwned for $raw {
+                type Owned = $owned;
+
+                fn into_owned(self) -> $owned {
+                    self.into()
+                }
+            }
+        )*
+    };
+}
+
+impl_raw_to_owned!(
+    raw::Vaa<'a> => Vaa,
+    raw::Header<'a> => VaaHeader,
+    raw::Body<'a> => VaaBody,
+    raw::Payload<'a> => PayloadKind,
+    raw::GuardianSetSig<'a> => GuardianSetSig,
+    raw::GovernanceHeader<'a> => GovernanceHeader,
+    raw::core::CoreBridgeGovPayload<'a> => GovernanceMessage<core_bridge::Decree>,
+    raw::core::CoreBridgeDecree<'a> => core_bridge::Decree,
+    raw::core::ContractUpgrade<'a> => core_bridge::ContractUpgrade,
+    raw::core::GuardianSetUpdate<'a> => core_bridge::GuardianSetUpdate,
+    raw::core::SetMessageFee<'a> => core_bridge::SetMessageFee,
+    raw::core::TransferFees<'a> => core_bridge::TransferFees,
+    raw::core::RecoverChainId<'a> => core_bridge::RecoverChainId,
+    raw::token_bridge::TokenBridgeGovPayload<'a> => GovernanceMessage<token_bridge_gov::Decree>,
+    raw::token_bridge::TokenBridgeDecree<'a> => token_bridge_gov::Decree,
+    raw::token_bridge::RegisterChain<'a> => token_bridge_gov::RegisterChain,
+    raw::token_bridge::ContractUpgrade<'a> => token_bridge_gov::ContractUpgrade,
+    raw::token_bridge::RecoverChainId<'a> => token_bridge_gov::RecoverChainId,
+    raw::token_bridge::TokenBridgePayload<'a> => token_bridge::TokenBridgeMessage,
+    raw::token_bridge::TokenBridgeMessage<'a> => token_bridge::TokenBridgeMessage,
+    raw::token_bridge::Transfer<'a> => token_bridge::Transfer,
+    raw::token_bridge::Attestation<'a> => token_bridge::Attestation,
+    raw::token_bridge::TransferWithMessage<'a> => token_bridge::TransferWithMessage,
+);
+
+fn u256(bytes: [u8; 32]) -> U256 {
+    U256::from_be_bytes(bytes)
+}
+
+impl From<raw::GuardianSetSig<'_>> for GuardianSetSig {
+    fn from(raw: raw::GuardianSetSig<'_>) -> Self {
+        Self {
+            guardian_set_index: raw.guardian_index(),
+            signature: raw.signature().into(),
+        }
+    }
+}
+
+impl From<raw::Header<'_>> for VaaHeader {
+    fn from(raw: raw::Header<'_>) -> Self {
+        Self {
+            version: raw.version(),
+            guardian_set_index: raw.guardian_set_index(),
+            signatures: raw.signatures().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<raw::Payload<'_>> for PayloadKind {
+    fn from(raw: raw::Payload<'_>) -> Self {
+        Self::Binary(raw.as_ref().to_vec())
+    }
+}
+
+impl From<raw::Body<'_>> for VaaBody {
+    fn from(raw: raw::Body<'_>) -> Self {
+        Self {
+            timestamp: raw.timestamp(),
+            nonce: raw.nonce(),
+            emitter_chain: raw.emitter_chain(),
+            emitter_address: raw.emitter_address().into(),
+            sequence: U64::from(raw.sequence()),
+            consistency_level: raw.consistency_level(),
+            payload: raw.payload().into(),
+        }
+    }
+}
+
+impl From<raw::Vaa<'_>> for Vaa {
+    fn from(raw: raw::Vaa<'_>) -> Self {
+        Self {
+            header: raw.header().into(),
+            body: raw.body().into(),
+        }
+    }
+}
+
+impl From<raw::GovernanceHeader<'_>> for GovernanceHeader {
+    fn from(raw: raw::GovernanceHeader<'_>) -> Self {
+        Self {
+            module: raw.module().into(),
+        }
+    }
+}
+
+impl From<raw::token_bridge::Transfer<'_>> for token_bridge::Transfer {
+    fn from(raw: raw::token_bridge::Transfer<'_>) -> Self {
+        Self {
+            norm_amount: EncodedAmount(u256(raw.amount())),
+            token_address: raw.token_address().into(),
+            token_chain: raw.token_chain(),
+            recipient: raw.recipient().into(),
+            recipient_chain: raw.recipient_chain(),
+            norm_relayer_fee: EncodedAmount(u256(raw.relayer_fee())),
+        }
+    }
+}
+
+impl From<raw::token_bridge::Attestation<'_>> for token_bridge::Attestation {
+    fn from(raw: raw::token_bridge::Attestation<'_>) -> Self {
+        Self {
+            token_address: raw.token_address().into(),
+            token_chain: raw.token_chain(),
+            decimals: raw.decimals(),
+            symbol: raw.symbol_raw().into(),
+            name: raw.name_raw().into(),
+        }
+    }
+}
+
+impl From<raw::token_bridge::TransferWithMessage<'_>> for token_bridge::TransferWithMessage {
+    fn from(raw: raw::token_bridge::TransferWithMessage<'_>) -> Self {
+        Self {
+            norm_amount: EncodedAmount(u256(raw.amount())),
+            token_address: raw.token_address().into(),
+            token_chain: raw.token_chain(),
+            redeemer: raw.redeemer().into(),
+            redeemer_chain: raw.redeemer_chain(),
+            sender: raw.sender().into(),
+            payload: raw.payload().as_ref().to_vec(),
+        }
+    }
+}
+
+impl From<raw::token_bridge::TokenBridgeMessage<'_>> for token_bridge::TokenBridgeMessage {
+    fn from(raw: raw::token_bridge::TokenBridgeMessage<'_>) -> Self {
+        use raw::token_bridge::TokenBridgeMessage as Raw;
+
+        match raw {
+            Raw::Transfer(inner) => Self::Transfer(inner.into()),
+            Raw::Attestation(inner) => Self::Attestation(inner.into()),
+            Raw::TransferWithMessage(inner) => Self::TransferWithMessage(inner.into()),
+        }
+    }
+}
+
+impl From<raw::token_bridge::TokenBridgePayload<'_>> for token_bridge::TokenBridgeMessage {
+    fn from(raw: raw::token_bridge::TokenBridgePayload<'_>) -> Self {
+        raw.message().into()
+    }
+}
+
+impl From<raw::core::ContractUpgrade<'_>> for core_bridge::ContractUpgrade {
+    fn from(raw: raw::core::ContractUpgrade<'_>) -> Self {
+        Self {
+            chain: raw.chain(),
+            implementation: raw.implementation().into(),
+        }
+    }
+}
+
+impl From<raw::core::SetMessageFee<'_>> for core_bridge::SetMessageFee {
+    fn from(raw: raw::core::SetMessageFee<'_>) -> Self {
+        Self {
+            chain: raw.chain(),
+            fee: u256(raw.fee()),
+        }
+    }
+}
+
+impl From<raw::core::TransferFees<'_>> for core_bridge::TransferFees {
+    fn from(raw: raw::core::TransferFees<'_>) -> Self {
+        Self {
+            chain: raw.chain(),
+            amount: u256(raw.amount()),
+            recipient: raw.recipient().into(),
+        }
+    }
+}
+
+impl From<raw::core::RecoverChainId<'_>> for core_bridge::RecoverChainId {
+    fn from(raw: raw::core::RecoverChainId<'_>) -> Self {
+        Self {
+            recovered_chain: raw.recovered_chain(),
+            evm_chain_id: u256(raw.evm_chain_id()),
+            new_chain: raw.new_chain(),
+        }
+    }
+}
+
+impl From<raw::core::CoreBridgeDecree<'_>> for core_bridge::Decree {
+    fn from(raw: raw::core::CoreBridgeDecree<'_>) -> Self {
+        use raw::core::CoreBridgeDecree as Raw;
+
+        match raw {
+            Raw::ContractUpgrade(inner) => Self::ContractUpgrade(inner.into()),
+            Raw::GuardianSetUpdate(inner) => Self::GuardianSetUpdate(inner.into()),
+            Raw::SetMessageFee(inner) => Self::SetMessageFee(inner.into()),
+            Raw::TransferFees(inner) => Self::TransferFees(inner.into()),
+            Raw::RecoverChainId(inner) => Self::RecoverChainId(inner.into()),
+        }
+    }
+}
+
+impl From<raw::core::CoreBridgeGovPayload<'_>> for GovernanceMessage<core_bridge::Decree> {
+    fn from(raw: raw::core::CoreBridgeGovPayload<'_>) -> Self {
+        Self {
+            // Parsing checked the module.
+            header: GovernanceHeader {
+                module: core_bridge::GOVERNANCE_MODULE,
+            },
+            decree: raw.decree().into(),
+        }
+    }
+}
+
+impl From<raw::token_bridge::ContractUpgrade<'_>> for token_bridge_gov::ContractUpgrade {
+    fn from(raw: raw::token_bridge::ContractUpgrade<'_>) -> Self {
+        Self {
+            chain: raw.chain(),
+            implementation: raw.implementation().into(),
+        }
+    }
+}
+
+impl From<raw::token_bridge::RecoverChainId<'_>> for token_bridge_gov::RecoverChainId {
+    fn from(raw: raw::token_bridge::RecoverChainId<'_>) -> Self {
+        Self {
+            recovered_chain: raw.recovered_chain(),
+            evm_chain_id: u256(raw.evm_chain_id()),
+            new_chain: raw.new_chain(),
+        }
+    }
+}
+
+impl From<raw::token_bridge::TokenBridgeDecree<'_>> for token_bridge_gov::Decree {
+    fn from(raw: raw::token_bridge::TokenBridgeDecree<'_>) -> Self {
+        use raw::token_bridge::TokenBridgeDecree as Raw;
+
+        match raw {
+            Raw::RegisterChain(inner) => Self::RegisterChain(inner.into()),
+            Raw::ContractUpgrade(inner) => Self::ContractUpgrade(inner.into()),
+            Raw::RecoverChainId(inner) => Self::RecoverChainId(inner.into()),
+        }
+    }
+}
+
+impl From<raw::token_bridge::TokenBridgeGovPayload<'_>>
+    for GovernanceMessage<token_bridge_gov::Decree>
+{
+    fn from(raw: raw::token_bridge::TokenBridgeGovPayload<'_>) -> Self {
+        Self {
+            // Parsing checked the module.
+            header: GovernanceHeader {
+                module: token_bridge_gov::GOVERNANCE_MODULE,
+            },
+            decree: raw.decree().into(),
+        }
+    }
+}
+
+/// An owned value together with its encoding, so that a raw view of it can be borrowed with
+/// `as_raw` without encoding again.
+///
+/// The value cannot be changed in place, which keeps the two in sync. Use
+/// [`into_inner`](Self::into_inner) to edit it, then convert it back.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Encoded<T> {
+    value: T,
+    bytes: Vec<u8>,
+}
+
+impl<T> Encoded<T> {
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T> Deref for Encoded<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T> AsRef<[u8]> for Encoded<T> {
+    fn as_ref(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+macro_rules! impl_encoded {
+    ($($owned:ty => $raw:ident $(:: $path:ident)*),* $(,)?) => {
+        $(
+            impl Encoded<$owned> {
+                /// Borrow the encoding as a raw view.
+                pub fn as_raw(&self) -> raw::$raw$(::$path)*<'_> {
+                    // The bytes were parsed as this view when `self` was built.
+                    raw::$raw$(::$path)*::parse(&self.bytes).expect("encoding already validated")
+                }
+            }
+
+            /// Encodes the value once. Fails if the encoding is not a valid raw view, e.g. a
+            /// header with more signatures than fit in the count byte.
+            impl TryFrom<$owned> for Encoded<$owned> {
+                type Error = ParseError;
+
+                fn try_from(value: $owned) -> Result<Self, ParseError> {
+                    let mut bytes = Vec::with_capacity(value.written_size());
+                    value.write(&mut bytes).expect("no alloc failure");
+                    raw::$raw$(::$path)*::parse(&bytes)?;
+                    Ok(Self { value, bytes })
+                }
+            }
+
+            impl From<raw::$raw$(::$path)*<'_>> for Encoded<$owned> {
+                fn from(raw: raw::$raw$(::$path)*<'_>) -> Self {
+                    Self {
+                        value: raw.into(),
+                        bytes: raw.as_ref().to_vec(),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_encoded!(
+    Vaa => Vaa,
+    VaaHeader => Header,
+    VaaBody => Body,
+    GovernanceMessage<core_bridge::Decree> => core::CoreBridgeGovPayload,
+    GovernanceMessage<token_bridge_gov::Decree> => token_bridge::TokenBridgeGovPayload,
+);
+
+impl PayloadKind {
+    /// Borrow a binary payload as a raw view, e.g. to parse it with
+    /// [`Payload::parse_as`](raw::Payload::parse_as). JSON payloads have no bytes to borrow.
+    pub fn as_raw(&self) -> Option<raw::Payload<'_>> {
+        #[allow(unreachable_patterns)]
+        match self {
+            Self::Binary(buf) => Some(raw::Payload::parse(buf)),
+            _ => None,
+        }
+    }
+}
+
+impl VaaBody {
+    /// Borrow the payload as a raw view. See [`PayloadKind::as_raw`].
+    pub fn raw_payload(&self) -> Option<raw::Payload<'_>> {
+        self.payload.as_raw()
+    }
+}
+
+impl token_bridge::TransferWithMessage {
+    /// Borrow the payload as a raw view.
+    pub fn raw_payload(&self) -> raw::Payload<'_> {
+        raw::Payload::parse(&self.payload)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use hex_literal::hex;
+    use wormhole_io::TypePrefixedPayload;
+
+    use super::*;
+    use crate::FixedBytes;
+
+    // https://github.com/wormhole-foundation/wormhole/blob/b09a644dac97fa8e037a16765728217ff3a1d057/clients/js/parse_tests/token-bridge-transfer-1.expected
+    const TRANSFER: &[u8] = &hex!("010000000001007d204ad9447c4dfd6be62406e7f5a05eec96300da4048e70ff530cfb52aec44807e98194990710ff166eb1b2eac942d38bc1cd6018f93662a6578d985e87c8d0016221346b0000b8bd0001c69a1b1a65dd336bf1df6a77afb501fc25db7fc0938cb08595a9ef473265cb4f0000000000000003200100000000000000000000000000000000000000000000000000000002540be400165809739240a0ac03b98440fe8985548e3aa683cd0d4d9df5b5659669faa3010001000000000000000000000000c10820983f33456ce7beb3a046f5a83fa34f027d00020000000000000000000000000000000000000000000000000000000000000000");
+
+    #[test]
+    fn vaa() {
+        let raw = raw::Vaa::parse(TRANSFER).unwrap();
+        let owned: Vaa = raw.into_owned();
+        assert_eq!(
+            owned,
+            <Vaa as crate::Readable>::read(&mut &TRANSFER[..]).unwrap()
+        );
+        assert_eq!(raw.header().into_owned(), owned.header);
+        assert_eq!(raw.body().into_owned(), owned.body);
+
+        let encoded = Encoded::from(raw);
+        assert_eq!(encoded.as_raw(), raw);
+        assert_eq!(encoded.value(), &owned);
+        assert_eq!(Encoded::try_from(owned.clone()), Ok(encoded));
+        let header = Encoded::try_from(owned.header.clone()).unwrap();
+        assert_eq!(header.as_raw(), raw.header());
+        let body = Encoded::try_from(owned.body.clone()).unwrap();
+        assert_eq!(body.as_raw(), raw.body());
+
+        let message = owned
+            .body
+            .raw_payload()
+            .unwrap()
+            .parse_as::<raw::token_bridge::TokenBridgeMessage>()
+            .unwrap();
+        let transfer = token_bridge::TokenBridgeMessage::from(message);
+        assert_eq!(
+            transfer,
+            token_bridge::TokenBridgeMessage::read_slice(raw.payload().as_ref()).unwrap()
+        );
+    }
+
+    #[test]
+    fn token_bridge_messages() {
+        let messages = [
+            token_bridge::TokenBridgeMessage::Attestation(token_bridge::Attestation {
+                token_address: FixedBytes([1; 32]),
+                token_chain: 2,
+                decimals: 18,
+                symbol: FixedBytes(
+                    *b"WETH\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0",
+                ),
+                name: FixedBytes([0x41; 32]),
+            }),
+            token_bridge::TokenBridgeMessage::TransferWithMessage(
+                token_bridge::TransferWithMessage {
+                    norm_amount: EncodedAmount(U256::from(1234)),
+                    token_address: FixedBytes([1; 32]),
+                    token_chain: 2,
+                    redeemer: FixedBytes([3; 32]),
+                    redeemer_chain: 4,
+                    sender: FixedBytes([5; 32]),
+                    payload: b"hello".to_vec(),
+                },
+            ),
+        ];
+
+        for message in messages {
+            let encoded = message.to_vec();
+            let raw = raw::token_bridge::TokenBridgePayload::parse(&encoded).unwrap();
+            assert_eq!(raw.into_owned(), message);
+        }
+    }
+
+    #[test]
+    fn governance() {
+        let core = GovernanceMessage {
+            header: GovernanceHeader {
+                module: core_bridge::GOVERNANCE_MODULE,
+            },
+            decree: core_bridge::Decree::GuardianSetUpdate(
+                <core_bridge::GuardianSetUpdate as crate::Readable>::read(
+                    &mut &hex!("00000000000402befa429d57cd18b7f8a4d91a2da9ab4af05d0fbe88d7d8b32a9105d228100e72dffe2fae0705d31c")[..],
+                )
+                .unwrap(),
+            ),
+        };
+        let encoded = core.to_vec();
+        let raw = raw::core::CoreBridgeGovPayload::parse(&encoded).unwrap();
+        assert_eq!(raw.into_owned(), core);
+        assert_eq!(Encoded::try_from(core).unwrap().as_raw(), raw);
+
+        let token_bridge = GovernanceMessage {
+            header: GovernanceHeader {
+                module: token_bridge_gov::GOVERNANCE_MODULE,
+            },
+            decree: token_bridge_gov::Decree::RecoverChainId(token_bridge_gov::RecoverChainId {
+                recovered_chain: 2,
+                evm_chain_id: U256::from(5),
+                new_chain: 10002,
+            }),
+        };
+        let encoded = token_bridge.to_vec();
+        let raw = raw::token_bridge::TokenBridgeGovPayload::parse(&encoded).unwrap();
+        assert_eq!(raw.into_owned(), token_bridge);
+        assert_eq!(Encoded::try_from(token_bridge).unwrap().as_raw(), raw);
+    }
+}
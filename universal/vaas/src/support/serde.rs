@@ -3,7 +3,7 @@ use serde::{de, de::SeqAccess, Deserializer, Serialize, Serializer};
 
 pub(crate) mod fixed_bytes_as_array {
     use super::*;
-    use alloy_primitives::FixedBytes;
+    use crate::FixedBytes;
 
     pub fn serialize<S, const N: usize>(
         bytes: &FixedBytes<N>,
@@ -65,6 +65,50 @@ pub(crate) mod fixed_bytes_as_array {
     }
 }
 
+pub(crate) mod u64_as_string {
+    use super::*;
+    use crate::U64;
+
+    pub fn serialize<S>(value: &U64, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        value.to_string().serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<U64, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        pub struct U64Visitor;
+
+        impl<'de> de::Visitor<'de> for U64Visitor {
+            type Value = U64;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a u64 represented as a number, or as a decimal string")
+            }
+
+            fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(U64::from(value))
+            }
+
+            fn visit_str<E>(self, s: &str) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                s.parse::<u64>()
+                    .map(U64::from)
+                    .map_err(|_| de::Error::invalid_value(de::Unexpected::Str(s), &self))
+            }
+        }
+        deserializer.deserialize_any(U64Visitor)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use crate::PayloadKind;
@@ -92,6 +136,7 @@ mod test {
         let vaa: Vaa = serde_json::from_value(json).unwrap();
 
         assert_eq!(vaa.header.version, 1);
+        assert_eq!(vaa.body.sequence, crate::U64::from(3));
         assert_eq!(vaa.body.payload, PayloadKind::Binary(vec![0, 1, 2]))
     }
 }
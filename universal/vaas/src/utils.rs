@@ -1,9 +1,11 @@
-use alloy_primitives::FixedBytes;
+use sha3::{Digest, Keccak256};
 
-/// Simple keccak256 hash with configurable backend.
+use crate::FixedBytes;
+
+/// Simple keccak256 hash.
 #[inline]
 pub fn keccak256(buf: impl AsRef<[u8]>) -> FixedBytes<32> {
-    alloy_primitives::keccak256(buf)
+    FixedBytes(Keccak256::digest(buf).into())
 }
 
 /// Return the number of guardians to reach quorum.